@@ -1,3 +1,5 @@
+use crate::utils;
+
 use std::borrow::Borrow;
 use std::hash::{Hash, Hasher};
 use std::io::{Result, Write};
@@ -38,7 +40,7 @@ impl Borrow<str> for Attr {
 }
 
 impl Attr {
-    pub(crate) fn new(key: String, value: String, is_html: bool) -> Attr {
+    pub fn new(key: String, value: String, is_html: bool) -> Attr {
         Attr { key, value, is_html }
     }
 
@@ -55,20 +57,30 @@ impl Attr {
     }
 
     /// Write the attribute to dot format
-    pub fn to_dot<W: ?Sized>(&self, indent: usize, writer: &mut W) -> Result<()>
+    pub fn to_dot<W>(&self, indent: usize, writer: &mut W) -> Result<()>
     where
-        W: Write,
+        W: Write + ?Sized,
     {
-        let key = &self.key;
+        (0..=indent).try_for_each(|_| write!(writer, "\t"))?;
+        self.write_value(writer)?;
+        writeln!(writer)?;
+
+        Ok(())
+    }
+
+    /// Write the attribute as `key="value"`, or `key=<value>` if it is html-like,
+    /// quoting the key if needed and escaping quotes in the value
+    pub(crate) fn write_value<W>(&self, writer: &mut W) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        let key = utils::pretty_id(&self.key);
         let value = &self.value;
 
-        (0..=indent).try_for_each(|_| write!(writer, "\t"))?;
         if self.is_html {
-            writeln!(writer, "{key}=<{value}>")?;
+            write!(writer, "{key}=<{value}>")
         } else {
-            writeln!(writer, "{key}=\"{value}\"")?;
+            write!(writer, "{key}={}", utils::quote(value))
         }
-
-        Ok(())
     }
 }
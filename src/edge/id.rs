@@ -1,5 +1,6 @@
 use crate::{node::NodeId, utils};
 
+use std::fmt;
 use std::io::{Result, Write};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -40,23 +41,49 @@ impl EdgeId {
         &self.headport
     }
 
+    /// The id of the edge in the opposite direction, with its ends and ports swapped
+    pub(crate) fn reversed(self) -> EdgeId {
+        EdgeId { from: self.to, tailport: self.headport, to: self.from, headport: self.tailport }
+    }
+
+    /// Ends of the edge including ports, to sort edges by
+    pub(crate) fn sort_key(&self) -> (&NodeId, &NodeId, &Option<String>, &Option<String>) {
+        (&self.from, &self.to, &self.tailport, &self.headport)
+    }
+
     /// Write the edge id to dot format
-    pub fn to_dot<W: ?Sized>(&self, indent: usize, writer: &mut W) -> Result<()>
+    pub fn to_dot<W>(&self, indent: usize, writer: &mut W) -> Result<()>
     where
-        W: Write,
+        W: Write + ?Sized,
     {
         (0..indent).try_for_each(|_| write!(writer, "\t"))?;
 
         let from = utils::pretty_id(&self.from);
         write!(writer, "{from}")?;
         if let Some(tailport) = &self.tailport {
-            write!(writer, ":{tailport}")?;
+            write!(writer, ":{}", utils::pretty_port(tailport))?;
         }
 
         let to = utils::pretty_id(&self.to);
         write!(writer, " -> {to}")?;
         if let Some(headport) = &self.headport {
-            write!(writer, ":{headport}")?;
+            write!(writer, ":{}", utils::pretty_port(headport))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for EdgeId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.from)?;
+        if let Some(tailport) = &self.tailport {
+            write!(f, ":{tailport}")?;
+        }
+
+        write!(f, " -> {}", self.to)?;
+        if let Some(headport) = &self.headport {
+            write!(f, ":{headport}")?;
         }
 
         Ok(())
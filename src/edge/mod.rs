@@ -1,10 +1,16 @@
 pub mod id;
 
-use crate::attr::Attr;
+use crate::{
+    attr::Attr,
+    graphs::DotWriteOptions,
+    layer::{self, LayerRange},
+    utils,
+};
 pub use id::EdgeId;
 
 use std::borrow::Borrow;
 use std::collections::HashSet;
+use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::io::{Result, Write};
 
@@ -36,7 +42,7 @@ impl Borrow<EdgeId> for Edge {
 }
 
 impl Edge {
-    pub(crate) fn new(id: EdgeId, attrs: HashSet<Attr>) -> Edge {
+    pub fn new(id: EdgeId, attrs: HashSet<Attr>) -> Edge {
         Edge { id, attrs }
     }
 
@@ -48,20 +54,64 @@ impl Edge {
         &self.attrs
     }
 
+    /// Layers of the edge as declared in its `layer` attribute,
+    /// `None` if it has none and thus belongs to all layers.
+    ///
+    /// The attribute is split with the default `layersep` and `layerlistsep`.
+    pub fn layers(&self) -> Option<Vec<LayerRange>> {
+        self.attrs.get("layer").map(|attr| {
+            layer::parse_ranges(&attr.value, layer::DEFAULT_LAYERSEP, layer::DEFAULT_LAYERLISTSEP)
+        })
+    }
+
+    /// Weight of the edge as declared in its `weight` attribute,
+    /// `None` if it has none or if it is not a number.
+    pub fn weight(&self) -> Option<f64> {
+        self.attrs.get("weight").and_then(|attr| attr.value.parse().ok())
+    }
+
     /// Write the edge to dot format
-    pub fn to_dot<W: ?Sized>(&self, indent: usize, writer: &mut W) -> Result<()>
+    pub fn to_dot<W>(&self, indent: usize, writer: &mut W) -> Result<()>
     where
-        W: Write,
+        W: Write + ?Sized,
     {
-        self.id.to_dot(indent, writer)?;
+        self.write_dot(indent, &DotWriteOptions::default(), &HashSet::new(), writer)
+    }
 
-        writeln!(writer, " [")?;
-        for attr in &self.attrs {
-            attr.to_dot(indent + 1, writer)?;
+    /// Write the edge to dot format, laid out by `options`,
+    /// omitting `defaults` declared by the subgraph holding it
+    pub(crate) fn write_dot<W>(
+        &self,
+        indent: usize,
+        options: &DotWriteOptions,
+        defaults: &HashSet<Attr>,
+        writer: &mut W,
+    ) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        let attrs: Vec<&Attr> =
+            self.attrs.iter().filter(|attr| !defaults.contains(*attr)).collect();
+        options.write_indent(indent, writer)?;
+        self.id.to_dot(0, writer)?;
+        if !(options.is_inline() && attrs.is_empty()) {
+            write!(writer, " ")?;
+            options.write_attrs(attrs, indent, writer)?;
         }
-        (0..indent).try_for_each(|_| write!(writer, "\t"))?;
-        writeln!(writer, "]")?;
+        options.write_end(false, writer)?;
 
         Ok(())
     }
 }
+
+impl fmt::Display for Edge {
+    /// Writes the edge in dot format, or a summary of it with `{:#}`,
+    /// e.g. `edge a -> b with 1 attr`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "edge {} with {}", self.id, utils::count(self.attrs.len(), "attr"))
+        } else {
+            utils::fmt_with(f, |writer| self.to_dot(0, writer))
+        }
+    }
+}
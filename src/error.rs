@@ -6,12 +6,37 @@ pub enum DotGraphError {
     InvalidGraph(String),
     #[error("`{0}` is not a digraph")]
     UndirectedGraph(String),
-    #[error("`{0}` contains a cycle")]
-    Cycle(String),
+    #[error("`{0}` contains a cycle `{}`", cycle(.1))]
+    Cycle(String, Vec<String>),
     #[error("`{0}` is not a node of graph `{1}`")]
     NoSuchNode(String, String),
+    #[error("`{0}` is already a node of graph `{1}`")]
+    DuplicateNode(String, String),
+    #[error("`{0}` is not a port of node `{1}`")]
+    NoSuchPort(String, String),
+    #[error("`{0}` is not an edge of graph `{1}`")]
+    NoSuchEdge(String, String),
+    #[error("`{0}` is already an edge of graph `{1}`")]
+    DuplicateEdge(String, String),
     #[error("`{0}` is not a subgraph of graph `{1}`")]
     NoSuchSubGraph(String, String),
+    #[error("`{0}` is already a subgraph of graph `{1}`")]
+    DuplicateSubGraph(String, String),
+    #[error("`{0}` is the root subgraph of graph `{1}`")]
+    RootSubGraph(String, String),
+    #[error("`{0}` cannot be nested within its own descendant `{1}`")]
+    CyclicSubGraph(String, String),
+    #[error("`{0}` is not a layer of graph `{1}`")]
+    NoSuchLayer(String, String),
+    #[error("`{0}` is not a valid query: {1}")]
+    InvalidQuery(String, String),
+    #[error("`{0}` cannot be rendered: {1}")]
+    RenderFailed(String, String),
     #[error(transparent)]
     IOError(#[from] std::io::Error),
 }
+
+/// Format the ids of nodes along a cycle, closing it back to the first node
+fn cycle(ids: &[String]) -> String {
+    ids.iter().chain(ids.first()).cloned().collect::<Vec<_>>().join(" -> ")
+}
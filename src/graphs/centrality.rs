@@ -0,0 +1,63 @@
+use crate::{attr::Attr, graphs::graph::Graph, node::NodeId};
+
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+impl Graph {
+    /// Score nodes of this `Graph` by PageRank, where a random walk follows an edge with
+    /// probability `damping` and jumps to any node otherwise, as well as from nodes without
+    /// outgoing edges.
+    ///
+    /// # Arguments
+    ///
+    /// * `damping` - Probability of following an edge, typically 0.85
+    /// * `iterations` - Number of rounds of power iteration
+    ///
+    /// # Returns
+    ///
+    /// The score of each node, summing up to one.
+    pub fn pagerank(&self, damping: f64, iterations: usize) -> HashMap<&NodeId, f64> {
+        let n = self.nodes.len() as f64;
+        let mut ranks: HashMap<&NodeId, f64> =
+            self.nodes.par_iter().map(|node| (&node.id, 1.0 / n)).collect();
+
+        for _ in 0..iterations {
+            let dangling: f64 = ranks
+                .par_iter()
+                .filter_map(|(&id, &rank)| self.fwdmap[id].is_empty().then_some(rank))
+                .sum();
+            let base = (1.0 - damping) / n + damping * dangling / n;
+
+            ranks = ranks
+                .par_iter()
+                .map(|(&id, _)| {
+                    let inflow: f64 = self.bwdmap[id]
+                        .iter()
+                        .map(|from| ranks[from] / self.fwdmap[from].len() as f64)
+                        .sum();
+                    (id, base + damping * inflow)
+                })
+                .collect();
+        }
+
+        ranks
+    }
+
+    /// Score nodes of this `Graph` by `Graph::pagerank`, writing the scores as attributes.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Key of the attributes to write, e.g., `width` to size nodes by their scores
+    /// * `damping` - Probability of following an edge, typically 0.85
+    /// * `iterations` - Number of rounds of power iteration
+    pub fn annotate_pagerank(&mut self, key: &str, damping: f64, iterations: usize) {
+        let ranks: HashMap<NodeId, f64> = self
+            .pagerank(damping, iterations)
+            .into_iter()
+            .map(|(id, rank)| (id.clone(), rank))
+            .collect();
+
+        self.put_node_attrs(|id| vec![Attr::new(key.to_string(), ranks[id].to_string(), false)]);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{
+    attr::Attr,
+    edge::EdgeId,
+    graphs::graph::{Graph, GraphId},
+    node::NodeId,
+};
+
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A `GraphChange` describes a mutation applied to a `Graph`.
+pub enum GraphChange {
+    /// A subgraph was added to a parent subgraph
+    SubGraphAdded(GraphId, GraphId),
+    /// A subgraph was moved under another parent subgraph
+    SubGraphMoved(GraphId, GraphId),
+    /// A subgraph was renamed from the first id to the second
+    SubGraphRenamed(GraphId, GraphId),
+    /// A subgraph was collapsed into a node
+    SubGraphCollapsed(GraphId, NodeId),
+    /// A collapsed subgraph was expanded back
+    SubGraphExpanded(GraphId),
+    /// A node was added to a subgraph
+    NodeAdded(NodeId, GraphId),
+    /// A node was removed, along with its edges
+    NodeRemoved(NodeId),
+    /// A node was moved to another subgraph
+    NodeMoved(NodeId, GraphId),
+    /// A node was renamed from the first id to the second, along with the edges touching it
+    NodeRenamed(NodeId, NodeId),
+    /// Every node was renamed at once
+    NodesRelabeled,
+    /// The attributes of a node changed
+    NodeAttrChanged(NodeId),
+    /// An edge was added to a subgraph
+    EdgeAdded(EdgeId, GraphId),
+    /// An edge was removed
+    EdgeRemoved(EdgeId),
+    /// The attributes of an edge changed
+    EdgeAttrChanged(EdgeId),
+    /// Every edge was reversed
+    EdgesReversed,
+}
+
+type Hook = Box<dyn Fn(&GraphChange) + Send + Sync>;
+
+#[derive(Default)]
+/// `Subscribers` are notified of the changes to a graph.
+///
+/// Subscribers do not follow clones of the graph, so that changes to a copy,
+/// e.g. a graph filtered from it, are not mistaken for changes to the original.
+pub(crate) struct Subscribers {
+    hooks: Vec<Hook>,
+    /// Changes withheld from subscribers until a transaction commits
+    log: Option<Vec<GraphChange>>,
+}
+
+impl Subscribers {
+    pub(crate) fn notify(&mut self, change: GraphChange) {
+        match &mut self.log {
+            Some(log) => log.push(change),
+            None => self.hooks.iter().for_each(|hook| hook(&change)),
+        }
+    }
+
+    /// Whether any change would be delivered or withheld
+    pub(crate) fn is_watching(&self) -> bool {
+        !self.hooks.is_empty() || self.log.is_some()
+    }
+
+    /// Start withholding changes
+    pub(crate) fn record(&mut self) {
+        self.log = Some(Vec::new());
+    }
+
+    /// Stop withholding changes, returning those withheld so far
+    pub(crate) fn recorded(&mut self) -> Vec<GraphChange> {
+        self.log.take().unwrap_or_default()
+    }
+}
+
+/// Whether `new` differs from `old` in any key or value
+pub(crate) fn attrs_changed(old: &HashSet<Attr>, new: &HashSet<Attr>) -> bool {
+    old.len() != new.len()
+        || new.iter().any(|attr| {
+            old.get(attr).is_none_or(|old| old.value != attr.value || old.is_html != attr.is_html)
+        })
+}
+
+impl Clone for Subscribers {
+    fn clone(&self) -> Subscribers {
+        Subscribers::default()
+    }
+}
+
+impl fmt::Debug for Subscribers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscribers").field("hooks", &self.hooks.len()).finish()
+    }
+}
+
+impl Graph {
+    /// Register `hook` to be called with every change applied to this graph from now on.
+    ///
+    /// Changes made within `Graph::transaction` are delivered once it commits,
+    /// and are never delivered if it fails.
+    pub fn subscribe<F>(&mut self, hook: F)
+    where
+        F: Fn(&GraphChange) + Send + Sync + 'static,
+    {
+        self.subscribers.hooks.push(Box::new(hook));
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{
+    edge::Edge,
+    error::DotGraphError,
+    graphs::{
+        graph::{Graph, GraphId},
+        subgraph::SubGraph,
+    },
+    node::NodeId,
+};
+
+use std::collections::HashSet;
+
+use rayon::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Sizes of a subgraph, counting everything in its descendants as well.
+pub struct SubGraphStats {
+    nodes: usize,
+    edges: usize,
+    children: usize,
+    depth: usize,
+}
+
+impl SubGraphStats {
+    /// Number of nodes in the subgraph and its descendants
+    pub fn nodes(&self) -> usize {
+        self.nodes
+    }
+
+    /// Number of edges in the subgraph and its descendants
+    pub fn edges(&self) -> usize {
+        self.edges
+    }
+
+    /// Number of direct child subgraphs
+    pub fn children(&self) -> usize {
+        self.children
+    }
+
+    /// Number of levels of subgraphs below the subgraph, zero if it has no children
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+impl Graph {
+    /// Collect all edges from nodes in the subgraph `from` to nodes in the subgraph `to`,
+    /// whose number is the weight of a cluster-level summary edge between them.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if either subgraph does not exist,
+    /// `Ok` with the edges between them otherwise.
+    pub fn edges_between_subgraphs(
+        &self,
+        from: &GraphId,
+        to: &GraphId,
+    ) -> Result<Vec<&Edge>, DotGraphError> {
+        let froms: HashSet<&NodeId> = self.collect_nodes(from)?.into_iter().collect();
+        let tos: HashSet<&NodeId> = self.collect_nodes(to)?.into_iter().collect();
+
+        Ok(self
+            .edges
+            .par_iter()
+            .filter(|edge| froms.contains(&edge.id.from) && tos.contains(&edge.id.to))
+            .collect())
+    }
+
+    /// Collect all edges with exactly one end in the subgraph with `id`, or its descendants,
+    /// i.e. the external interface of the subgraph.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no subgraph with `id`,
+    /// `Ok` with the edges crossing its boundary otherwise.
+    pub fn boundary_edges(&self, id: &GraphId) -> Result<Vec<&Edge>, DotGraphError> {
+        let inside: HashSet<&NodeId> = self.collect_nodes(id)?.into_iter().collect();
+
+        Ok(self
+            .edges
+            .par_iter()
+            .filter(|edge| inside.contains(&edge.id.from) != inside.contains(&edge.id.to))
+            .collect())
+    }
+
+    /// Search for all subgraphs satisfying `matches`, in the order of their ids.
+    pub fn find_subgraphs<P>(&self, matches: P) -> Vec<&SubGraph>
+    where
+        P: Fn(&SubGraph) -> bool + Sync,
+    {
+        let mut found: Vec<&SubGraph> =
+            self.subgraphs.par_iter().filter(|subgraph| matches(subgraph)).collect();
+        found.sort_unstable_by(|a, b| a.id.cmp(&b.id));
+
+        found
+    }
+
+    /// Search for all subgraphs whose attribute `key` is `value`, in the order of their ids.
+    pub fn find_subgraphs_by_attr(&self, key: &str, value: &str) -> Vec<&SubGraph> {
+        self.find_subgraphs(|subgraph| {
+            subgraph.attrs.get(key).is_some_and(|attr| attr.value == value)
+        })
+    }
+
+    /// Search for all subgraphs whose attribute `key` contains `pattern`,
+    /// e.g. clusters labelled with `decoder`, in the order of their ids.
+    pub fn find_subgraphs_by_attr_contains(&self, key: &str, pattern: &str) -> Vec<&SubGraph> {
+        self.find_subgraphs(|subgraph| {
+            subgraph.attrs.get(key).is_some_and(|attr| attr.value.contains(pattern))
+        })
+    }
+
+    /// Compute the sizes of the subgraph with `id` in a single pass over its descendants.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no subgraph with `id`,
+    /// `Ok` with the statistics of the subgraph otherwise.
+    pub fn subgraph_stats(&self, id: &GraphId) -> Result<SubGraphStats, DotGraphError> {
+        let subgraph = self
+            .subgraphs
+            .get(id)
+            .ok_or_else(|| DotGraphError::NoSuchSubGraph(id.to_string(), self.id.clone()))?;
+
+        let mut stats = SubGraphStats {
+            nodes: subgraph.node_ids.len(),
+            edges: subgraph.edge_ids.len(),
+            children: subgraph.subgraph_ids.len(),
+            depth: 0,
+        };
+        for child in &subgraph.subgraph_ids {
+            let child = self.subgraph_stats(child)?;
+            stats.nodes += child.nodes;
+            stats.edges += child.edges;
+            stats.depth = stats.depth.max(child.depth + 1);
+        }
+
+        Ok(stats)
+    }
+}
@@ -0,0 +1,234 @@
+use crate::{
+    attr::Attr,
+    edge::{Edge, EdgeId},
+    error::DotGraphError,
+    graphs::{
+        change::GraphChange,
+        graph::{make_edge_maps, make_subtree, Graph, GraphId},
+        payload::Payloads,
+        subgraph::SubGraph,
+    },
+    node::{Node, NodeId},
+};
+
+use std::collections::{HashMap, HashSet};
+
+use rayon::prelude::*;
+
+#[derive(Debug, Clone)]
+/// A `CollapsedState` holds what `Graph::collapse_subgraph` removed from a graph,
+/// so that `Graph::expand` can restore it.
+pub struct CollapsedState {
+    /// Id of the node the subgraph was collapsed into
+    node: NodeId,
+    /// Id of the parent of the collapsed subgraph
+    parent: GraphId,
+    /// Id of the collapsed subgraph
+    subgraph: GraphId,
+    /// The collapsed subgraph and all its descendants
+    subgraphs: Vec<SubGraph>,
+    /// Nodes within the collapsed subgraph
+    nodes: Vec<Node>,
+    /// Edges from or to the nodes within the collapsed subgraph
+    edges: Vec<Edge>,
+    /// Removed edges held by subgraphs outside the collapsed subgraph, with their holders
+    owners: Vec<(GraphId, EdgeId)>,
+    /// Payloads of the removed nodes and edges
+    payloads: Payloads,
+}
+
+impl CollapsedState {
+    pub fn node(&self) -> &NodeId {
+        &self.node
+    }
+
+    pub fn subgraph(&self) -> &GraphId {
+        &self.subgraph
+    }
+}
+
+impl Graph {
+    /// Collapse the subgraph with `id` into a single node, named after the subgraph.
+    ///
+    /// All nodes, edges, and subgraphs within the subgraph are removed. Edges crossing the
+    /// boundary of the subgraph are rewired to the new node, where parallel edges from or to
+    /// the same outside node are aggregated into one edge, with their number as its `count`.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no subgraph with `id` or if it is the root subgraph,
+    /// `Ok` with the state to `expand` the subgraph back from otherwise.
+    pub fn collapse_subgraph(&mut self, id: &GraphId) -> Result<CollapsedState, DotGraphError> {
+        if !self.subgraphs.contains(id) {
+            return Err(DotGraphError::NoSuchSubGraph(id.to_string(), self.id.clone()));
+        }
+        let Some(parent) = self.subgraph_parent(id).cloned() else {
+            return Err(DotGraphError::RootSubGraph(id.to_string(), self.id.clone()));
+        };
+
+        let descendants = self.subgraph_descendants(id);
+        let inside: HashSet<NodeId> =
+            self.collect_nodes(id).unwrap().into_iter().cloned().collect();
+
+        // nodes within the subgraph are about to be removed, so only those outside may clash
+        let mut collapsed = id.clone();
+        while self.nodes.contains(&collapsed) && !inside.contains(&collapsed) {
+            collapsed.push('_');
+        }
+
+        // count boundary edges per outside node, in each direction
+        let mut outgoing: HashMap<NodeId, usize> = HashMap::new();
+        let mut incoming: HashMap<NodeId, usize> = HashMap::new();
+        for edge in &self.edges {
+            match (inside.contains(&edge.id.from), inside.contains(&edge.id.to)) {
+                (true, false) => *outgoing.entry(edge.id.to.clone()).or_default() += 1,
+                (false, true) => *incoming.entry(edge.id.from.clone()).or_default() += 1,
+                _ => {}
+            }
+        }
+
+        let subgraph = self.search_subgraph(id).unwrap();
+        let label = subgraph.attrs.get("label").map_or(id.clone(), |attr| attr.value.clone());
+        let attrs = HashSet::from([
+            Attr::new(String::from("label"), label, false),
+            Attr::new(String::from("shape"), String::from("box3d"), false),
+        ]);
+
+        let is_inside =
+            |edge: &Edge| inside.contains(&edge.id.from) || inside.contains(&edge.id.to);
+        let nodes: Vec<Node> =
+            self.nodes.par_iter().filter(|node| inside.contains(&node.id)).cloned().collect();
+        let edges: Vec<Edge> =
+            self.edges.par_iter().filter(|edge| is_inside(edge)).cloned().collect();
+        let subgraphs: Vec<SubGraph> = self
+            .subgraphs
+            .par_iter()
+            .filter(|subgraph| descendants.contains(&subgraph.id))
+            .cloned()
+            .collect();
+        let mut owners = Vec::new();
+        for subgraph in &self.subgraphs {
+            if !descendants.contains(&subgraph.id) {
+                for edge_id in &subgraph.edge_ids {
+                    if inside.contains(&edge_id.from) || inside.contains(&edge_id.to) {
+                        owners.push((subgraph.id.clone(), edge_id.clone()));
+                    }
+                }
+            }
+        }
+
+        let node_ids: HashSet<&NodeId> = nodes.iter().map(|node| &node.id).collect();
+        let edge_ids: HashSet<&EdgeId> = edges.iter().map(|edge| &edge.id).collect();
+        let payloads = self.payloads.take(&node_ids, &edge_ids);
+
+        self.nodes.retain(|node| !inside.contains(&node.id));
+        self.edges.retain(|edge| !is_inside(edge));
+        self.subgraphs.retain(|subgraph| !descendants.contains(&subgraph.id));
+        self.subgraphs = std::mem::take(&mut self.subgraphs)
+            .into_par_iter()
+            .map(|mut subgraph| {
+                subgraph.subgraph_ids.remove(id);
+                subgraph.edge_ids.retain(|id| self.edges.contains(id));
+                subgraph
+            })
+            .collect();
+
+        self.nodes.insert(Node::new(collapsed.clone(), attrs));
+        self.prefixes.invalidate();
+        self.update_subgraph(&parent, |subgraph| {
+            subgraph.node_ids.insert(collapsed.clone());
+        });
+
+        let boundary =
+            outgoing
+                .into_iter()
+                .map(|(to, count)| (EdgeId::new(collapsed.clone(), None, to, None), count))
+                .chain(incoming.into_iter().map(|(from, count)| {
+                    (EdgeId::new(from, None, collapsed.clone(), None), count)
+                }));
+        for (edge_id, count) in boundary {
+            let attrs = HashSet::from([Attr::new(String::from("count"), count.to_string(), false)]);
+            self.update_subgraph(&parent, |subgraph| {
+                subgraph.edge_ids.insert(edge_id.clone());
+            });
+            self.edges.insert(Edge::new(edge_id, attrs));
+        }
+
+        self.subtree = make_subtree(&self.subgraphs);
+        (self.fwdmap, self.bwdmap) = make_edge_maps(&self.nodes, &self.edges);
+        self.owners.invalidate();
+
+        self.subscribers.notify(GraphChange::SubGraphCollapsed(id.clone(), collapsed.clone()));
+
+        Ok(CollapsedState {
+            node: collapsed,
+            parent,
+            subgraph: id.clone(),
+            subgraphs,
+            nodes,
+            edges,
+            owners,
+            payloads,
+        })
+    }
+
+    /// Expand a subgraph collapsed by `Graph::collapse_subgraph`, replacing its node with the
+    /// nodes, edges, and subgraphs it held, and restoring the edges crossing its boundary.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if the collapsed node or the parent of the subgraph is missing,
+    /// or if any of the nodes or subgraphs to restore already exists, `Ok` otherwise.
+    pub fn expand(&mut self, state: CollapsedState) -> Result<(), DotGraphError> {
+        if !self.nodes.contains(&state.node) {
+            return Err(DotGraphError::NoSuchNode(state.node, self.id.clone()));
+        }
+        if !self.subgraphs.contains(&state.parent) {
+            return Err(DotGraphError::NoSuchSubGraph(state.parent, self.id.clone()));
+        }
+        // a node within the subgraph may share its id with the collapsed node
+        let exists = |node: &&Node| node.id != state.node && self.nodes.contains(&node.id);
+        if let Some(node) = state.nodes.iter().find(exists) {
+            return Err(DotGraphError::DuplicateNode(node.id.clone(), self.id.clone()));
+        }
+        if let Some(subgraph) = state.subgraphs.iter().find(|s| self.subgraphs.contains(&s.id)) {
+            return Err(DotGraphError::DuplicateSubGraph(subgraph.id.clone(), self.id.clone()));
+        }
+
+        self.remove_node(&state.node).unwrap();
+
+        self.update_subgraph(&state.parent, |subgraph| {
+            subgraph.subgraph_ids.insert(state.subgraph.clone());
+        });
+        self.nodes.extend(state.nodes);
+        self.prefixes.invalidate();
+        for edge in state.edges {
+            if self.nodes.contains(&edge.id.from) && self.nodes.contains(&edge.id.to) {
+                self.edges.insert(edge);
+            }
+        }
+        // edges to nodes removed or renamed since the collapse are not restored
+        let edges = &self.edges;
+        self.subgraphs.extend(state.subgraphs.into_iter().map(|mut subgraph| {
+            subgraph.edge_ids.retain(|id| edges.contains(id));
+            subgraph
+        }));
+        let node_ids: HashSet<&NodeId> = self.nodes.iter().map(|node| &node.id).collect();
+        let edge_ids: HashSet<&EdgeId> = self.edges.iter().map(|edge| &edge.id).collect();
+        self.payloads.extend(state.payloads.extract(&node_ids, &edge_ids));
+        for (owner, edge_id) in state.owners {
+            if self.subgraphs.contains(&owner) && self.edges.contains(&edge_id) {
+                self.update_subgraph(&owner, |subgraph| {
+                    subgraph.edge_ids.insert(edge_id);
+                });
+            }
+        }
+
+        self.subtree = make_subtree(&self.subgraphs);
+        (self.fwdmap, self.bwdmap) = make_edge_maps(&self.nodes, &self.edges);
+        self.owners.invalidate();
+        self.subscribers.notify(GraphChange::SubGraphExpanded(state.subgraph));
+
+        Ok(())
+    }
+}
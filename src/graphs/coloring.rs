@@ -0,0 +1,111 @@
+use crate::{
+    attr::Attr,
+    graphs::{graph::Graph, traversal::Direction},
+    node::NodeId,
+};
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use rayon::prelude::*;
+
+/// Colors of the `set312` color scheme, which `Graph::paint_coloring` cycles through
+const PALETTE_SIZE: usize = 12;
+
+impl Graph {
+    /// Split nodes of this `Graph` into two sides such that every edge, regardless of its
+    /// direction, connects nodes of different sides.
+    ///
+    /// Connected components are colored in the order of their least node ids,
+    /// each putting its least node id on the first side.
+    ///
+    /// # Returns
+    ///
+    /// `None` if this graph is not bipartite, e.g., if it has a self-loop or an odd cycle,
+    /// otherwise `Some` with the ids of nodes on each side.
+    pub fn bipartition(&self) -> Option<(HashSet<NodeId>, HashSet<NodeId>)> {
+        let mut starts: Vec<&NodeId> = self.nodes.iter().map(|node| &node.id).collect();
+        starts.sort_unstable();
+
+        let mut sides: HashMap<&NodeId, bool> = HashMap::new();
+        for start in starts {
+            if sides.contains_key(start) {
+                continue;
+            }
+
+            sides.insert(start, false);
+            let mut frontier = VecDeque::from([start]);
+            while let Some(id) = frontier.pop_front() {
+                let side = sides[id];
+                for next in self.adjacent(id, Direction::Both) {
+                    match sides.get(next) {
+                        Some(&other) if other == side => return None,
+                        Some(_) => {}
+                        None => {
+                            sides.insert(next, !side);
+                            frontier.push_back(next);
+                        }
+                    }
+                }
+            }
+        }
+
+        let (left, right): (Vec<_>, Vec<_>) = sides.into_iter().partition(|(_, side)| !side);
+        let left = left.into_iter().map(|(id, _)| id.clone()).collect();
+        let right = right.into_iter().map(|(id, _)| id.clone()).collect();
+
+        Some((left, right))
+    }
+
+    /// Color nodes of this `Graph` such that nodes adjacent through an edge, regardless of its
+    /// direction, differ in color, trying to use few colors.
+    ///
+    /// Nodes are colored one at a time with the least color not taken by their neighbors,
+    /// those with more neighbors first, ties broken by their ids. Self-loops are ignored.
+    ///
+    /// # Returns
+    ///
+    /// The color of each node, numbered from zero.
+    pub fn greedy_coloring(&self) -> HashMap<&NodeId, usize> {
+        let neighbors = |id| self.adjacent(id, Direction::Both).filter(move |&other| other != id);
+        let degrees: HashMap<&NodeId, usize> = self
+            .nodes
+            .par_iter()
+            .map(|node| (&node.id, neighbors(&node.id).collect::<HashSet<_>>().len()))
+            .collect();
+
+        let mut order: Vec<&NodeId> = degrees.keys().copied().collect();
+        order.sort_unstable_by(|a, b| degrees[b].cmp(&degrees[a]).then(a.cmp(b)));
+
+        let mut colors: HashMap<&NodeId, usize> = HashMap::new();
+        for id in order {
+            let taken: HashSet<usize> =
+                neighbors(id).filter_map(|next| colors.get(next)).copied().collect();
+            let color = (0..).find(|color| !taken.contains(color)).unwrap();
+            colors.insert(id, color);
+        }
+
+        colors
+    }
+
+    /// Color nodes of this `Graph` by `Graph::greedy_coloring`, writing the colors as filled
+    /// `fillcolor`s of the `set312` color scheme, which repeats after 12 colors.
+    ///
+    /// # Returns
+    ///
+    /// The number of colors used.
+    pub fn paint_coloring(&mut self) -> usize {
+        let colors: HashMap<NodeId, usize> =
+            self.greedy_coloring().into_iter().map(|(id, color)| (id.clone(), color)).collect();
+
+        self.put_node_attrs(|id| {
+            let color = colors[id] % PALETTE_SIZE + 1;
+            vec![
+                Attr::new(String::from("style"), String::from("filled"), false),
+                Attr::new(String::from("colorscheme"), String::from("set312"), false),
+                Attr::new(String::from("fillcolor"), color.to_string(), false),
+            ]
+        });
+
+        colors.values().max().map_or(0, |color| color + 1)
+    }
+}
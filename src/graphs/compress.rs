@@ -0,0 +1,75 @@
+use crate::graphs::{graph::Graph, write::DotWriteOptions};
+
+use std::io::{Result, Write};
+
+/// An `Encoder` compresses, or otherwise encodes, what is written to it into an underlying
+/// writer, which it hands back once the encoding is finished, e.g. with trailing checksums.
+///
+/// It is implemented for the encoders of `flate2` with the `flate2` feature,
+/// and may be implemented for any other compression format.
+pub trait Encoder: Write {
+    /// The writer the encoded bytes go to
+    type Inner;
+
+    /// Finish encoding and return the underlying writer
+    fn finish(self) -> Result<Self::Inner>;
+}
+
+#[cfg(feature = "flate2")]
+impl<W: Write> Encoder for flate2::write::GzEncoder<W> {
+    type Inner = W;
+
+    fn finish(self) -> Result<W> {
+        flate2::write::GzEncoder::finish(self)
+    }
+}
+
+#[cfg(feature = "flate2")]
+impl<W: Write> Encoder for flate2::write::ZlibEncoder<W> {
+    type Inner = W;
+
+    fn finish(self) -> Result<W> {
+        flate2::write::ZlibEncoder::finish(self)
+    }
+}
+
+#[cfg(feature = "flate2")]
+impl<W: Write> Encoder for flate2::write::DeflateEncoder<W> {
+    type Inner = W;
+
+    fn finish(self) -> Result<W> {
+        flate2::write::DeflateEncoder::finish(self)
+    }
+}
+
+impl Graph {
+    /// Write the graph to dot format, laid out by `options`, through `encoder`.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if writing or finishing the encoding fails, `Ok` with the writer underlying
+    /// `encoder` otherwise
+    pub fn to_dot_encoded<E>(&self, options: &DotWriteOptions, mut encoder: E) -> Result<E::Inner>
+    where
+        E: Encoder,
+    {
+        self.to_dot_with_options(options, &mut encoder)?;
+
+        encoder.finish()
+    }
+
+    /// Write the graph to gzip compressed dot format, to be saved as `.dot.gz`.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if writing fails, `Ok` with `writer` otherwise
+    #[cfg(feature = "flate2")]
+    pub fn to_dot_gz<W>(&self, writer: W) -> Result<W>
+    where
+        W: Write,
+    {
+        let encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::default());
+
+        self.to_dot_encoded(&DotWriteOptions::default(), encoder)
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{attr::Attr, edge::EdgeId, graphs::graph::Graph, node::NodeId, utils};
+
+use std::collections::HashSet;
+use std::io::{Result, Write};
+
+impl Graph {
+    /// Write nodes and edges of the graph as two CSV tables, flattening the attributes
+    /// of `columns` into columns of the same names, e.g., to load them into dataframes.
+    ///
+    /// Nodes are written as rows of `id` followed by `columns`, sorted by their ids,
+    /// and edges as rows of `from`, `to`, `tailport`, and `headport` followed by `columns`,
+    /// sorted by their ends. Missing attributes or ports are written as empty fields.
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes_writer` - Destination of the node table
+    /// * `edges_writer` - Destination of the edge table
+    /// * `columns` - Keys of attributes to write, in the order of columns
+    pub fn to_csv<N, E>(
+        &self,
+        nodes_writer: &mut N,
+        edges_writer: &mut E,
+        columns: &[&str],
+    ) -> Result<()>
+    where
+        N: Write + ?Sized,
+        E: Write + ?Sized,
+    {
+        let mut nodes: Vec<&NodeId> = self.nodes.iter().map(|node| &node.id).collect();
+        nodes.sort_unstable();
+        let mut edges: Vec<&EdgeId> = self.edges.iter().map(|edge| &edge.id).collect();
+        edges.sort_unstable_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+
+        let header = |keys: &[&str]| {
+            keys.iter().chain(columns).map(|key| utils::csv_field(key)).collect::<Vec<_>>()
+        };
+
+        writeln!(nodes_writer, "{}", header(&["id"]).join(","))?;
+        for id in nodes {
+            let node = self.search_node(id).unwrap();
+
+            let mut row = vec![utils::csv_field(id)];
+            row.extend(fields(&node.attrs, columns));
+            writeln!(nodes_writer, "{}", row.join(","))?;
+        }
+
+        writeln!(edges_writer, "{}", header(&["from", "to", "tailport", "headport"]).join(","))?;
+        for id in edges {
+            let edge = self.search_edge(id).unwrap();
+
+            let port =
+                |port: &Option<String>| port.as_deref().map_or(String::new(), utils::csv_field);
+            let mut row = vec![
+                utils::csv_field(&id.from),
+                utils::csv_field(&id.to),
+                port(&id.tailport),
+                port(&id.headport),
+            ];
+            row.extend(fields(&edge.attrs, columns));
+            writeln!(edges_writer, "{}", row.join(","))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Values of `attrs` for `columns`, empty for those missing
+fn fields<'a>(attrs: &'a HashSet<Attr>, columns: &'a [&str]) -> impl Iterator<Item = String> + 'a {
+    columns
+        .iter()
+        .map(|&key| attrs.get(key).map_or(String::new(), |attr| utils::csv_field(&attr.value)))
+}
@@ -0,0 +1,424 @@
+use crate::{
+    edge::{Edge, EdgeId},
+    error::DotGraphError,
+    graphs::{change::GraphChange, graph::Graph, traversal::Direction},
+    node::NodeId,
+};
+
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use rayon::prelude::*;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mark {
+    /// On the path currently being searched
+    Active,
+    /// Searched, and not on any cycle reachable from the start
+    Done,
+}
+
+/// A lazy enumeration of elementary cycles, created by `Graph::cycles`.
+///
+/// Cycles are found with Johnson's algorithm, one start node at a time in the order of
+/// node ids, where each cycle begins with its least node id. Strongly connected components
+/// are found once up front, so that nodes on no cycle are never searched from.
+pub struct Cycles<'a> {
+    graph: &'a Graph,
+    /// Ids of nodes on some cycle in sorted order, the ones before `next` are searched
+    starts: Vec<&'a NodeId>,
+    /// The strongly connected component of each node in `starts`
+    components: HashMap<&'a NodeId, usize>,
+    next: usize,
+    /// Longest cycle to enumerate, counted in nodes
+    max_len: Option<usize>,
+    /// Cycles found from the last start but not yet returned
+    pending: VecDeque<Vec<&'a NodeId>>,
+}
+
+impl<'a> Iterator for Cycles<'a> {
+    type Item = Vec<&'a NodeId>;
+
+    fn next(&mut self) -> Option<Vec<&'a NodeId>> {
+        while self.pending.is_empty() {
+            let start = *self.starts.get(self.next)?;
+            self.next += 1;
+            self.search(start);
+        }
+
+        self.pending.pop_front()
+    }
+}
+
+impl<'a> Cycles<'a> {
+    /// Find all cycles through `start` that avoid the starts searched before it
+    fn search(&mut self, start: &'a NodeId) {
+        let graph = self.graph;
+        // cycles through `start` stay within its component, among nodes not searched yet
+        let within = self.components[start];
+        let component = graph.strong_component(start, |id| {
+            id >= start && self.components.get(id).is_some_and(|&c| c == within)
+        });
+        let successors = |id: &'a NodeId| -> Vec<&'a NodeId> {
+            let mut successors: Vec<&NodeId> =
+                graph.fwdmap[id].iter().filter(|next| component.contains(*next)).collect();
+            successors.sort_unstable_by(|a, b| b.cmp(a));
+            successors
+        };
+
+        let mut blocked: HashSet<&NodeId> = HashSet::from([start]);
+        let mut blockers: HashMap<&NodeId, HashSet<&NodeId>> = HashMap::new();
+
+        // each frame holds a node on the path, its successors to visit, and whether a cycle
+        // has been closed from it or a longer path has been cut off by `max_len`
+        let mut path = vec![start];
+        let mut frames = vec![(start, successors(start), false)];
+        while let Some((id, nexts, closed)) = frames.last_mut() {
+            if let Some(next) = nexts.pop() {
+                if next == start {
+                    if self.max_len.is_none_or(|max_len| path.len() <= max_len) {
+                        self.pending.push_back(path.clone());
+                    }
+                    *closed = true;
+                } else if !blocked.contains(next) {
+                    if self.max_len.is_some_and(|max_len| path.len() >= max_len) {
+                        *closed = true;
+                    } else {
+                        blocked.insert(next);
+                        path.push(next);
+                        frames.push((next, successors(next), false));
+                    }
+                }
+                continue;
+            }
+
+            let (id, closed) = (*id, *closed);
+            if closed {
+                let mut unblocks = vec![id];
+                while let Some(id) = unblocks.pop() {
+                    if blocked.remove(id) {
+                        unblocks.extend(blockers.remove(id).into_iter().flatten());
+                    }
+                }
+            } else {
+                for next in successors(id) {
+                    blockers.entry(next).or_default().insert(id);
+                }
+            }
+
+            path.pop();
+            frames.pop();
+            if let Some((_, _, parent)) = frames.last_mut() {
+                *parent |= closed;
+            }
+        }
+    }
+}
+
+impl Graph {
+    /// Enumerate the elementary cycles of this `Graph`, i.e., those without repeated nodes,
+    /// as the ids of nodes along each cycle, in the order of its edges.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_len` - The most nodes of each cycle to enumerate, or unlimited if `None`
+    pub fn cycles(&self, max_len: Option<usize>) -> Cycles<'_> {
+        let mut components = HashMap::new();
+        for (i, component) in self.strong_components().into_iter().enumerate() {
+            let single = component[0];
+            if component.len() > 1 || self.fwdmap[single].contains(single) {
+                components.extend(component.into_iter().map(|id| (id, i)));
+            }
+        }
+        let mut starts: Vec<&NodeId> = components.keys().copied().collect();
+        starts.sort_unstable();
+
+        Cycles { graph: self, starts, components, next: 0, max_len, pending: VecDeque::new() }
+    }
+
+    /// Strongly connected components of this `Graph` by Tarjan's algorithm,
+    /// as the ids of nodes in each
+    fn strong_components(&self) -> Vec<Vec<&NodeId>> {
+        let mut indices: HashMap<&NodeId, usize> = HashMap::new();
+        let mut lowlinks: HashMap<&NodeId, usize> = HashMap::new();
+        let mut stack: Vec<&NodeId> = Vec::new();
+        let mut on_stack: HashSet<&NodeId> = HashSet::new();
+        let mut components = Vec::new();
+
+        for node in &self.nodes {
+            if indices.contains_key(&node.id) {
+                continue;
+            }
+
+            // each frame holds a node being visited and its successors to visit
+            let mut frames: Vec<(&NodeId, Vec<&NodeId>)> = Vec::new();
+            let mut entering = Some(&node.id);
+            loop {
+                if let Some(id) = entering.take() {
+                    let index = indices.len();
+                    indices.insert(id, index);
+                    lowlinks.insert(id, index);
+                    stack.push(id);
+                    on_stack.insert(id);
+                    frames.push((id, self.fwdmap[id].iter().collect()));
+                }
+                let Some((id, nexts)) = frames.last_mut() else {
+                    break;
+                };
+
+                let id = *id;
+                if let Some(next) = nexts.pop() {
+                    if !indices.contains_key(next) {
+                        entering = Some(next);
+                    } else if on_stack.contains(next) {
+                        let lowlink = lowlinks[id].min(indices[next]);
+                        lowlinks.insert(id, lowlink);
+                    }
+                    continue;
+                }
+
+                frames.pop();
+                if let Some((parent, _)) = frames.last() {
+                    let lowlink = lowlinks[*parent].min(lowlinks[id]);
+                    lowlinks.insert(*parent, lowlink);
+                }
+                if lowlinks[id] == indices[id] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack.remove(member);
+                        component.push(member);
+                        if member == id {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+
+    /// Ids of nodes in the strongly connected component of `start`,
+    /// among nodes for which `within` holds
+    fn strong_component<'a, F>(&'a self, start: &'a NodeId, within: F) -> HashSet<&'a NodeId>
+    where
+        F: Fn(&NodeId) -> bool,
+    {
+        let reach = |direction| {
+            let mut visited = HashSet::from([start]);
+            let mut stack = vec![start];
+            while let Some(id) = stack.pop() {
+                for next in self.adjacent(id, direction) {
+                    if within(next) && visited.insert(next) {
+                        stack.push(next);
+                    }
+                }
+            }
+            visited
+        };
+
+        let forward = reach(Direction::Forward);
+        let backward = reach(Direction::Backward);
+
+        forward.intersection(&backward).copied().collect()
+    }
+
+    /// Search for a cycle in this `Graph`.
+    ///
+    /// Nodes are searched in the order of their ids, so the same cycle is found every time.
+    ///
+    /// # Returns
+    ///
+    /// `None` if this graph is acyclic,
+    /// otherwise `Some` with the ids of nodes along a cycle, in the order of its edges,
+    /// where the last node has an edge back to the first.
+    pub fn find_cycle(&self) -> Option<Vec<&NodeId>> {
+        let mut starts: Vec<&NodeId> = self.nodes.iter().map(|node| &node.id).collect();
+        starts.sort_unstable();
+
+        let mut marks: HashMap<&NodeId, Mark> = HashMap::new();
+        for start in starts {
+            if marks.contains_key(start) {
+                continue;
+            }
+
+            let mut path: Vec<&NodeId> = vec![start];
+            let mut nexts: Vec<Vec<&NodeId>> = vec![self.sorted_successors(start)];
+            marks.insert(start, Mark::Active);
+
+            while let Some(successors) = nexts.last_mut() {
+                let Some(next) = successors.pop() else {
+                    marks.insert(path.pop().unwrap(), Mark::Done);
+                    nexts.pop();
+                    continue;
+                };
+
+                match marks.get(next) {
+                    Some(Mark::Active) => {
+                        let begin = path.iter().position(|&id| id == next).unwrap();
+                        return Some(path.split_off(begin));
+                    }
+                    Some(Mark::Done) => {}
+                    None => {
+                        marks.insert(next, Mark::Active);
+                        path.push(next);
+                        nexts.push(self.sorted_successors(next));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Search for a cycle in this `Graph`, the same as `Graph::find_cycle`.
+    ///
+    /// # Returns
+    ///
+    /// `None` if this graph is acyclic,
+    /// otherwise `Some` with the edges along a cycle, in order.
+    /// Of parallel edges, the one with the least ports is taken.
+    pub fn find_cycle_edges(&self) -> Option<Vec<&Edge>> {
+        let cycle = self.find_cycle()?;
+
+        let edges = (0..cycle.len())
+            .map(|i| {
+                let (from, to) = (cycle[i], cycle[(i + 1) % cycle.len()]);
+                self.edges
+                    .iter()
+                    .filter(|edge| &edge.id.from == from && &edge.id.to == to)
+                    .min_by_key(|edge| (&edge.id.tailport, &edge.id.headport))
+                    .unwrap()
+            })
+            .collect();
+
+        Some(edges)
+    }
+
+    /// Choose edges whose removal makes this `Graph` acyclic, keeping the choice small with the
+    /// greedy heuristic of Eades, Lin, and Smyth. Self-loops are always chosen.
+    ///
+    /// # Returns
+    ///
+    /// The ids of chosen edges, empty if this graph is already acyclic.
+    pub fn feedback_arc_set(&self) -> HashSet<&EdgeId> {
+        if self.is_acyclic() {
+            return HashSet::new();
+        }
+
+        let positions: HashMap<&NodeId, usize> =
+            self.greedy_order().into_iter().enumerate().map(|(i, id)| (id, i)).collect();
+
+        self.edges
+            .par_iter()
+            .filter(|edge| positions[&edge.id.from] >= positions[&edge.id.to])
+            .map(|edge| &edge.id)
+            .collect()
+    }
+
+    /// Remove the edges chosen by `Graph::feedback_arc_set`, making this `Graph` acyclic.
+    ///
+    /// # Returns
+    ///
+    /// The removed edges.
+    pub fn break_cycles(&mut self) -> Vec<Edge> {
+        let ids: Vec<EdgeId> = self.feedback_arc_set().into_iter().cloned().collect();
+        let removed: Vec<Edge> = ids.iter().map(|id| self.detach_edge(id).unwrap()).collect();
+
+        // other edges may connect the same nodes through different ports
+        let pairs: HashSet<(&NodeId, &NodeId)> = ids.iter().map(|id| (&id.from, &id.to)).collect();
+        let remaining: HashSet<(&NodeId, &NodeId)> = self
+            .edges
+            .par_iter()
+            .map(|edge| (&edge.id.from, &edge.id.to))
+            .filter(|pair| pairs.contains(pair))
+            .collect();
+        for (from, to) in pairs.difference(&remaining) {
+            self.fwdmap.get_mut(*from).unwrap().remove(*to);
+            self.bwdmap.get_mut(*to).unwrap().remove(*from);
+        }
+
+        for id in ids {
+            self.subscribers.notify(GraphChange::EdgeRemoved(id));
+        }
+
+        removed
+    }
+
+    /// Order nodes such that few edges point backwards, by repeatedly taking sinks to the back,
+    /// sources to the front, and otherwise the node with the most outgoing edges over incoming
+    fn greedy_order<'a>(&'a self) -> Vec<&'a NodeId> {
+        let mut remaining: BTreeSet<&NodeId> = self.nodes.iter().map(|node| &node.id).collect();
+        let degrees = |direction| -> HashMap<&NodeId, usize> {
+            let degree = |id| self.adjacent(id, direction).filter(|&other| other != id).count();
+            remaining.iter().map(|&id| (id, degree(id))).collect()
+        };
+        let mut outdegrees = degrees(Direction::Forward);
+        let mut indegrees = degrees(Direction::Backward);
+
+        let mut sinks: Vec<&NodeId> =
+            remaining.iter().rev().filter(|id| outdegrees[*id] == 0).copied().collect();
+        let mut sources: Vec<&NodeId> =
+            remaining.iter().rev().filter(|id| indegrees[*id] == 0).copied().collect();
+
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        loop {
+            // queued nodes may have been taken already from the other queue
+            let pop = |queue: &mut Vec<&'a NodeId>| {
+                std::iter::from_fn(|| queue.pop()).find(|id| remaining.contains(id))
+            };
+
+            let id = if let Some(sink) = pop(&mut sinks) {
+                back.push(sink);
+                sink
+            } else if let Some(source) = pop(&mut sources) {
+                front.push(source);
+                source
+            } else if let Some(&id) = remaining.iter().max_by(|a, b| {
+                let delta = |id| outdegrees[id] as isize - indegrees[id] as isize;
+                delta(*a).cmp(&delta(*b)).then(b.cmp(a))
+            }) {
+                front.push(id);
+                id
+            } else {
+                break;
+            };
+
+            remaining.remove(id);
+            for next in self.fwdmap[id].iter().filter(|next| remaining.contains(next)) {
+                let indegree = indegrees.get_mut(next).unwrap();
+                *indegree -= 1;
+                if *indegree == 0 {
+                    sources.push(next);
+                }
+            }
+            for prev in self.bwdmap[id].iter().filter(|prev| remaining.contains(prev)) {
+                let outdegree = outdegrees.get_mut(prev).unwrap();
+                *outdegree -= 1;
+                if *outdegree == 0 {
+                    sinks.push(prev);
+                }
+            }
+        }
+
+        front.extend(back.into_iter().rev());
+
+        front
+    }
+
+    /// A `DotGraphError::Cycle` of this graph, holding the cycle `Graph::find_cycle` finds
+    pub(crate) fn cycle_error(&self) -> DotGraphError {
+        let cycle = self.find_cycle().into_iter().flatten().cloned().collect();
+
+        DotGraphError::Cycle(self.id.clone(), cycle)
+    }
+
+    /// Successors of the node with `id`, in the reverse order of their ids to be popped
+    fn sorted_successors(&self, id: &NodeId) -> Vec<&NodeId> {
+        let mut successors: Vec<&NodeId> = self.fwdmap[id].iter().collect();
+        successors.sort_unstable_by(|a, b| b.cmp(a));
+
+        successors
+    }
+}
@@ -0,0 +1,302 @@
+use crate::{
+    attr::Attr,
+    edge::EdgeId,
+    graphs::graph::{Graph, GraphId},
+    node::NodeId,
+};
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::io::Write;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// An `AttrDiff` lists how the attributes of an element differ between two graphs.
+pub struct AttrDiff<'a> {
+    /// Attributes only in the new graph
+    added: Vec<&'a Attr>,
+    /// Attributes only in the old graph
+    removed: Vec<&'a Attr>,
+    /// Attributes in both graphs with different values, as old and new
+    changed: Vec<(&'a Attr, &'a Attr)>,
+}
+
+impl<'a> AttrDiff<'a> {
+    fn new(old: &'a HashSet<Attr>, new: &'a HashSet<Attr>) -> AttrDiff<'a> {
+        let mut added: Vec<&Attr> = new.iter().filter(|attr| !old.contains(*attr)).collect();
+        let mut removed: Vec<&Attr> = old.iter().filter(|attr| !new.contains(*attr)).collect();
+        let mut changed: Vec<(&Attr, &Attr)> = old
+            .iter()
+            .filter_map(|attr| new.get(attr).map(|other| (attr, other)))
+            .filter(|(old, new)| old.value != new.value || old.is_html != new.is_html)
+            .collect();
+        added.sort_unstable_by(|a, b| a.key.cmp(&b.key));
+        removed.sort_unstable_by(|a, b| a.key.cmp(&b.key));
+        changed.sort_unstable_by(|(a, _), (b, _)| a.key.cmp(&b.key));
+
+        AttrDiff { added, removed, changed }
+    }
+
+    pub fn added(&self) -> &[&'a Attr] {
+        &self.added
+    }
+
+    pub fn removed(&self) -> &[&'a Attr] {
+        &self.removed
+    }
+
+    pub fn changed(&self) -> &[(&'a Attr, &'a Attr)] {
+        &self.changed
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Changes in a line each, as `+key=value`, `-key`, or `key: old -> new`
+    fn summary(&self) -> String {
+        let added = self.added.iter().map(|attr| format!("+{}={}", attr.key, attr.value));
+        let removed = self.removed.iter().map(|attr| format!("-{}", attr.key));
+        let changed = self
+            .changed
+            .iter()
+            .map(|(old, new)| format!("{}: {} -> {}", old.key, old.value, new.value));
+
+        // escaped for dot, which breaks lines at `\n` in strings
+        added.chain(removed).chain(changed).collect::<Vec<_>>().join("\\n")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A `Changes` lists which subgraphs, nodes, or edges with ids of type `T` were added,
+/// removed, or changed in their attributes between two graphs, each sorted by id.
+pub struct Changes<'a, T> {
+    added: Vec<&'a T>,
+    removed: Vec<&'a T>,
+    changed: Vec<(&'a T, AttrDiff<'a>)>,
+}
+
+impl<'a, T> Changes<'a, T> {
+    pub fn added(&self) -> &[&'a T] {
+        &self.added
+    }
+
+    pub fn removed(&self) -> &[&'a T] {
+        &self.removed
+    }
+
+    /// Ids in both graphs, with how their attributes differ
+    pub fn changed(&self) -> &[(&'a T, AttrDiff<'a>)] {
+        &self.changed
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A `GraphDiff` describes how a new `Graph` differs from an old one, created by `Graph::diff`.
+///
+/// Elements are matched by their ids, so a renamed element is both removed and added.
+pub struct GraphDiff<'a> {
+    before: &'a Graph,
+    after: &'a Graph,
+    subgraphs: Changes<'a, GraphId>,
+    nodes: Changes<'a, NodeId>,
+    edges: Changes<'a, EdgeId>,
+}
+
+impl<'a> GraphDiff<'a> {
+    /// The old graph, compared from
+    pub fn before(&self) -> &'a Graph {
+        self.before
+    }
+
+    /// The new graph, compared to
+    pub fn after(&self) -> &'a Graph {
+        self.after
+    }
+
+    pub fn subgraphs(&self) -> &Changes<'a, GraphId> {
+        &self.subgraphs
+    }
+
+    pub fn nodes(&self) -> &Changes<'a, NodeId> {
+        &self.nodes
+    }
+
+    pub fn edges(&self) -> &Changes<'a, EdgeId> {
+        &self.edges
+    }
+
+    /// Whether the graphs have the same subgraphs, nodes, and edges with the same attributes
+    pub fn is_empty(&self) -> bool {
+        self.subgraphs.is_empty() && self.nodes.is_empty() && self.edges.is_empty()
+    }
+
+    /// Write the new graph to dot format, along with the elements removed from the old graph,
+    /// where added elements are colored green, removed ones red and dashed, and changed ones
+    /// orange with their attribute changes summarized in their `xlabel`.
+    pub fn to_dot<W>(&self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        self.merged().to_dot(writer)
+    }
+
+    /// The new graph with the removed elements put back where they were, if their parents
+    /// remain, or in the root subgraph otherwise, and every element styled by how it changed
+    fn merged(&self) -> Graph {
+        let (before, mut merged) = (self.before, self.after.clone());
+        let root = merged.id.clone();
+        let parent_in = |merged: &Graph, parent: Option<&GraphId>| {
+            parent.filter(|parent| merged.subgraphs.contains(*parent)).unwrap_or(&root).clone()
+        };
+
+        // parents go before their children
+        let mut subgraph_ids = self.subgraphs.removed.clone();
+        subgraph_ids.sort_by_cached_key(|id| before.subtree_depth(id).unwrap());
+        for id in subgraph_ids {
+            let parent = parent_in(&merged, before.subgraph_parent(id));
+            merged.add_subgraph(&parent, id.clone()).unwrap();
+            let attrs = before.search_subgraph(id).unwrap().attrs.clone();
+            merged.update_subgraph(id, |subgraph| subgraph.attrs = attrs);
+        }
+        for &id in &self.nodes.removed {
+            let parent = parent_in(&merged, before.parent_subgraph(id));
+            merged.add_node(before.search_node(id).unwrap().clone(), &parent).unwrap();
+        }
+        for &id in &self.edges.removed {
+            // ports are not validated again, as ports of remaining nodes may have changed
+            let parent = parent_in(&merged, before.edge_owner(id));
+            merged.update_subgraph(&parent, |subgraph| {
+                subgraph.edge_ids.insert(id.clone());
+            });
+            merged.owners.insert_edge(id, &parent);
+            merged.fwdmap.get_mut(&id.from).unwrap().insert(id.to.clone());
+            merged.bwdmap.get_mut(&id.to).unwrap().insert(id.from.clone());
+            merged.edges.insert(before.search_edge(id).unwrap().clone());
+        }
+
+        for (ids, color) in [(&self.subgraphs.added, ADDED), (&self.subgraphs.removed, REMOVED)] {
+            for &id in ids {
+                merged.update_subgraph(id, |subgraph| style(&mut subgraph.attrs, color, None));
+            }
+        }
+        for (id, attrs) in &self.subgraphs.changed {
+            let summary = attrs.summary();
+            merged
+                .update_subgraph(id, |subgraph| style(&mut subgraph.attrs, CHANGED, Some(summary)));
+        }
+
+        let styles = self.styles(&self.nodes);
+        merged.nodes = std::mem::take(&mut merged.nodes)
+            .into_iter()
+            .map(|mut node| {
+                if let Some((color, summary)) = styles.get(&node.id) {
+                    style(&mut node.attrs, color, summary.clone());
+                }
+                node
+            })
+            .collect();
+        let styles = self.styles(&self.edges);
+        merged.edges = std::mem::take(&mut merged.edges)
+            .into_iter()
+            .map(|mut edge| {
+                if let Some((color, summary)) = styles.get(&edge.id) {
+                    style(&mut edge.attrs, color, summary.clone());
+                }
+                edge
+            })
+            .collect();
+
+        merged
+    }
+
+    /// Color of each added, removed, or changed element, with a summary of changed attributes
+    fn styles<'b, T: Eq + Hash>(
+        &self,
+        changes: &'b Changes<'a, T>,
+    ) -> HashMap<&'b T, (&'static str, Option<String>)> {
+        let added = changes.added.iter().map(|&id| (id, (ADDED, None)));
+        let removed = changes.removed.iter().map(|&id| (id, (REMOVED, None)));
+        let changed =
+            changes.changed.iter().map(|(id, attrs)| (*id, (CHANGED, Some(attrs.summary()))));
+
+        added.chain(removed).chain(changed).collect()
+    }
+}
+
+/// Colors of added, removed, and changed elements in `GraphDiff::to_dot`
+const ADDED: &str = "green";
+const REMOVED: &str = "red";
+const CHANGED: &str = "orange";
+
+/// Color `attrs` of an element, dashing removed ones and labeling changed ones with `summary`
+fn style(attrs: &mut HashSet<Attr>, color: &str, summary: Option<String>) {
+    let mut set = |key: &str, value: String| {
+        attrs.replace(Attr::new(key.to_string(), value, false));
+    };
+
+    set("color", color.to_string());
+    set("fontcolor", color.to_string());
+    if color == REMOVED {
+        set("style", String::from("dashed"));
+    }
+    if let Some(summary) = summary {
+        set("xlabel", summary);
+    }
+}
+
+impl Graph {
+    /// Compare this `Graph` as the old graph to `other` as the new one.
+    ///
+    /// # Returns
+    ///
+    /// The subgraphs, nodes, and edges added, removed, and changed in their attributes.
+    pub fn diff<'a>(&'a self, other: &'a Graph) -> GraphDiff<'a> {
+        let subgraphs = changes(
+            self.subgraphs.iter().map(|subgraph| (&subgraph.id, &subgraph.attrs)),
+            other.subgraphs.iter().map(|subgraph| (&subgraph.id, &subgraph.attrs)),
+            |a, b| a.cmp(b),
+        );
+        let nodes = changes(
+            self.nodes.iter().map(|node| (&node.id, &node.attrs)),
+            other.nodes.iter().map(|node| (&node.id, &node.attrs)),
+            |a, b| a.cmp(b),
+        );
+        let edges = changes(
+            self.edges.iter().map(|edge| (&edge.id, &edge.attrs)),
+            other.edges.iter().map(|edge| (&edge.id, &edge.attrs)),
+            |a, b| a.sort_key().cmp(&b.sort_key()),
+        );
+
+        GraphDiff { before: self, after: other, subgraphs, nodes, edges }
+    }
+}
+
+/// Compare elements of the old and new graphs by their ids, sorting each list with `cmp`
+fn changes<'a, T, I, J, C>(olds: I, news: J, cmp: C) -> Changes<'a, T>
+where
+    T: Eq + Hash,
+    I: Iterator<Item = (&'a T, &'a HashSet<Attr>)>,
+    J: Iterator<Item = (&'a T, &'a HashSet<Attr>)>,
+    C: Fn(&T, &T) -> Ordering,
+{
+    let olds: HashMap<&T, &HashSet<Attr>> = olds.collect();
+    let news: HashMap<&T, &HashSet<Attr>> = news.collect();
+
+    let mut added: Vec<&T> = news.keys().filter(|id| !olds.contains_key(*id)).copied().collect();
+    let mut removed: Vec<&T> = olds.keys().filter(|id| !news.contains_key(*id)).copied().collect();
+    let mut changed: Vec<(&T, AttrDiff)> = olds
+        .iter()
+        .filter_map(|(&id, &old)| news.get(id).map(|&new| (id, AttrDiff::new(old, new))))
+        .filter(|(_, diff)| !diff.is_empty())
+        .collect();
+    added.sort_unstable_by(|a, b| cmp(a, b));
+    removed.sort_unstable_by(|a, b| cmp(a, b));
+    changed.sort_unstable_by(|(a, _), (b, _)| cmp(a, b));
+
+    Changes { added, removed, changed }
+}
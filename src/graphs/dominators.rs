@@ -0,0 +1,142 @@
+use crate::{
+    error::DotGraphError,
+    graphs::{graph::Graph, traversal::Direction},
+    node::NodeId,
+};
+
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone)]
+/// A `DominatorTree` relates each node reachable from a root to its immediate dominator,
+/// the closest node that every path from the root to it passes through.
+///
+/// Built by `Graph::dominators` following edges forward, and by `Graph::post_dominators`
+/// following edges backward from an exit, where dominators are post-dominators instead.
+pub struct DominatorTree<'a> {
+    /// Id of the node paths start from
+    root: &'a NodeId,
+    /// Immediate dominator of each reachable node, except the root
+    idoms: HashMap<&'a NodeId, &'a NodeId>,
+}
+
+impl<'a> DominatorTree<'a> {
+    pub fn root(&self) -> &'a NodeId {
+        self.root
+    }
+
+    /// Immediate dominator of the node with `id`,
+    /// `None` if it is the root or is not reachable from the root.
+    pub fn immediate_dominator(&self, id: &NodeId) -> Option<&'a NodeId> {
+        self.idoms.get(id).copied()
+    }
+
+    /// Dominators of the node with `id`, from itself up to the root,
+    /// `None` if it is not reachable from the root.
+    pub fn dominators(&self, id: &NodeId) -> Option<Vec<&'a NodeId>> {
+        let id = if id == self.root { self.root } else { *self.idoms.get_key_value(id)?.0 };
+
+        let mut dominators = vec![id];
+        while let Some(&idom) = self.idoms.get(dominators.last().unwrap()) {
+            dominators.push(idom);
+        }
+
+        Some(dominators)
+    }
+
+    /// Whether the node `a` dominates the node `b`, which is true if they are the same
+    /// reachable node and false if either is not reachable from the root.
+    pub fn dominates(&self, a: &NodeId, b: &NodeId) -> bool {
+        self.dominators(b).is_some_and(|dominators| dominators.contains(&a))
+    }
+}
+
+impl Graph {
+    /// Compute the dominators of nodes reachable from the node `entry`.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node with `entry`,
+    /// `Ok` with the dominator tree rooted at `entry` otherwise.
+    pub fn dominators(&self, entry: &NodeId) -> Result<DominatorTree<'_>, DotGraphError> {
+        self.dominator_tree(entry, Direction::Forward)
+    }
+
+    /// Compute the post-dominators of nodes from which the node `exit` is reachable,
+    /// i.e., the dominators on the reversed graph.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node with `exit`,
+    /// `Ok` with the post-dominator tree rooted at `exit` otherwise.
+    pub fn post_dominators(&self, exit: &NodeId) -> Result<DominatorTree<'_>, DotGraphError> {
+        self.dominator_tree(exit, Direction::Backward)
+    }
+
+    /// Dominators from `root` along `direction`, by the iterative algorithm of
+    /// Cooper, Harvey, and Kennedy
+    fn dominator_tree<'a>(
+        &'a self,
+        root: &NodeId,
+        direction: Direction,
+    ) -> Result<DominatorTree<'a>, DotGraphError> {
+        let root = self.node_id(root)?;
+        let reverse = match direction {
+            Direction::Forward => Direction::Backward,
+            _ => Direction::Forward,
+        };
+
+        // number nodes in postorder of a depth-first search
+        let mut postorder: Vec<&NodeId> = Vec::new();
+        let mut numbers: HashMap<&NodeId, usize> = HashMap::new();
+        let mut visited = HashSet::from([root]);
+        let mut stack = vec![(root, self.sorted_adjacent(root, direction))];
+        while let Some((id, nexts)) = stack.last_mut() {
+            if let Some(next) = nexts.pop() {
+                if visited.insert(next) {
+                    stack.push((next, self.sorted_adjacent(next, direction)));
+                }
+            } else {
+                numbers.insert(id, postorder.len());
+                postorder.push(id);
+                stack.pop();
+            }
+        }
+
+        let mut idoms: HashMap<&NodeId, &NodeId> = HashMap::from([(root, root)]);
+        let intersect = |idoms: &HashMap<&'a NodeId, &'a NodeId>, mut a: &'a NodeId, mut b| {
+            while a != b {
+                while numbers[a] < numbers[b] {
+                    a = idoms[a];
+                }
+                while numbers[b] < numbers[a] {
+                    b = idoms[b];
+                }
+            }
+            a
+        };
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &id in postorder.iter().rev().skip(1) {
+                let mut preds = self.adjacent(id, reverse).filter(|pred| idoms.contains_key(pred));
+                let first = preds.next().unwrap();
+                let idom = preds.fold(first, |idom, pred| intersect(&idoms, idom, pred));
+                if idoms.insert(id, idom) != Some(idom) {
+                    changed = true;
+                }
+            }
+        }
+        idoms.remove(root);
+
+        Ok(DominatorTree { root, idoms })
+    }
+
+    /// Adjacent nodes of the node with `id`, in the reverse order of their ids to be popped
+    fn sorted_adjacent(&self, id: &NodeId, direction: Direction) -> Vec<&NodeId> {
+        let mut adjacent: Vec<&NodeId> = self.adjacent(id, direction).collect();
+        adjacent.sort_unstable_by(|a, b| b.cmp(a));
+
+        adjacent
+    }
+}
@@ -0,0 +1,412 @@
+use crate::{
+    attr::Attr,
+    edge::{Edge, EdgeId},
+    error::DotGraphError,
+    graphs::{
+        graph::{Graph, GraphId},
+        igraph::IGraph,
+        subgraph::SubGraph,
+    },
+    node::{Node, NodeId},
+    utils,
+};
+
+use std::collections::{HashMap, HashSet};
+
+use dot_structures as ast;
+
+impl From<&Graph> for ast::Graph {
+    /// Convert a `Graph` into a `dot_structures` digraph, with subgraphs, nodes, edges,
+    /// and attributes sorted by their ids or keys.
+    fn from(graph: &Graph) -> ast::Graph {
+        let root = graph.search_subgraph(&graph.id).unwrap();
+
+        ast::Graph::DiGraph {
+            id: to_ast_id(&graph.id),
+            strict: false,
+            stmts: to_stmts(graph, root),
+        }
+    }
+}
+
+impl TryFrom<&ast::Graph> for Graph {
+    type Error = DotGraphError;
+
+    /// Convert a `dot_structures` digraph into a `Graph`, resolving statements as cgraph does
+    /// when parsing dot, e.g., default attributes apply to nodes and edges declared after them
+    /// within the same subgraph, and edges from or to a subgraph connect all its nodes.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if the given graph is not a digraph, or if a subgraph is nested within itself or
+    /// under two different parents, `Ok` with the converted graph otherwise.
+    fn try_from(graph: &ast::Graph) -> Result<Graph, DotGraphError> {
+        let (id, stmts) = match graph {
+            ast::Graph::DiGraph { id, stmts, .. } => (from_ast_id(id), stmts),
+            ast::Graph::Graph { id, .. } => {
+                return Err(DotGraphError::UndirectedGraph(from_ast_id(id)))
+            }
+        };
+
+        let mut builder = Builder::default();
+        builder.scope(&id, 0, stmts, Defaults::default())?;
+
+        builder.build(id)
+    }
+}
+
+/// Convert statements held by `subgraph` into `dot_structures` statements
+fn to_stmts(graph: &Graph, subgraph: &SubGraph) -> Vec<ast::Stmt> {
+    let mut stmts = Vec::new();
+
+    if !subgraph.attrs.is_empty() {
+        let attrs = to_ast_attrs(&subgraph.attrs);
+        stmts.push(ast::Stmt::GAttribute(ast::GraphAttributes::Graph(attrs)));
+    }
+
+    let mut subgraph_ids: Vec<&GraphId> = subgraph.subgraph_ids.iter().collect();
+    subgraph_ids.sort_unstable();
+    for id in subgraph_ids {
+        let child = graph.search_subgraph(id).unwrap();
+        let subgraph = ast::Subgraph { id: to_ast_id(id), stmts: to_stmts(graph, child) };
+        stmts.push(ast::Stmt::Subgraph(subgraph));
+    }
+
+    let mut node_ids: Vec<&NodeId> = subgraph.node_ids.iter().collect();
+    node_ids.sort_unstable();
+    for id in node_ids {
+        let node = graph.search_node(id).unwrap();
+        let attributes = to_ast_attrs(&node.attrs);
+        stmts.push(ast::Stmt::Node(ast::Node { id: ast::NodeId(to_ast_id(id), None), attributes }));
+    }
+
+    let mut edge_ids: Vec<&EdgeId> = subgraph.edge_ids.iter().collect();
+    edge_ids.sort_unstable_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+    for id in edge_ids {
+        let edge = graph.search_edge(id).unwrap();
+        let from = ast::Vertex::N(ast::NodeId(
+            to_ast_id(&id.from),
+            id.tailport.as_deref().map(to_ast_port),
+        ));
+        let to =
+            ast::Vertex::N(ast::NodeId(to_ast_id(&id.to), id.headport.as_deref().map(to_ast_port)));
+        let attributes = to_ast_attrs(&edge.attrs);
+        stmts.push(ast::Stmt::Edge(ast::Edge { ty: ast::EdgeTy::Pair(from, to), attributes }));
+    }
+
+    stmts
+}
+
+/// Convert `attrs` into `dot_structures` attributes sorted by their keys
+fn to_ast_attrs(attrs: &HashSet<Attr>) -> Vec<ast::Attribute> {
+    let mut attrs: Vec<&Attr> = attrs.iter().collect();
+    attrs.sort_unstable_by(|a, b| a.key.cmp(&b.key));
+
+    attrs
+        .into_iter()
+        .map(|attr| {
+            let value = if attr.is_html {
+                ast::Id::Html(format!("<{}>", attr.value))
+            } else {
+                to_ast_id(&attr.value)
+            };
+            ast::Attribute(to_ast_id(&attr.key), value)
+        })
+        .collect()
+}
+
+/// Convert `id` into a plain `dot_structures` id if it needs no quotes, or an escaped one
+fn to_ast_id(id: &str) -> ast::Id {
+    if utils::is_plain_id(id) {
+        ast::Id::Plain(id.to_string())
+    } else {
+        ast::Id::Escaped(utils::quote(id))
+    }
+}
+
+/// Convert a port, as in `p` or `p:n`, into a `dot_structures` port
+fn to_ast_port(port: &str) -> ast::Port {
+    match port.split_once(':') {
+        Some((id, compass)) => ast::Port(Some(to_ast_id(id)), Some(compass.to_string())),
+        None => ast::Port(Some(to_ast_id(port)), None),
+    }
+}
+
+/// Take the string of a `dot_structures` id, without quotes or angle brackets
+fn from_ast_id(id: &ast::Id) -> String {
+    match id {
+        ast::Id::Html(html) => html
+            .strip_prefix('<')
+            .and_then(|html| html.strip_suffix('>'))
+            .unwrap_or(html)
+            .to_string(),
+        ast::Id::Escaped(escaped) => {
+            let unquoted = escaped.strip_prefix('"').and_then(|s| s.strip_suffix('"'));
+            unquoted.unwrap_or(escaped).replace("\\\"", "\"")
+        }
+        ast::Id::Plain(id) | ast::Id::Anonymous(id) => id.clone(),
+    }
+}
+
+/// Take the string of a `dot_structures` port, as in `p` or `p:n`
+fn from_ast_port(port: &ast::Port) -> Option<String> {
+    match port {
+        ast::Port(Some(id), Some(compass)) => Some(format!("{}:{compass}", from_ast_id(id))),
+        ast::Port(Some(id), None) => Some(from_ast_id(id)),
+        ast::Port(None, Some(compass)) => Some(compass.clone()),
+        ast::Port(None, None) => None,
+    }
+}
+
+/// Convert `dot_structures` attributes, taking html-like values as such
+fn from_ast_attrs(attributes: &[ast::Attribute]) -> Vec<Attr> {
+    attributes
+        .iter()
+        .map(|ast::Attribute(key, value)| {
+            let is_html = matches!(value, ast::Id::Html(_));
+            Attr::new(from_ast_id(key), from_ast_id(value), is_html)
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Default)]
+/// Default attributes of nodes and edges declared within a subgraph
+struct Defaults {
+    nodes: HashSet<Attr>,
+    edges: HashSet<Attr>,
+}
+
+#[derive(Debug, Default)]
+/// Subgraphs, nodes, and edges collected from `dot_structures` statements
+struct Builder {
+    /// Attributes and children of each subgraph
+    subgraphs: HashMap<GraphId, (HashSet<Attr>, Vec<GraphId>)>,
+    /// The parent of each subgraph but the root
+    parents: HashMap<GraphId, GraphId>,
+    nodes: HashMap<NodeId, HashSet<Attr>>,
+    edges: HashMap<EdgeId, HashSet<Attr>>,
+    /// The deepest subgraph each node is declared in, with its depth, or the first declared
+    /// of those as deep. Like `parser`, which takes nodes of subgraphs before their parents',
+    /// a node goes to the innermost subgraph declaring it, while siblings are taken in the
+    /// order they are declared rather than the order cgraph happens to keep them in.
+    node_owners: HashMap<NodeId, (usize, GraphId)>,
+    /// The deepest subgraph each edge is declared in, with its depth
+    edge_owners: HashMap<EdgeId, (usize, GraphId)>,
+    /// Number of anonymous subgraphs named so far
+    anonymous: usize,
+}
+
+impl Builder {
+    /// Collect `stmts` of the subgraph `id` at `depth`
+    ///
+    /// # Returns
+    ///
+    /// `Err` if a subgraph within is nested within itself or under two different parents,
+    /// `Ok` with ids of nodes declared within the subgraph, including its descendants, otherwise.
+    fn scope(
+        &mut self,
+        id: &GraphId,
+        depth: usize,
+        stmts: &[ast::Stmt],
+        mut defaults: Defaults,
+    ) -> Result<Vec<NodeId>, DotGraphError> {
+        self.subgraphs.entry(id.clone()).or_default();
+
+        let mut declared = Vec::new();
+        for stmt in stmts {
+            match stmt {
+                ast::Stmt::Node(node) => {
+                    let node_id = from_ast_id(&node.id.0);
+                    self.node(&node_id, id, depth, &defaults, from_ast_attrs(&node.attributes));
+                    declared.push(node_id);
+                }
+                ast::Stmt::Attribute(ast::Attribute(key, value)) => {
+                    let is_html = matches!(value, ast::Id::Html(_));
+                    let attr = Attr::new(from_ast_id(key), from_ast_id(value), is_html);
+                    self.subgraphs.get_mut(id).unwrap().0.replace(attr);
+                }
+                ast::Stmt::GAttribute(ast::GraphAttributes::Graph(attrs)) => {
+                    self.subgraphs.get_mut(id).unwrap().0.extend(from_ast_attrs(attrs));
+                }
+                ast::Stmt::GAttribute(ast::GraphAttributes::Node(attrs)) => {
+                    from_ast_attrs(attrs).into_iter().for_each(|attr| {
+                        defaults.nodes.replace(attr);
+                    });
+                }
+                ast::Stmt::GAttribute(ast::GraphAttributes::Edge(attrs)) => {
+                    from_ast_attrs(attrs).into_iter().for_each(|attr| {
+                        defaults.edges.replace(attr);
+                    });
+                }
+                ast::Stmt::Subgraph(subgraph) => {
+                    declared.extend(self.subgraph(id, depth, subgraph, &defaults)?);
+                }
+                ast::Stmt::Edge(edge) => {
+                    let vertices: Vec<&ast::Vertex> = match &edge.ty {
+                        ast::EdgeTy::Pair(from, to) => vec![from, to],
+                        ast::EdgeTy::Chain(vertices) => vertices.iter().collect(),
+                    };
+
+                    let mut ends: Vec<Vec<(NodeId, Option<String>)>> = Vec::new();
+                    for vertex in vertices {
+                        let nodes = match vertex {
+                            ast::Vertex::N(ast::NodeId(node_id, port)) => {
+                                let node_id = from_ast_id(node_id);
+                                self.node(&node_id, id, depth, &defaults, Vec::new());
+                                vec![(node_id, port.as_ref().and_then(from_ast_port))]
+                            }
+                            ast::Vertex::S(subgraph) => {
+                                let nodes = self.subgraph(id, depth, subgraph, &defaults)?;
+                                nodes.into_iter().map(|node_id| (node_id, None)).collect()
+                            }
+                        };
+                        declared.extend(nodes.iter().map(|(node_id, _)| node_id.clone()));
+                        ends.push(nodes);
+                    }
+
+                    let attrs = from_ast_attrs(&edge.attributes);
+                    for pair in ends.windows(2) {
+                        for (from, tailport) in &pair[0] {
+                            for (to, headport) in &pair[1] {
+                                let edge_id = EdgeId::new(
+                                    from.clone(),
+                                    tailport.clone(),
+                                    to.clone(),
+                                    headport.clone(),
+                                );
+                                self.edge(edge_id, id, depth, &defaults, attrs.clone());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(declared)
+    }
+
+    /// Collect the `subgraph` nested in `parent` at `depth`, inheriting `defaults`
+    ///
+    /// # Returns
+    ///
+    /// `Err` if the subgraph is `parent` or one of its ancestors, or is already nested under
+    /// another parent, `Ok` with ids of nodes declared within the subgraph otherwise.
+    fn subgraph(
+        &mut self,
+        parent: &GraphId,
+        depth: usize,
+        subgraph: &ast::Subgraph,
+        defaults: &Defaults,
+    ) -> Result<Vec<NodeId>, DotGraphError> {
+        let id = match &subgraph.id {
+            ast::Id::Anonymous(id) if id.is_empty() => {
+                self.anonymous += 1;
+                format!("%{}", self.anonymous)
+            }
+            id => from_ast_id(id),
+        };
+
+        let path = self.path(parent);
+        if path.contains(&&id) {
+            return Err(DotGraphError::CyclicSubGraph(id, parent.clone()));
+        }
+        match self.parents.get(&id) {
+            Some(owner) if owner != parent => {
+                let root = path[path.len() - 1].clone();
+                return Err(DotGraphError::DuplicateSubGraph(id, root));
+            }
+            Some(_) => {}
+            None => {
+                self.parents.insert(id.clone(), parent.clone());
+                self.subgraphs.entry(parent.clone()).or_default().1.push(id.clone());
+            }
+        }
+
+        self.scope(&id, depth + 1, &subgraph.stmts, defaults.clone())
+    }
+
+    /// Ids of the subgraph `id` and its ancestors, up to the root
+    fn path<'a>(&'a self, id: &'a GraphId) -> Vec<&'a GraphId> {
+        let mut path = vec![id];
+        while let Some(parent) = self.parents.get(path[path.len() - 1]) {
+            path.push(parent);
+        }
+
+        path
+    }
+
+    /// Declare the node `id` within `subgraph` at `depth`, updating its `attrs`
+    fn node(
+        &mut self,
+        id: &NodeId,
+        subgraph: &GraphId,
+        depth: usize,
+        defaults: &Defaults,
+        attrs: Vec<Attr>,
+    ) {
+        let node = self.nodes.entry(id.clone()).or_insert_with(|| defaults.nodes.clone());
+        attrs.into_iter().for_each(|attr| {
+            node.replace(attr);
+        });
+
+        let owner = self.node_owners.entry(id.clone()).or_insert((depth, subgraph.clone()));
+        if owner.0 < depth {
+            *owner = (depth, subgraph.clone());
+        }
+    }
+
+    /// Declare the edge `id` within `subgraph` at `depth`, updating its `attrs`
+    fn edge(
+        &mut self,
+        id: EdgeId,
+        subgraph: &GraphId,
+        depth: usize,
+        defaults: &Defaults,
+        attrs: Vec<Attr>,
+    ) {
+        let edge = self.edges.entry(id.clone()).or_insert_with(|| defaults.edges.clone());
+        attrs.into_iter().for_each(|attr| {
+            edge.replace(attr);
+        });
+
+        let owner = self.edge_owners.entry(id).or_insert((depth, subgraph.clone()));
+        if owner.0 < depth {
+            *owner = (depth, subgraph.clone());
+        }
+    }
+
+    /// Build the `Graph` named `id` out of what has been collected
+    fn build(self, id: GraphId) -> Result<Graph, DotGraphError> {
+        let nodes: HashSet<Node> =
+            self.nodes.into_iter().map(|(id, attrs)| Node::new(id, attrs)).collect();
+        let edges: HashSet<Edge> =
+            self.edges.into_iter().map(|(id, attrs)| Edge::new(id, attrs)).collect();
+
+        let mut members: HashMap<&GraphId, (HashSet<Node>, HashSet<Edge>)> = HashMap::new();
+        for node in &nodes {
+            let (_, owner) = &self.node_owners[&node.id];
+            members.entry(owner).or_default().0.insert(node.clone());
+        }
+        for edge in &edges {
+            let (_, owner) = &self.edge_owners[&edge.id];
+            members.entry(owner).or_default().1.insert(edge.clone());
+        }
+
+        let root = igraph(&id, &self.subgraphs, &mut members);
+
+        Graph::new(id, root, nodes, edges)
+    }
+}
+
+/// Assemble the `IGraph` of the subgraph `id` and its descendants
+fn igraph(
+    id: &GraphId,
+    subgraphs: &HashMap<GraphId, (HashSet<Attr>, Vec<GraphId>)>,
+    members: &mut HashMap<&GraphId, (HashSet<Node>, HashSet<Edge>)>,
+) -> IGraph {
+    let (attrs, children) = &subgraphs[id];
+    let igraphs = children.iter().map(|child| igraph(child, subgraphs, members)).collect();
+    let (nodes, edges) = members.remove(id).unwrap_or_default();
+
+    IGraph::new(id.clone(), igraphs, nodes, edges, attrs.clone())
+}
@@ -0,0 +1,69 @@
+use crate::{edge::EdgeId, graphs::graph::Graph, node::NodeId};
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A `Duplicates` reports edges of a `Graph` that go between the same ends, created by
+/// `Graph::find_duplicates`.
+///
+/// Repeated declarations of a node, or of an edge without ports, are merged while parsing,
+/// where later attributes override earlier ones, so they cannot be told apart afterwards.
+pub struct Duplicates<'a> {
+    /// Groups of edges from and to the same nodes through different ports
+    parallel: Vec<Vec<&'a EdgeId>>,
+    /// Indices of the groups in `parallel` whose edges do not agree on their attributes
+    conflicting: Vec<usize>,
+}
+
+impl<'a> Duplicates<'a> {
+    /// Groups of edges from and to the same nodes, through different ports,
+    /// sorted by their ends
+    pub fn parallel_edges(&self) -> &[Vec<&'a EdgeId>] {
+        &self.parallel
+    }
+
+    /// Groups of parallel edges whose attributes differ in keys or values
+    pub fn conflicting_edges(&self) -> impl Iterator<Item = &[&'a EdgeId]> {
+        self.conflicting.iter().map(|&index| self.parallel[index].as_slice())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parallel.is_empty()
+    }
+}
+
+impl Graph {
+    /// Search for edges of this `Graph` going between the same ends, as suspicious redundancies
+    /// for linting tools to flag.
+    pub fn find_duplicates(&self) -> Duplicates<'_> {
+        let mut groups: HashMap<(&NodeId, &NodeId), Vec<&EdgeId>> = HashMap::new();
+        for edge in &self.edges {
+            groups.entry((&edge.id.from, &edge.id.to)).or_default().push(&edge.id);
+        }
+
+        let mut parallel: Vec<Vec<&EdgeId>> =
+            groups.into_values().filter(|group| group.len() > 1).collect();
+        for group in &mut parallel {
+            group.sort_unstable_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+        }
+        parallel.sort_unstable_by(|a, b| a[0].sort_key().cmp(&b[0].sort_key()));
+
+        let conflicting = parallel
+            .iter()
+            .enumerate()
+            .filter(|(_, group)| {
+                let attrs = &self.search_edge(group[0]).unwrap().attrs;
+                group[1..].iter().any(|id| {
+                    let others = &self.search_edge(id).unwrap().attrs;
+                    others.len() != attrs.len()
+                        || others.iter().any(|other| {
+                            attrs.get(other).is_none_or(|attr| attr.value != other.value)
+                        })
+                })
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        Duplicates { parallel, conflicting }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{edge::EdgeId, graphs::graph::Graph, node::NodeId};
+
+use std::collections::HashMap;
+
+impl Graph {
+    /// Search for an Eulerian path of this `Graph`, traversing every edge exactly once
+    /// along its direction.
+    ///
+    /// The path is a circuit if every node has as many incoming edges as outgoing ones,
+    /// in which case it starts from the least node id with outgoing edges. Otherwise, it starts
+    /// from the node with one more outgoing edge than incoming ones. Hierholzer's algorithm
+    /// follows outgoing edges in the order of their ends, so that the path is deterministic.
+    ///
+    /// # Returns
+    ///
+    /// `None` if there is no such path, e.g., if edges are not all connected,
+    /// otherwise `Some` with the edges in the order of traversal.
+    pub fn eulerian_path(&self) -> Option<Vec<&EdgeId>> {
+        let mut outs: HashMap<&NodeId, Vec<&EdgeId>> = HashMap::new();
+        let mut balances: HashMap<&NodeId, isize> = HashMap::new();
+        for edge in &self.edges {
+            outs.entry(&edge.id.from).or_default().push(&edge.id);
+            *balances.entry(&edge.id.from).or_default() += 1;
+            *balances.entry(&edge.id.to).or_default() -= 1;
+        }
+        for edges in outs.values_mut() {
+            // sorted in reverse to pop the least edge first
+            edges.sort_unstable_by(|a, b| b.sort_key().cmp(&a.sort_key()));
+        }
+
+        let mut starts = Vec::new();
+        let mut ends = 0;
+        for (&id, &balance) in &balances {
+            match balance {
+                0 => {}
+                1 => starts.push(id),
+                -1 => ends += 1,
+                _ => return None,
+            }
+        }
+        let start = match (starts.as_slice(), ends) {
+            ([], 0) => match outs.keys().min() {
+                Some(&start) => start,
+                None => return Some(Vec::new()),
+            },
+            ([start], 1) => *start,
+            _ => return None,
+        };
+
+        let mut path = Vec::with_capacity(self.edges.len());
+        let mut stack: Vec<(&NodeId, Option<&EdgeId>)> = vec![(start, None)];
+        while let Some(&(id, via)) = stack.last() {
+            match outs.get_mut(id).and_then(Vec::pop) {
+                Some(edge) => stack.push((&edge.to, Some(edge))),
+                None => {
+                    stack.pop();
+                    path.extend(via);
+                }
+            }
+        }
+        path.reverse();
+
+        (path.len() == self.edges.len()).then_some(path)
+    }
+}
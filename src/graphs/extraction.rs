@@ -0,0 +1,95 @@
+use crate::{
+    edge::EdgeId,
+    graphs::graph::{Graph, GraphId},
+    node::NodeId,
+};
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+#[derive(Debug, Clone)]
+/// A `Mapping` relates ids of a derived graph to ids of the original graph.
+///
+/// A derived id may stand for several original ids (e.g. a node synthesized by collapsing
+/// a cluster), while an original id is represented by at most one derived id.
+pub struct Mapping<T: Eq + Hash> {
+    /// Derived id to the original ids it stands for
+    originals: HashMap<T, HashSet<T>>,
+    /// Original id to the derived id representing it
+    deriveds: HashMap<T, T>,
+}
+
+impl<T: Eq + Hash + Clone> Mapping<T> {
+    pub(crate) fn new() -> Mapping<T> {
+        Mapping { originals: HashMap::new(), deriveds: HashMap::new() }
+    }
+
+    pub(crate) fn identity<'a, I>(ids: I) -> Mapping<T>
+    where
+        I: IntoIterator<Item = &'a T>,
+        T: 'a,
+    {
+        let mut mapping = Mapping::new();
+        for id in ids {
+            mapping.insert(id.clone(), id.clone());
+        }
+
+        mapping
+    }
+
+    /// Record that `derived` represents `original`
+    pub(crate) fn insert(&mut self, derived: T, original: T) {
+        self.originals.entry(derived.clone()).or_default().insert(original.clone());
+        self.deriveds.insert(original, derived);
+    }
+
+    /// Original ids represented by the `derived` id
+    pub fn originals(&self, derived: &T) -> Option<&HashSet<T>> {
+        self.originals.get(derived)
+    }
+
+    /// Derived id representing the `original` id
+    pub fn derived(&self, original: &T) -> Option<&T> {
+        self.deriveds.get(original)
+    }
+}
+
+#[derive(Debug, Clone)]
+/// An `ExtractionMap` relates subgraphs, nodes, and edges in a derived `Graph`
+/// back to those in the `Graph` it was extracted from.
+pub struct ExtractionMap {
+    subgraphs: Mapping<GraphId>,
+    nodes: Mapping<NodeId>,
+    edges: Mapping<EdgeId>,
+}
+
+impl ExtractionMap {
+    pub(crate) fn new(
+        subgraphs: Mapping<GraphId>,
+        nodes: Mapping<NodeId>,
+        edges: Mapping<EdgeId>,
+    ) -> ExtractionMap {
+        ExtractionMap { subgraphs, nodes, edges }
+    }
+
+    /// Map every element of `derived` onto itself
+    pub(crate) fn identity(derived: &Graph) -> ExtractionMap {
+        let subgraphs = Mapping::identity(derived.subgraphs());
+        let nodes = Mapping::identity(derived.nodes());
+        let edges = Mapping::identity(derived.edges());
+
+        ExtractionMap { subgraphs, nodes, edges }
+    }
+
+    pub fn subgraphs(&self) -> &Mapping<GraphId> {
+        &self.subgraphs
+    }
+
+    pub fn nodes(&self) -> &Mapping<NodeId> {
+        &self.nodes
+    }
+
+    pub fn edges(&self) -> &Mapping<EdgeId> {
+        &self.edges
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{graphs::graph::Graph, node::NodeId};
+
+use std::collections::{HashMap, HashSet};
+
+use rayon::prelude::*;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Hash `words` by 64-bit FNV-1a, which is stable across platforms and releases
+fn fnv<I>(words: I) -> u64
+where
+    I: IntoIterator<Item = u64>,
+{
+    words
+        .into_iter()
+        .flat_map(u64::to_le_bytes)
+        .fold(FNV_OFFSET, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME))
+}
+
+impl Graph {
+    /// Fingerprint the structure of this `Graph` by Weisfeiler-Lehman refinement, which labels
+    /// every node by hashing its label with the sorted labels of its predecessors and
+    /// successors, until the number of distinct labels stops growing.
+    ///
+    /// Ids, attributes, ports, and subgraphs are ignored, so graphs that are isomorphic have the
+    /// same hash, while graphs with the same hash are very likely, though not surely, isomorphic.
+    ///
+    /// # Returns
+    ///
+    /// A hash that is stable across runs, platforms, and the order of nodes.
+    pub fn structural_hash(&self) -> u64 {
+        let mut labels: HashMap<&NodeId, u64> =
+            self.nodes.par_iter().map(|node| (&node.id, 0)).collect();
+        let mut history = vec![self.nodes.len() as u64, self.edges.len() as u64];
+        let mut distinct = 1;
+
+        loop {
+            let refined: HashMap<&NodeId, u64> = labels
+                .par_iter()
+                .map(|(&id, &label)| {
+                    let mut froms: Vec<u64> =
+                        self.bwdmap[id].iter().map(|from| labels[from]).collect();
+                    let mut tos: Vec<u64> = self.fwdmap[id].iter().map(|to| labels[to]).collect();
+                    froms.sort_unstable();
+                    tos.sort_unstable();
+
+                    let words = [label, froms.len() as u64]
+                        .into_iter()
+                        .chain(froms)
+                        .chain([tos.len() as u64])
+                        .chain(tos);
+                    (id, fnv(words))
+                })
+                .collect();
+
+            let mut round: Vec<u64> = refined.values().copied().collect();
+            round.sort_unstable();
+            history.push(fnv(round));
+
+            let refined_distinct = refined.values().collect::<HashSet<_>>().len();
+            labels = refined;
+            if refined_distinct <= distinct {
+                break;
+            }
+            distinct = refined_distinct;
+        }
+
+        fnv(history)
+    }
+}
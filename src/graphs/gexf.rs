@@ -0,0 +1,139 @@
+use crate::{attr::Attr, edge::EdgeId, graphs::graph::Graph, node::NodeId, utils};
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::{Result, Write};
+
+impl Graph {
+    /// Write the graph to GEXF 1.3, the native format of Gephi.
+    ///
+    /// Every attribute key of nodes, and of edges, is declared as a string attribute, numbered
+    /// in the order of the keys. Nodes are labeled by their `label` attributes, falling back to
+    /// their ids, and are positioned by their `pos` attributes, as in `"x,y"`, if they have ones.
+    /// Edges are numbered in the order of their ends and weighted by their `weight` attributes.
+    pub fn to_gexf<W>(&self, writer: &mut W) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        let mut nodes: Vec<&NodeId> = self.nodes.iter().map(|node| &node.id).collect();
+        nodes.sort_unstable();
+        let mut edges: Vec<&EdgeId> = self.edges.iter().map(|edge| &edge.id).collect();
+        edges.sort_unstable_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+
+        let node_keys = attr_keys(self.nodes.iter().map(|node| &node.attrs));
+        let edge_keys = attr_keys(self.edges.iter().map(|edge| &edge.attrs));
+
+        writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+        writeln!(
+            writer,
+            "<gexf xmlns=\"http://gexf.net/1.3\" xmlns:viz=\"http://gexf.net/1.3/viz\" version=\"1.3\">"
+        )?;
+        writeln!(writer, "  <graph mode=\"static\" defaultedgetype=\"directed\">")?;
+        write_attributes("node", &node_keys, writer)?;
+        write_attributes("edge", &edge_keys, writer)?;
+
+        writeln!(writer, "    <nodes>")?;
+        for id in nodes {
+            let node = self.search_node(id).unwrap();
+            let label = node.attrs.get("label").map_or(id, |attr| &attr.value);
+            let position = node.attrs.get("pos").and_then(|attr| position(&attr.value));
+
+            let (id, label) = (utils::xml_escape(id), utils::xml_escape(label));
+            write!(writer, "      <node id=\"{id}\" label=\"{label}\"")?;
+            if node.attrs.is_empty() {
+                writeln!(writer, "/>")?;
+                continue;
+            }
+            writeln!(writer, ">")?;
+            write_attvalues(&node.attrs, &node_keys, writer)?;
+            if let Some((x, y)) = position {
+                writeln!(writer, "        <viz:position x=\"{x}\" y=\"{y}\" z=\"0\"/>")?;
+            }
+            writeln!(writer, "      </node>")?;
+        }
+        writeln!(writer, "    </nodes>")?;
+
+        writeln!(writer, "    <edges>")?;
+        for (i, id) in edges.into_iter().enumerate() {
+            let edge = self.search_edge(id).unwrap();
+
+            let (from, to) = (utils::xml_escape(&id.from), utils::xml_escape(&id.to));
+            write!(writer, "      <edge id=\"{i}\" source=\"{from}\" target=\"{to}\"")?;
+            if let Some(weight) = edge.weight() {
+                write!(writer, " weight=\"{weight}\"")?;
+            }
+            if edge.attrs.is_empty() {
+                writeln!(writer, "/>")?;
+                continue;
+            }
+            writeln!(writer, ">")?;
+            write_attvalues(&edge.attrs, &edge_keys, writer)?;
+            writeln!(writer, "      </edge>")?;
+        }
+        writeln!(writer, "    </edges>")?;
+
+        writeln!(writer, "  </graph>")?;
+        writeln!(writer, "</gexf>")?;
+
+        Ok(())
+    }
+}
+
+/// Number the keys of all `attrs` in their order
+fn attr_keys<'a>(attrs: impl Iterator<Item = &'a HashSet<Attr>>) -> HashMap<&'a str, usize> {
+    let keys: BTreeSet<&str> = attrs.flatten().map(|attr| attr.key.as_str()).collect();
+
+    keys.into_iter().enumerate().map(|(i, key)| (key, i)).collect()
+}
+
+/// Parse the first two coordinates of a `pos` attribute, e.g. `"1.5,2"` or `"1.5,2!"`
+fn position(pos: &str) -> Option<(f64, f64)> {
+    let mut coordinates = pos.trim_end_matches('!').split(',');
+    let x = coordinates.next()?.trim().parse().ok()?;
+    let y = coordinates.next()?.trim().parse().ok()?;
+
+    Some((x, y))
+}
+
+/// Write declarations of string attributes of `class`, numbered by `keys`
+fn write_attributes<W>(class: &str, keys: &HashMap<&str, usize>, writer: &mut W) -> Result<()>
+where
+    W: Write + ?Sized,
+{
+    if keys.is_empty() {
+        return Ok(());
+    }
+
+    let mut keys: Vec<(&str, usize)> = keys.iter().map(|(&key, &i)| (key, i)).collect();
+    keys.sort_unstable_by_key(|&(_, i)| i);
+
+    writeln!(writer, "    <attributes class=\"{class}\">")?;
+    for (key, i) in keys {
+        let key = utils::xml_escape(key);
+        writeln!(writer, "      <attribute id=\"{i}\" title=\"{key}\" type=\"string\"/>")?;
+    }
+    writeln!(writer, "    </attributes>")?;
+
+    Ok(())
+}
+
+/// Write values of `attrs` for the attributes numbered by `keys`
+fn write_attvalues<W>(
+    attrs: &HashSet<Attr>,
+    keys: &HashMap<&str, usize>,
+    writer: &mut W,
+) -> Result<()>
+where
+    W: Write + ?Sized,
+{
+    let mut attrs: Vec<&Attr> = attrs.iter().collect();
+    attrs.sort_unstable_by(|a, b| a.key.cmp(&b.key));
+
+    writeln!(writer, "        <attvalues>")?;
+    for attr in attrs {
+        let (i, value) = (keys[attr.key.as_str()], utils::xml_escape(&attr.value));
+        writeln!(writer, "          <attvalue for=\"{i}\" value=\"{value}\"/>")?;
+    }
+    writeln!(writer, "        </attvalues>")?;
+
+    Ok(())
+}
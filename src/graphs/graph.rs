@@ -1,19 +1,37 @@
 use crate::{
+    attr::Attr,
     edge::{Edge, EdgeId},
     error::DotGraphError,
-    graphs::{igraph::IGraph, subgraph::SubGraph},
-    node::{Node, NodeId},
+    graphs::{
+        change::{self, GraphChange, Subscribers},
+        extraction::{ExtractionMap, Mapping},
+        igraph::IGraph,
+        owners::OwnerIndex,
+        payload::Payloads,
+        search::PrefixIndex,
+        subgraph::SubGraph,
+        transaction::Transaction,
+        traversal::Direction,
+        write::{AttrAction, DotElement, DotWriteOptions},
+    },
+    layer,
+    node::{port, Node, NodeId},
+    parser, utils,
 };
 
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
 use std::io::Write;
+use std::ops::RangeBounds;
+use std::path::Path;
+use std::str::FromStr;
 
 use rayon::prelude::*;
 
 pub type GraphId = String;
 
 type SubTree = HashMap<GraphId, HashSet<GraphId>>;
-type EdgeMap = HashMap<NodeId, HashSet<NodeId>>;
+pub(crate) type EdgeMap = HashMap<NodeId, HashSet<NodeId>>;
 
 #[derive(Debug, Clone)]
 /// A `Graph` serves as a database of the entire dot graph.
@@ -24,24 +42,35 @@ type EdgeMap = HashMap<NodeId, HashSet<NodeId>>;
 /// **All subgraphs, nodes, and edges in the graph MUST HAVE UNIQUE IDS.**
 pub struct Graph {
     /// Name of the entire graph
-    id: GraphId,
+    pub(crate) id: GraphId,
 
     /// All subgraphs in the graph (subgraph ids must be unique)
-    subgraphs: HashSet<SubGraph>,
+    pub(crate) subgraphs: HashSet<SubGraph>,
 
     /// All nodes in the graph (node ids must be unique)
-    nodes: HashSet<Node>,
+    pub(crate) nodes: HashSet<Node>,
 
     /// All edges in the graph (edge ids must be unique)
-    edges: HashSet<Edge>,
+    pub(crate) edges: HashSet<Edge>,
 
     /// Parent-children relationships of the subgraphs
-    subtree: SubTree,
+    pub(crate) subtree: SubTree,
 
     /// Map constructed from edges, in forward direction
-    fwdmap: EdgeMap,
+    pub(crate) fwdmap: EdgeMap,
     /// Map constructed from edges, in backward direction
-    bwdmap: EdgeMap,
+    pub(crate) bwdmap: EdgeMap,
+
+    /// Subgraphs holding each element, built on demand
+    pub(crate) owners: OwnerIndex,
+    /// Sorted node ids, built on demand
+    pub(crate) prefixes: PrefixIndex,
+
+    /// User data attached to nodes and edges
+    pub(crate) payloads: Payloads,
+
+    /// Hooks notified of mutations
+    pub(crate) subscribers: Subscribers,
 }
 
 impl Graph {
@@ -58,7 +87,24 @@ impl Graph {
 
         let subtree = make_subtree(&subgraphs);
 
-        let graph = Graph { id, subgraphs, nodes, edges, subtree, fwdmap, bwdmap };
+        let owners = OwnerIndex::default();
+        let prefixes = PrefixIndex::default();
+        let payloads = Payloads::default();
+        let subscribers = Subscribers::default();
+
+        let graph = Graph {
+            id,
+            subgraphs,
+            nodes,
+            edges,
+            subtree,
+            fwdmap,
+            bwdmap,
+            owners,
+            prefixes,
+            payloads,
+            subscribers,
+        };
 
         Ok(graph)
     }
@@ -67,6 +113,12 @@ impl Graph {
         &self.id
     }
 
+    /// Attributes of the graph itself, e.g. `rankdir`, as held by its root subgraph,
+    /// empty if the root was dropped for holding nothing.
+    pub fn attrs(&self) -> HashSet<&Attr> {
+        self.subgraphs.get(&self.id).map_or(HashSet::new(), |root| root.attrs.iter().collect())
+    }
+
     pub fn subgraphs(&self) -> HashSet<&GraphId> {
         self.subgraphs.par_iter().map(|subgraph| &subgraph.id).collect()
     }
@@ -75,10 +127,30 @@ impl Graph {
         self.nodes.par_iter().map(|node| &node.id).collect()
     }
 
+    /// Iterate over all nodes in the graph, in arbitrary order
+    pub fn iter_nodes(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.iter()
+    }
+
+    /// Iterate over all nodes in the graph in parallel
+    pub fn par_iter_nodes(&self) -> impl ParallelIterator<Item = &Node> {
+        self.nodes.par_iter()
+    }
+
     pub fn edges(&self) -> HashSet<&EdgeId> {
         self.edges.par_iter().map(|edge| &edge.id).collect()
     }
 
+    /// Iterate over all edges in the graph, in arbitrary order
+    pub fn iter_edges(&self) -> impl Iterator<Item = &Edge> {
+        self.edges.iter()
+    }
+
+    /// Iterate over all edges in the graph in parallel
+    pub fn par_iter_edges(&self) -> impl ParallelIterator<Item = &Edge> {
+        self.edges.par_iter()
+    }
+
     pub fn is_empty(&self) -> bool {
         self.subgraphs.is_empty() && self.nodes.is_empty() && self.edges.is_empty()
     }
@@ -134,7 +206,51 @@ impl Graph {
         if sorted.len() == self.nodes.len() {
             Ok(sorted)
         } else {
-            Err(DotGraphError::Cycle(self.id.clone()))
+            Err(self.cycle_error())
+        }
+    }
+
+    /// Group nodes in this `Graph` into topological generations, where the first generation
+    /// holds nodes without predecessors, and every other node belongs to the generation after
+    /// the latest one among its predecessors.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if this graph has a cycle, otherwise
+    /// `Ok` with a vector of generations, each a sorted vector of node ids.
+    pub fn topo_generations(&self) -> Result<Vec<Vec<&NodeId>>, DotGraphError> {
+        let mut indegrees: HashMap<&NodeId, usize> =
+            self.bwdmap.par_iter().map(|(to, froms)| (to, froms.len())).collect();
+
+        let mut generation: Vec<&NodeId> = indegrees
+            .par_iter()
+            .filter_map(|(&id, &indegree)| (indegree == 0).then_some(id))
+            .collect();
+
+        let mut generations = Vec::new();
+        let mut sorted = 0;
+        while !generation.is_empty() {
+            generation.sort_unstable();
+
+            let mut next = Vec::new();
+            for &id in &generation {
+                for to in &self.fwdmap[id] {
+                    let indegree = indegrees.get_mut(to).unwrap();
+                    *indegree -= 1;
+                    if *indegree == 0 {
+                        next.push(to);
+                    }
+                }
+            }
+
+            sorted += generation.len();
+            generations.push(std::mem::replace(&mut generation, next));
+        }
+
+        if sorted == self.nodes.len() {
+            Ok(generations)
+        } else {
+            Err(self.cycle_error())
         }
     }
 
@@ -155,28 +271,49 @@ impl Graph {
     /// `Err` if there is no node named `center`,
     /// `Ok` with neighbors `Graph` otherwise.
     pub fn neighbors(&self, center: &NodeId, depth: usize) -> Result<Graph, DotGraphError> {
-        if self.nodes.get(center).is_some() {
-            let mut visited = HashSet::new();
-            let mut frontier: VecDeque<(&NodeId, usize)> = VecDeque::new();
-            frontier.push_back((center, 0));
-
-            while let Some((id, vicinity)) = frontier.pop_front() {
-                if vicinity > depth || !visited.insert(id) {
-                    continue;
-                }
-
-                let tos = self.fwdmap.get(id).unwrap();
-                let froms = self.bwdmap.get(id).unwrap();
-                let nexts = tos.union(froms);
+        self.neighbors_directed(center, depth, Direction::Both)
+    }
 
-                frontier.extend(nexts.map(|next| (next, vicinity + 1)));
-            }
+    /// Rank the successors and predecessors of `center` by a numeric edge attribute.
+    ///
+    /// # Arguments
+    ///
+    /// * `center` - Id of the center node
+    /// * `key` - Key of the edge attribute to rank by, e.g. `weight`
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node named `center`,
+    /// `Ok` with neighbor ids and their weights in descending order of weight otherwise.
+    /// The weight of a neighbor sums over all edges between it and `center`,
+    /// where edges with a missing or non-numeric attribute weigh zero.
+    pub fn ranked_neighbors(
+        &self,
+        center: &NodeId,
+        key: &str,
+    ) -> Result<Vec<(&NodeId, f64)>, DotGraphError> {
+        if !self.nodes.contains(center) {
+            return Err(DotGraphError::NoSuchNode(center.clone(), self.id.clone()));
+        }
 
-            let visited: Vec<&NodeId> = visited.into_par_iter().collect();
-            Ok(self.extract(&visited))
-        } else {
-            Err(DotGraphError::NoSuchNode(center.clone(), self.id.clone()))
+        let mut weights: HashMap<&NodeId, f64> = HashMap::new();
+        for edge in &self.edges {
+            let neighbor = if edge.id.from == *center {
+                &edge.id.to
+            } else if edge.id.to == *center {
+                &edge.id.from
+            } else {
+                continue;
+            };
+
+            let weight = edge.attrs.get(key).and_then(|attr| attr.value.parse().ok());
+            *weights.entry(neighbor).or_default() += weight.unwrap_or(0.0);
         }
+
+        let mut ranked: Vec<(&NodeId, f64)> = weights.into_iter().collect();
+        ranked.sort_unstable_by(|(a, x), (b, y)| y.total_cmp(x).then_with(|| a.cmp(b)));
+
+        Ok(ranked)
     }
 
     /// Constructs a new `Graph`, with a new `root`.
@@ -196,7 +333,347 @@ impl Graph {
         )
     }
 
+    /// Same as [`Graph::filter`], but also returns an `ExtractionMap`
+    /// relating the elements of the new `Graph` to those of this one.
+    pub fn filter_with_map(&self, node_ids: &[&NodeId]) -> (Graph, ExtractionMap) {
+        with_identity_map(self.extract(node_ids))
+    }
+
+    /// Same as [`Graph::neighbors`], but also returns an `ExtractionMap`
+    /// relating the elements of the new `Graph` to those of this one.
+    pub fn neighbors_with_map(
+        &self,
+        center: &NodeId,
+        depth: usize,
+    ) -> Result<(Graph, ExtractionMap), DotGraphError> {
+        self.neighbors(center, depth).map(with_identity_map)
+    }
+
+    /// Same as [`Graph::subgraph`], but also returns an `ExtractionMap`
+    /// relating the elements of the new `Graph` to those of this one.
+    pub fn subgraph_with_map(
+        &self,
+        root: &GraphId,
+    ) -> Result<(Graph, ExtractionMap), DotGraphError> {
+        self.subgraph(root).map(with_identity_map)
+    }
+
+    /// Layers declared in the `layers` attribute of the graph, in order.
+    pub fn layers(&self) -> Vec<&str> {
+        let layersep = self.graph_attr("layersep").map_or(layer::DEFAULT_LAYERSEP, |a| &a.value);
+
+        self.graph_attr("layers").map_or(Vec::new(), |a| layer::parse_layers(&a.value, layersep))
+    }
+
+    /// Constructs a new `Graph`, containing only the nodes and edges in layer `name`.
+    ///
+    /// Nodes and edges without a `layer` attribute belong to all layers.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if `name` is not declared in `layers`,
+    /// `Ok` with the layer's `Graph` otherwise.
+    pub fn select_layer(&self, name: &str) -> Result<Graph, DotGraphError> {
+        let layers = self.layers();
+        if !layers.contains(&name) {
+            return Err(DotGraphError::NoSuchLayer(name.to_string(), self.id.clone()));
+        }
+
+        let layersep = self.graph_attr("layersep").map_or(layer::DEFAULT_LAYERSEP, |a| &a.value);
+        let layerlistsep =
+            self.graph_attr("layerlistsep").map_or(layer::DEFAULT_LAYERLISTSEP, |a| &a.value);
+        let in_layer = |attrs: &HashSet<Attr>| {
+            attrs.get("layer").is_none_or(|attr| {
+                layer::parse_ranges(&attr.value, layersep, layerlistsep)
+                    .iter()
+                    .any(|range| range.contains(&layers, name))
+            })
+        };
+
+        let node_ids: Vec<&NodeId> = self
+            .nodes
+            .par_iter()
+            .filter(|node| in_layer(&node.attrs))
+            .map(|node| &node.id)
+            .collect();
+
+        Ok(self.extract_where(&node_ids, |edge| in_layer(&edge.attrs)))
+    }
+
+    /// Constructs a new `Graph`, containing only the nodes whose numeric attribute `key`
+    /// falls in `range`, e.g. a timestamp or a step index.
+    ///
+    /// Nodes without a numeric `key` are left out, while edges among the remaining nodes
+    /// are left out only if their own numeric `key` falls out of `range`.
+    pub fn window<R>(&self, key: &str, range: R) -> Graph
+    where
+        R: RangeBounds<f64> + Sync,
+    {
+        let value = |attrs: &HashSet<Attr>| -> Option<f64> {
+            attrs.get(key).and_then(|attr| attr.value.parse().ok())
+        };
+
+        let node_ids: Vec<&NodeId> = self
+            .nodes
+            .par_iter()
+            .filter(|node| value(&node.attrs).is_some_and(|value| range.contains(&value)))
+            .map(|node| &node.id)
+            .collect();
+
+        self.extract_where(&node_ids, |edge| {
+            value(&edge.attrs).is_none_or(|value| range.contains(&value))
+        })
+    }
+
+    /// Constructs a new `Graph`, containing only the nodes whose attribute `key` is `value`,
+    /// e.g. all nodes with `shape=record`.
+    pub fn filter_by_attr(&self, key: &str, value: &str) -> Graph {
+        self.filter_nodes(|node| node.attrs.get(key).is_some_and(|attr| attr.value == value))
+    }
+
+    /// Constructs a new `Graph`, containing only the nodes whose attribute `key`
+    /// contains `pattern`, e.g. all nodes labelled with `conv`.
+    pub fn filter_by_attr_contains(&self, key: &str, pattern: &str) -> Graph {
+        self.filter_nodes(|node| {
+            node.attrs.get(key).is_some_and(|attr| attr.value.contains(pattern))
+        })
+    }
+
+    /// Constructs a new `Graph`, containing only the nodes satisfying `keep`
+    /// and the edges among them.
+    pub fn filter_nodes<P>(&self, keep: P) -> Graph
+    where
+        P: Fn(&Node) -> bool + Sync,
+    {
+        let node_ids: Vec<&NodeId> =
+            self.nodes.par_iter().filter(|node| keep(node)).map(|node| &node.id).collect();
+
+        self.extract(&node_ids)
+    }
+
+    /// Constructs a new `Graph`, containing all nodes but only the edges satisfying `keep`.
+    pub fn filter_edges<P>(&self, keep: P) -> Graph
+    where
+        P: Fn(&Edge) -> bool,
+    {
+        let node_ids: Vec<&NodeId> = self.nodes.par_iter().map(|node| &node.id).collect();
+
+        self.extract_where(&node_ids, keep)
+    }
+
+    /// Constructs a new `Graph`, containing the nodes and edges of this graph that are not
+    /// in `other`.
+    ///
+    /// Ends of the remaining edges are kept as well, even when they are in `other`.
+    pub fn difference(&self, other: &Graph) -> Graph {
+        let mut node_ids: HashSet<&NodeId> = self
+            .nodes
+            .par_iter()
+            .filter(|node| !other.nodes.contains(&node.id))
+            .map(|node| &node.id)
+            .collect();
+        for edge in &self.edges {
+            if !other.edges.contains(&edge.id) {
+                node_ids.insert(&edge.id.from);
+                node_ids.insert(&edge.id.to);
+            }
+        }
+        let node_ids: Vec<&NodeId> = node_ids.into_iter().collect();
+
+        self.extract_where(&node_ids, |edge| !other.edges.contains(&edge.id))
+    }
+
+    /// Constructs a new `Graph`, containing the nodes and edges that are in exactly one of
+    /// this graph and `other`, as a union of both differences.
+    pub fn symmetric_difference(&self, other: &Graph) -> Graph {
+        self.difference(other).merge(&other.difference(self))
+    }
+
+    /// Constructs a new `Graph` from `view`, a graph extracted from this one, where each
+    /// connected region of nodes left out of `view` is replaced by a placeholder summary
+    /// node, e.g. `… 1,243 nodes hidden`, connected to the nodes the region attached to.
+    pub fn summarize(&self, view: &Graph) -> Graph {
+        self.summarize_with_map(view).0
+    }
+
+    /// Same as [`Graph::summarize`], but also returns an `ExtractionMap`
+    /// relating the elements of the new `Graph` to those of this one,
+    /// where each summary node maps to the nodes of its region.
+    pub fn summarize_with_map(&self, view: &Graph) -> (Graph, ExtractionMap) {
+        let mut graph = view.clone();
+        if !graph.subgraphs.contains(&graph.id) {
+            let id = graph.id.clone();
+            let root = SubGraph {
+                id: id.clone(),
+                subgraph_ids: HashSet::new(),
+                node_ids: HashSet::new(),
+                edge_ids: HashSet::new(),
+                attrs: HashSet::new(),
+            };
+            graph.subgraphs.insert(root);
+            graph.subtree.insert(id, HashSet::new());
+            graph.owners.invalidate();
+        }
+
+        let mut nodes = Mapping::identity(view.nodes());
+        let mut edges = Mapping::identity(view.edges());
+
+        let mut hidden: Vec<&NodeId> = self
+            .nodes
+            .par_iter()
+            .map(|node| &node.id)
+            .filter(|id| !view.nodes.contains(*id))
+            .collect();
+        hidden.sort_unstable();
+
+        // group hidden nodes into regions connected regardless of edge direction
+        let mut regions: Vec<Vec<&NodeId>> = Vec::new();
+        let mut visited: HashSet<&NodeId> = HashSet::new();
+        for &start in &hidden {
+            if !visited.insert(start) {
+                continue;
+            }
+
+            let mut region = Vec::new();
+            let mut frontier = vec![start];
+            while let Some(id) = frontier.pop() {
+                region.push(id);
+
+                let nexts = self.fwdmap[id].iter().chain(self.bwdmap[id].iter());
+                for next in nexts {
+                    if !view.nodes.contains(next) && visited.insert(next) {
+                        frontier.push(next);
+                    }
+                }
+            }
+            regions.push(region);
+        }
+
+        let root = graph.id.clone();
+        let mut summaries: HashMap<&NodeId, NodeId> = HashMap::new();
+        let mut count = 0;
+        for region in regions {
+            let summary = loop {
+                let id = format!("summary_{count}");
+                count += 1;
+                if !self.nodes.contains(&id) && !view.nodes.contains(&id) {
+                    break id;
+                }
+            };
+
+            let hidden = if region.len() == 1 { "node" } else { "nodes" };
+            let label = format!("… {} {hidden} hidden", utils::thousands(region.len()));
+            let attrs = HashSet::from([
+                Attr::new(String::from("label"), label, false),
+                Attr::new(String::from("shape"), String::from("box"), false),
+                Attr::new(String::from("style"), String::from("dashed"), false),
+            ]);
+            graph.add_node(Node::new(summary.clone(), attrs), &root).unwrap();
+
+            for id in region {
+                nodes.insert(summary.clone(), id.clone());
+                summaries.insert(id, summary.clone());
+            }
+        }
+
+        for edge in &self.edges {
+            let from = &edge.id.from;
+            let to = &edge.id.to;
+            let id = match (summaries.get(from), summaries.get(to)) {
+                (Some(summary), None) if view.nodes.contains(to) => {
+                    EdgeId::new(summary.clone(), None, to.clone(), None)
+                }
+                (None, Some(summary)) if view.nodes.contains(from) => {
+                    EdgeId::new(from.clone(), None, summary.clone(), None)
+                }
+                _ => continue,
+            };
+
+            if !graph.edges.contains(&id) {
+                let attrs = HashSet::from([Attr::new(
+                    String::from("style"),
+                    String::from("dashed"),
+                    false,
+                )]);
+                graph.add_edge(Edge::new(id.clone(), attrs), &root).unwrap();
+            }
+            edges.insert(id, edge.id.clone());
+        }
+
+        let subgraphs = Mapping::identity(view.subgraphs());
+        let map = ExtractionMap::new(subgraphs, nodes, edges);
+
+        (graph, map)
+    }
+
+    /// Constructs a new `Graph` holding the elements of both this graph and `other`,
+    /// where the root of `other` is merged into the root of this graph.
+    /// Elements in both keep the attributes and the parent subgraph of this graph.
+    fn merge(&self, other: &Graph) -> Graph {
+        let mut graph = self.clone();
+
+        let rename = |id: &GraphId| if *id == other.id { self.id.clone() } else { id.clone() };
+        for subgraph in &other.subgraphs {
+            let id = rename(&subgraph.id);
+            // subgraphs in both stay under their parent in this graph
+            let subgraph_ids: HashSet<GraphId> = subgraph
+                .subgraph_ids
+                .iter()
+                .map(rename)
+                .filter(|child| self.subgraph_parent(child).is_none_or(|parent| *parent == id))
+                .collect();
+            let node_ids: HashSet<NodeId> =
+                subgraph.node_ids.iter().filter(|id| !self.nodes.contains(*id)).cloned().collect();
+            let edge_ids: HashSet<EdgeId> =
+                subgraph.edge_ids.iter().filter(|id| !self.edges.contains(*id)).cloned().collect();
+
+            let merged = match graph.subgraphs.take(&id) {
+                Some(mut merged) => {
+                    merged.subgraph_ids.extend(subgraph_ids);
+                    merged.node_ids.extend(node_ids);
+                    merged.edge_ids.extend(edge_ids);
+                    merged
+                }
+                None => {
+                    let attrs = subgraph.attrs.clone();
+                    SubGraph { id, subgraph_ids, node_ids, edge_ids, attrs }
+                }
+            };
+            graph.subgraphs.insert(merged);
+        }
+
+        for node in &other.nodes {
+            if !graph.nodes.contains(&node.id) {
+                graph.nodes.insert(node.clone());
+            }
+        }
+        for edge in &other.edges {
+            if !graph.edges.contains(&edge.id) {
+                graph.edges.insert(edge.clone());
+            }
+        }
+
+        graph.subtree = make_subtree(&graph.subgraphs);
+        (graph.fwdmap, graph.bwdmap) = make_edge_maps(&graph.nodes, &graph.edges);
+        graph.owners.invalidate();
+        graph.prefixes.invalidate();
+
+        graph
+    }
+
+    fn graph_attr(&self, key: &str) -> Option<&Attr> {
+        self.subgraphs.get(&self.id).and_then(|root| root.attrs.get(key))
+    }
+
     fn extract(&self, node_ids: &[&NodeId]) -> Graph {
+        self.extract_where(node_ids, |_| true)
+    }
+
+    /// Constructs a new `Graph` with the given nodes and the edges among them satisfying `keep`.
+    fn extract_where<P>(&self, node_ids: &[&NodeId], keep: P) -> Graph
+    where
+        P: Fn(&Edge) -> bool,
+    {
         let mut nodes = HashSet::new();
         for id in node_ids {
             if let Some(node) = self.search_node(id) {
@@ -210,7 +687,7 @@ impl Graph {
             let from = &edge.id.from;
             let to = &edge.id.to;
 
-            if node_ids.get(from).is_some() && node_ids.get(to).is_some() {
+            if node_ids.contains(from) && node_ids.contains(to) && keep(edge) {
                 edges.insert(edge.clone());
             }
         }
@@ -240,7 +717,24 @@ impl Graph {
 
         let subtree = make_subtree(&subgraphs);
 
-        Graph { id: self.id.clone(), subgraphs, nodes, edges, subtree, fwdmap, bwdmap }
+        let owners = OwnerIndex::default();
+        let prefixes = PrefixIndex::default();
+        let payloads = self.payloads.extract(&node_ids, &edge_ids);
+        let subscribers = Subscribers::default();
+
+        Graph {
+            id: self.id.clone(),
+            subgraphs,
+            nodes,
+            edges,
+            subtree,
+            fwdmap,
+            bwdmap,
+            owners,
+            prefixes,
+            payloads,
+            subscribers,
+        }
     }
 
     /// Search for a subgraph by `id`
@@ -353,18 +847,771 @@ impl Graph {
             })
     }
 
+    /// Retrieve all edges from the node with `id`, including parallel edges between
+    /// different ports.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node with `id`,
+    /// `Ok` with a vector of outgoing edges.
+    pub fn edges_from(&self, id: &NodeId) -> Result<Vec<&Edge>, DotGraphError> {
+        if !self.nodes.contains(id) {
+            return Err(DotGraphError::NoSuchNode(id.to_string(), self.id.clone()));
+        }
+
+        Ok(self.edges.par_iter().filter(|edge| edge.id.from == *id).collect())
+    }
+
+    /// Retrieve all edges to the node with `id`, including parallel edges between
+    /// different ports.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node with `id`,
+    /// `Ok` with a vector of incoming edges.
+    pub fn edges_to(&self, id: &NodeId) -> Result<Vec<&Edge>, DotGraphError> {
+        if !self.nodes.contains(id) {
+            return Err(DotGraphError::NoSuchNode(id.to_string(), self.id.clone()));
+        }
+
+        Ok(self.edges.par_iter().filter(|edge| edge.id.to == *id).collect())
+    }
+
+    /// Retrieve all nodes without predecessors.
+    pub fn sources(&self) -> HashSet<&NodeId> {
+        self.bwdmap.par_iter().filter_map(|(id, froms)| froms.is_empty().then_some(id)).collect()
+    }
+
+    /// Retrieve all nodes without successors.
+    pub fn sinks(&self) -> HashSet<&NodeId> {
+        self.fwdmap.par_iter().filter_map(|(id, tos)| tos.is_empty().then_some(id)).collect()
+    }
+
+    /// Add a new, empty subgraph `id` as a child of the subgraph `parent`.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no subgraph named `parent` or if `id` is already a subgraph,
+    /// `Ok` otherwise.
+    pub fn add_subgraph(&mut self, parent: &GraphId, id: GraphId) -> Result<(), DotGraphError> {
+        if self.subgraphs.contains(&id) {
+            return Err(DotGraphError::DuplicateSubGraph(id, self.id.clone()));
+        }
+
+        if !self.subgraphs.contains(parent) {
+            return Err(DotGraphError::NoSuchSubGraph(parent.to_string(), self.id.clone()));
+        }
+
+        self.update_subgraph(parent, |subgraph| {
+            subgraph.subgraph_ids.insert(id.clone());
+        });
+        self.subtree.get_mut(parent).unwrap().insert(id.clone());
+
+        let subgraph = SubGraph {
+            id: id.clone(),
+            subgraph_ids: HashSet::new(),
+            node_ids: HashSet::new(),
+            edge_ids: HashSet::new(),
+            attrs: HashSet::new(),
+        };
+        self.subgraphs.insert(subgraph);
+        self.subtree.insert(id.clone(), HashSet::new());
+        self.owners.insert_subgraph(&id, parent);
+        self.subscribers.notify(GraphChange::SubGraphAdded(id, parent.clone()));
+
+        Ok(())
+    }
+
+    /// Move the node with `id` from the subgraph that currently holds it to `target`.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node with `id` or no subgraph named `target`,
+    /// `Ok` otherwise.
+    pub fn move_node(&mut self, id: &NodeId, target: &GraphId) -> Result<(), DotGraphError> {
+        if !self.nodes.contains(id) {
+            return Err(DotGraphError::NoSuchNode(id.to_string(), self.id.clone()));
+        }
+        if !self.subgraphs.contains(target) {
+            return Err(DotGraphError::NoSuchSubGraph(target.to_string(), self.id.clone()));
+        }
+
+        if let Some(owner) = self.parent_subgraph(id).cloned() {
+            self.update_subgraph(&owner, |subgraph| {
+                subgraph.node_ids.remove(id);
+            });
+        }
+        self.update_subgraph(target, |subgraph| {
+            subgraph.node_ids.insert(id.clone());
+        });
+        self.owners.insert_node(id, target);
+        self.subscribers.notify(GraphChange::NodeMoved(id.clone(), target.clone()));
+
+        Ok(())
+    }
+
+    /// Detach the subgraph `child`, along with its descendants, nodes, and edges,
+    /// from its current parent and attach it under `parent`.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if either subgraph does not exist, if `child` is the root subgraph,
+    /// or if `parent` lies within `child`, `Ok` otherwise.
+    pub fn reparent_subgraph(
+        &mut self,
+        child: &GraphId,
+        parent: &GraphId,
+    ) -> Result<(), DotGraphError> {
+        for id in [child, parent] {
+            if !self.subgraphs.contains(id) {
+                return Err(DotGraphError::NoSuchSubGraph(id.to_string(), self.id.clone()));
+            }
+        }
+        if *child == self.id {
+            return Err(DotGraphError::RootSubGraph(child.to_string(), self.id.clone()));
+        }
+        if self.subgraph_descendants(child).contains(parent) {
+            return Err(DotGraphError::CyclicSubGraph(child.to_string(), parent.to_string()));
+        }
+
+        if let Some(owner) = self.subgraph_parent(child).cloned() {
+            self.subtree.get_mut(&owner).unwrap().remove(child);
+            self.update_subgraph(&owner, |subgraph| {
+                subgraph.subgraph_ids.remove(child);
+            });
+        }
+        self.subtree.get_mut(parent).unwrap().insert(child.clone());
+        self.update_subgraph(parent, |subgraph| {
+            subgraph.subgraph_ids.insert(child.clone());
+        });
+        self.owners.insert_subgraph(child, parent);
+        self.subscribers.notify(GraphChange::SubGraphMoved(child.clone(), parent.clone()));
+
+        Ok(())
+    }
+
+    /// Add `node` to the subgraph `parent`.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no subgraph named `parent` or if the node id is already a node,
+    /// `Ok` otherwise.
+    pub fn add_node(&mut self, node: Node, parent: &GraphId) -> Result<(), DotGraphError> {
+        if self.nodes.contains(&node.id) {
+            return Err(DotGraphError::DuplicateNode(node.id, self.id.clone()));
+        }
+        if !self.subgraphs.contains(parent) {
+            return Err(DotGraphError::NoSuchSubGraph(parent.to_string(), self.id.clone()));
+        }
+
+        self.update_subgraph(parent, |subgraph| {
+            subgraph.node_ids.insert(node.id.clone());
+        });
+        self.owners.insert_node(&node.id, parent);
+        self.fwdmap.entry(node.id.clone()).or_default();
+        self.bwdmap.entry(node.id.clone()).or_default();
+        self.subscribers.notify(GraphChange::NodeAdded(node.id.clone(), parent.clone()));
+        self.nodes.insert(node);
+        self.prefixes.invalidate();
+
+        Ok(())
+    }
+
+    /// Remove the node with `id`, along with all edges from or to it.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node with `id`,
+    /// `Ok` with the removed node otherwise.
+    pub fn remove_node(&mut self, id: &NodeId) -> Result<Node, DotGraphError> {
+        let node = self
+            .nodes
+            .take(id)
+            .ok_or_else(|| DotGraphError::NoSuchNode(id.to_string(), self.id.clone()))?;
+
+        let touching: Vec<EdgeId> = self
+            .edges
+            .par_iter()
+            .filter_map(|edge| (edge.id.from == *id || edge.id.to == *id).then_some(&edge.id))
+            .cloned()
+            .collect();
+        // every edge between the node and its neighbors goes, so the neighbors lose it
+        for edge_id in &touching {
+            self.detach_edge(edge_id);
+            if edge_id.from == *id {
+                self.bwdmap.get_mut(&edge_id.to).unwrap().remove(id);
+            }
+            if edge_id.to == *id {
+                self.fwdmap.get_mut(&edge_id.from).unwrap().remove(id);
+            }
+            self.subscribers.notify(GraphChange::EdgeRemoved(edge_id.clone()));
+        }
+
+        if let Some(owner) = self.parent_subgraph(id).cloned() {
+            self.update_subgraph(&owner, |subgraph| {
+                subgraph.node_ids.remove(id);
+            });
+        }
+        self.owners.remove_node(id);
+        self.fwdmap.remove(id);
+        self.bwdmap.remove(id);
+        self.payloads.remove_node(id);
+        self.prefixes.invalidate();
+        self.subscribers.notify(GraphChange::NodeRemoved(id.clone()));
+
+        Ok(node)
+    }
+
+    /// Add `edge` to the subgraph `parent`.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no subgraph named `parent`, if either end of the edge is not a node,
+    /// if either port of the edge is not a port of its node, or if the edge id is already an edge,
+    /// `Ok` otherwise.
+    pub fn add_edge(&mut self, edge: Edge, parent: &GraphId) -> Result<(), DotGraphError> {
+        if self.edges.contains(&edge.id) {
+            return Err(DotGraphError::DuplicateEdge(edge.id.to_string(), self.id.clone()));
+        }
+        for (end, port) in [(&edge.id.from, &edge.id.tailport), (&edge.id.to, &edge.id.headport)] {
+            let node = self
+                .nodes
+                .get(end)
+                .ok_or_else(|| DotGraphError::NoSuchNode(end.to_string(), self.id.clone()))?;
+            if let Some(port) = port {
+                if !port::is_valid(port, &node.ports()) {
+                    return Err(DotGraphError::NoSuchPort(port.to_string(), end.to_string()));
+                }
+            }
+        }
+        if !self.subgraphs.contains(parent) {
+            return Err(DotGraphError::NoSuchSubGraph(parent.to_string(), self.id.clone()));
+        }
+
+        let from = &edge.id.from;
+        let to = &edge.id.to;
+        self.fwdmap.get_mut(from).unwrap().insert(to.clone());
+        self.bwdmap.get_mut(to).unwrap().insert(from.clone());
+        self.update_subgraph(parent, |subgraph| {
+            subgraph.edge_ids.insert(edge.id.clone());
+        });
+        self.owners.insert_edge(&edge.id, parent);
+        self.subscribers.notify(GraphChange::EdgeAdded(edge.id.clone(), parent.clone()));
+        self.edges.insert(edge);
+
+        Ok(())
+    }
+
+    /// Remove the edge with `id`.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no edge with `id`,
+    /// `Ok` with the removed edge otherwise.
+    pub fn remove_edge(&mut self, id: &EdgeId) -> Result<Edge, DotGraphError> {
+        let edge = self
+            .detach_edge(id)
+            .ok_or_else(|| DotGraphError::NoSuchEdge(id.to_string(), self.id.clone()))?;
+
+        // other edges may connect the same nodes through different ports
+        let from = &id.from;
+        let to = &id.to;
+        if !self.edges.par_iter().any(|edge| edge.id.from == *from && edge.id.to == *to) {
+            self.fwdmap.get_mut(from).unwrap().remove(to);
+            self.bwdmap.get_mut(to).unwrap().remove(from);
+        }
+        self.subscribers.notify(GraphChange::EdgeRemoved(id.clone()));
+
+        Ok(edge)
+    }
+
+    /// Take the edge with `id` out of the edges, its subgraph, and the payloads,
+    /// leaving `fwdmap` and `bwdmap` to the caller
+    pub(crate) fn detach_edge(&mut self, id: &EdgeId) -> Option<Edge> {
+        let edge = self.edges.take(id)?;
+
+        if let Some(owner) = self.edge_owner(id).cloned() {
+            self.update_subgraph(&owner, |subgraph| {
+                subgraph.edge_ids.remove(id);
+            });
+        }
+        self.owners.remove_edge(id);
+        self.payloads.remove_edge(id);
+
+        Some(edge)
+    }
+
+    /// Apply a batch of mutations atomically.
+    ///
+    /// `f` mutates the graph in place through a `Transaction`, which records how to undo
+    /// each mutation, so that the graph is rolled back if `f` fails.
+    /// Changes are delivered to subscribers only once `f` succeeds.
+    ///
+    /// # Returns
+    ///
+    /// `Err` of `f`, leaving the graph untouched,
+    /// otherwise `Ok` with the result of `f`.
+    pub fn transaction<T, F>(&mut self, f: F) -> Result<T, DotGraphError>
+    where
+        F: FnOnce(&mut Transaction<'_>) -> Result<T, DotGraphError>,
+    {
+        let watching = self.subscribers.is_watching();
+        if watching {
+            self.subscribers.record();
+        }
+
+        let mut tx = Transaction::new(self);
+        let result = f(&mut tx);
+        if result.is_err() {
+            tx.rollback();
+        }
+
+        if watching {
+            let changes = self.subscribers.recorded();
+            if result.is_ok() {
+                for change in changes {
+                    self.subscribers.notify(change);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Ids of the subgraph with `id` and all its descendants
+    pub(crate) fn subgraph_descendants(&self, id: &GraphId) -> HashSet<GraphId> {
+        let mut descendants = HashSet::new();
+
+        let mut frontier = vec![id];
+        while let Some(id) = frontier.pop() {
+            if descendants.insert(id.clone()) {
+                frontier.extend(&self.subtree[id]);
+            }
+        }
+
+        descendants
+    }
+
+    /// Apply `f` to the subgraph with `id`, which must exist.
+    /// Callers moving elements in or out of the subgraph update `owners` themselves.
+    pub(crate) fn update_subgraph<F>(&mut self, id: &GraphId, f: F)
+    where
+        F: FnOnce(&mut SubGraph),
+    {
+        let mut subgraph = self.subgraphs.take(id).unwrap();
+        f(&mut subgraph);
+        self.subgraphs.insert(subgraph);
+    }
+
+    /// Reverse the direction of every edge, swapping its ends and ports.
+    pub fn reverse(&mut self) {
+        self.edges = std::mem::take(&mut self.edges)
+            .into_par_iter()
+            .map(|mut edge| {
+                edge.id = edge.id.reversed();
+                edge
+            })
+            .collect();
+
+        self.subgraphs = std::mem::take(&mut self.subgraphs)
+            .into_par_iter()
+            .map(|mut subgraph| {
+                subgraph.edge_ids = subgraph.edge_ids.into_iter().map(EdgeId::reversed).collect();
+                subgraph
+            })
+            .collect();
+        self.owners.invalidate();
+
+        std::mem::swap(&mut self.fwdmap, &mut self.bwdmap);
+        self.payloads.relabel(NodeId::clone, |id| id.clone().reversed());
+        self.subscribers.notify(GraphChange::EdgesReversed);
+    }
+
+    /// Rewrite the attributes of every node with `f`,
+    /// which maps a node id and one of its attributes to a new attribute, or `None` to drop it.
+    pub fn map_node_attrs<F>(&mut self, f: F)
+    where
+        F: Fn(&NodeId, Attr) -> Option<Attr> + Sync,
+    {
+        let watching = self.subscribers.is_watching();
+        let changed: Vec<Option<NodeId>>;
+        (self.nodes, changed) = std::mem::take(&mut self.nodes)
+            .into_par_iter()
+            .map(|mut node| {
+                let attrs = std::mem::take(&mut node.attrs);
+                let old = watching.then(|| attrs.clone());
+                node.attrs = attrs.into_iter().filter_map(|attr| f(&node.id, attr)).collect();
+
+                let changed = old.filter(|old| change::attrs_changed(old, &node.attrs));
+                let changed = changed.map(|_| node.id.clone());
+                (node, changed)
+            })
+            .unzip();
+
+        for id in changed.into_iter().flatten() {
+            self.subscribers.notify(GraphChange::NodeAttrChanged(id));
+        }
+    }
+
+    /// Set the attributes `f` returns for every node, replacing those with the same keys.
+    pub(crate) fn put_node_attrs<F>(&mut self, f: F)
+    where
+        F: Fn(&NodeId) -> Vec<Attr> + Sync,
+    {
+        let watching = self.subscribers.is_watching();
+        let changed: Vec<Option<NodeId>>;
+        (self.nodes, changed) = std::mem::take(&mut self.nodes)
+            .into_par_iter()
+            .map(|mut node| {
+                let old = watching.then(|| node.attrs.clone());
+                for attr in f(&node.id) {
+                    node.attrs.replace(attr);
+                }
+
+                let changed = old.filter(|old| change::attrs_changed(old, &node.attrs));
+                let changed = changed.map(|_| node.id.clone());
+                (node, changed)
+            })
+            .unzip();
+
+        for id in changed.into_iter().flatten() {
+            self.subscribers.notify(GraphChange::NodeAttrChanged(id));
+        }
+    }
+
+    /// Rewrite the attributes of every edge with `f`,
+    /// which maps an edge id and one of its attributes to a new attribute, or `None` to drop it.
+    pub fn map_edge_attrs<F>(&mut self, f: F)
+    where
+        F: Fn(&EdgeId, Attr) -> Option<Attr> + Sync,
+    {
+        let watching = self.subscribers.is_watching();
+        let changed: Vec<Option<EdgeId>>;
+        (self.edges, changed) = std::mem::take(&mut self.edges)
+            .into_par_iter()
+            .map(|mut edge| {
+                let attrs = std::mem::take(&mut edge.attrs);
+                let old = watching.then(|| attrs.clone());
+                edge.attrs = attrs.into_iter().filter_map(|attr| f(&edge.id, attr)).collect();
+
+                let changed = old.filter(|old| change::attrs_changed(old, &edge.attrs));
+                let changed = changed.map(|_| edge.id.clone());
+                (edge, changed)
+            })
+            .unzip();
+
+        for id in changed.into_iter().flatten() {
+            self.subscribers.notify(GraphChange::EdgeAttrChanged(id));
+        }
+    }
+
+    /// Rename the node `old` to `new`, rewriting every edge and subgraph referencing it.
+    /// Renaming a node to its own id changes nothing.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node with `old` or if `new` is already another node,
+    /// `Ok` otherwise.
+    pub fn rename_node(&mut self, old: &NodeId, new: NodeId) -> Result<(), DotGraphError> {
+        if *old == new {
+            return self.node_id(old).map(|_| ());
+        }
+        if self.nodes.contains(&new) {
+            return Err(DotGraphError::DuplicateNode(new, self.id.clone()));
+        }
+
+        let mut node = self
+            .nodes
+            .take(old)
+            .ok_or_else(|| DotGraphError::NoSuchNode(old.to_string(), self.id.clone()))?;
+        node.id = new.clone();
+        self.nodes.insert(node);
+        self.prefixes.invalidate();
+
+        let touching: Vec<EdgeId> = self
+            .edges
+            .par_iter()
+            .filter_map(|edge| (edge.id.from == *old || edge.id.to == *old).then_some(&edge.id))
+            .cloned()
+            .collect();
+
+        let mut renamed = HashMap::new();
+        for id in touching {
+            let mut edge = self.edges.take(&id).unwrap();
+            if edge.id.from == *old {
+                edge.id.from = new.clone();
+            }
+            if edge.id.to == *old {
+                edge.id.to = new.clone();
+            }
+            renamed.insert(id, edge.id.clone());
+            self.edges.insert(edge);
+        }
+
+        self.subgraphs = std::mem::take(&mut self.subgraphs)
+            .into_par_iter()
+            .map(|mut subgraph| {
+                if subgraph.node_ids.remove(old) {
+                    subgraph.node_ids.insert(new.clone());
+                }
+                for (old, new) in &renamed {
+                    if subgraph.edge_ids.remove(old) {
+                        subgraph.edge_ids.insert(new.clone());
+                    }
+                }
+                subgraph
+            })
+            .collect();
+        self.owners.invalidate();
+
+        (self.fwdmap, self.bwdmap) = make_edge_maps(&self.nodes, &self.edges);
+        self.payloads.relabel(
+            |id| if id == old { new.clone() } else { id.clone() },
+            |id| renamed.get(id).unwrap_or(id).clone(),
+        );
+        self.subscribers.notify(GraphChange::NodeRenamed(old.clone(), new));
+
+        Ok(())
+    }
+
+    /// Rename every node by `f`, rewriting every edge and subgraph referencing them in one pass.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - Maps the id of a node to its new id
+    ///
+    /// # Returns
+    ///
+    /// `Err` if `f` maps two nodes to the same id, leaving the graph untouched,
+    /// `Ok` otherwise.
+    pub fn relabel<F>(&mut self, f: F) -> Result<(), DotGraphError>
+    where
+        F: Fn(&NodeId) -> NodeId + Sync,
+    {
+        let renamed: HashMap<NodeId, NodeId> =
+            self.nodes.par_iter().map(|node| (node.id.clone(), f(&node.id))).collect();
+
+        let mut seen = HashSet::with_capacity(renamed.len());
+        if let Some(new) = renamed.values().find(|&new| !seen.insert(new)) {
+            return Err(DotGraphError::DuplicateNode(new.clone(), self.id.clone()));
+        }
+
+        let rename = |id: &NodeId| renamed.get(id).unwrap_or(id).clone();
+        let relabel = |id: &EdgeId| {
+            let mut id = id.clone();
+            id.from = rename(&id.from);
+            id.to = rename(&id.to);
+            id
+        };
+
+        self.nodes = std::mem::take(&mut self.nodes)
+            .into_par_iter()
+            .map(|mut node| {
+                node.id = renamed[&node.id].clone();
+                node
+            })
+            .collect();
+        self.prefixes.invalidate();
+        self.edges = std::mem::take(&mut self.edges)
+            .into_par_iter()
+            .map(|mut edge| {
+                edge.id = relabel(&edge.id);
+                edge
+            })
+            .collect();
+        self.subgraphs = std::mem::take(&mut self.subgraphs)
+            .into_par_iter()
+            .map(|mut subgraph| {
+                subgraph.node_ids =
+                    subgraph.node_ids.iter().map(|id| renamed[id].clone()).collect();
+                subgraph.edge_ids = subgraph.edge_ids.iter().map(relabel).collect();
+                subgraph
+            })
+            .collect();
+        self.owners.invalidate();
+
+        (self.fwdmap, self.bwdmap) = make_edge_maps(&self.nodes, &self.edges);
+        self.payloads.relabel(rename, relabel);
+        self.subscribers.notify(GraphChange::NodesRelabeled);
+
+        Ok(())
+    }
+
+    /// Rename the subgraph `old` to `new`, rewriting every parent referencing it.
+    /// Renaming the root subgraph renames the graph itself.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no subgraph named `old` or if `new` is already a subgraph,
+    /// `Ok` otherwise.
+    pub fn rename_subgraph(&mut self, old: &GraphId, new: GraphId) -> Result<(), DotGraphError> {
+        if self.subgraphs.contains(&new) {
+            return Err(DotGraphError::DuplicateSubGraph(new, self.id.clone()));
+        }
+
+        let mut subgraph = self
+            .subgraphs
+            .take(old)
+            .ok_or_else(|| DotGraphError::NoSuchSubGraph(old.to_string(), self.id.clone()))?;
+        subgraph.id = new.clone();
+        self.subgraphs.insert(subgraph);
+
+        self.subgraphs = std::mem::take(&mut self.subgraphs)
+            .into_par_iter()
+            .map(|mut subgraph| {
+                if subgraph.subgraph_ids.remove(old) {
+                    subgraph.subgraph_ids.insert(new.clone());
+                }
+                subgraph
+            })
+            .collect();
+        self.owners.invalidate();
+
+        let children = self.subtree.remove(old).unwrap();
+        self.subtree.insert(new.clone(), children);
+        for children in self.subtree.values_mut() {
+            if children.remove(old) {
+                children.insert(new.clone());
+            }
+        }
+
+        if self.id == *old {
+            self.id = new.clone();
+        }
+        self.subscribers.notify(GraphChange::SubGraphRenamed(old.clone(), new));
+
+        Ok(())
+    }
+
+    /// Rename the graph, along with its root subgraph, to `id`.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if `id` is already a subgraph,
+    /// `Ok` otherwise.
+    pub fn set_id(&mut self, id: GraphId) -> Result<(), DotGraphError> {
+        if self.id == id {
+            return Ok(());
+        }
+
+        let old = self.id.clone();
+        self.rename_subgraph(&old, id)
+    }
+
     /// Write the graph to dot format.
-    pub fn to_dot<W: ?Sized>(&self, writer: &mut W) -> std::io::Result<()>
+    pub fn to_dot<W>(&self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        self.to_dot_with_options(&DotWriteOptions::default(), writer)
+    }
+
+    /// Write the graph to dot format, laid out by `options`,
+    /// e.g., with `DotWriteOptions::compact` to keep large graphs small.
+    pub fn to_dot_with_options<W>(
+        &self,
+        options: &DotWriteOptions,
+        writer: &mut W,
+    ) -> std::io::Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        let root = self.subgraphs.get(&self.id).unwrap();
+
+        root.to_dot(self, 0, options, None, &mut HashSet::new(), writer)
+    }
+
+    /// Write the graph to dot format as `Graph::to_dot` does, with what `filter` returns for
+    /// each attribute of the graph, its subgraphs, nodes, and edges, so that e.g. layout
+    /// attributes are stripped or labels are redacted without mutating the graph.
+    ///
+    /// ```ignore
+    /// graph.to_dot_with(&mut writer, |_, attr| match attr.key().as_str() {
+    ///     "pos" | "width" | "height" => AttrAction::Skip,
+    ///     _ => AttrAction::Keep,
+    /// })?;
+    /// ```
+    pub fn to_dot_with<W, F>(&self, writer: &mut W, filter: F) -> std::io::Result<()>
     where
-        W: Write,
+        W: Write + ?Sized,
+        F: Fn(DotElement, &Attr) -> AttrAction,
     {
         let root = self.subgraphs.get(&self.id).unwrap();
 
-        root.to_dot(self, 0, writer)
+        let options = DotWriteOptions::default();
+        root.to_dot(self, 0, &options, Some(&filter), &mut HashSet::new(), writer)
+    }
+
+    /// Write the graph to dot format as `Graph::to_dot` does, but with subgraphs, nodes, edges,
+    /// and attributes sorted by their ids or keys, so that equal graphs are always written
+    /// the same, e.g., to be diffed or cached.
+    pub fn to_dot_sorted<W>(&self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        self.to_dot_with_options(&DotWriteOptions::pretty().sorted(true), writer)
+    }
+
+    /// Write the graph to a normalized dot format, where subgraphs, nodes, edges, and
+    /// attributes are sorted by their ids or keys and every attribute list takes a single line,
+    /// so that equal graphs are always written the same and line diffs show real changes.
+    pub fn to_canonical_dot<W>(&self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        self.to_dot_with_options(&DotWriteOptions::canonical(), writer)
+    }
+}
+
+impl fmt::Display for Graph {
+    /// Writes the graph in dot format, or a summary of it with `{:#}`,
+    /// e.g. `digraph G with 2 subgraphs, 1,243 nodes, and 2,000 edges`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(
+                f,
+                "digraph {} with {}, {}, and {}",
+                self.id,
+                utils::count(self.subgraphs.len().saturating_sub(1), "subgraph"),
+                utils::count(self.nodes.len(), "node"),
+                utils::count(self.edges.len(), "edge"),
+            )
+        } else {
+            utils::fmt_with(f, |writer| self.to_dot(writer))
+        }
+    }
+}
+
+impl FromStr for Graph {
+    type Err = DotGraphError;
+
+    /// Parse a graph from dot format as `parser::parse_from_memory` does
+    fn from_str(contents: &str) -> Result<Graph, DotGraphError> {
+        parser::parse_from_memory(contents)
     }
 }
 
-fn make_edge_maps(nodes: &HashSet<Node>, edges: &HashSet<Edge>) -> (EdgeMap, EdgeMap) {
+impl TryFrom<&Path> for Graph {
+    type Error = DotGraphError;
+
+    /// Parse a graph from the dot file in `path` as `parser::parse_from_file` does,
+    /// where `path` must be valid unicode
+    fn try_from(path: &Path) -> Result<Graph, DotGraphError> {
+        let Some(path) = path.to_str() else {
+            return Err(DotGraphError::InvalidGraph(path.display().to_string()));
+        };
+
+        parser::parse_from_file(path)
+    }
+}
+
+fn with_identity_map(graph: Graph) -> (Graph, ExtractionMap) {
+    let map = ExtractionMap::identity(&graph);
+
+    (graph, map)
+}
+
+pub(crate) fn make_edge_maps(nodes: &HashSet<Node>, edges: &HashSet<Edge>) -> (EdgeMap, EdgeMap) {
     let mut fwdmap = EdgeMap::new();
     let mut bwdmap = EdgeMap::new();
 
@@ -386,7 +1633,7 @@ fn make_edge_maps(nodes: &HashSet<Node>, edges: &HashSet<Edge>) -> (EdgeMap, Edg
     (fwdmap, bwdmap)
 }
 
-fn make_subtree(subgraphs: &HashSet<SubGraph>) -> SubTree {
+pub(crate) fn make_subtree(subgraphs: &HashSet<SubGraph>) -> SubTree {
     let mut subtree = HashMap::new();
 
     for subgraph in subgraphs {
@@ -0,0 +1,163 @@
+use crate::{
+    attr::Attr,
+    edge::EdgeId,
+    graphs::graph::{Graph, GraphId},
+    node::NodeId,
+    utils,
+};
+
+use std::collections::{HashMap, HashSet};
+use std::io::{Result, Write};
+
+impl Graph {
+    /// Write the graph to the JSON format of `dot -Tjson0`, without any layout.
+    ///
+    /// Subgraphs other than the root come first in `objects`, in preorder with children sorted
+    /// by their ids, followed by nodes sorted by their ids, so that the `_gvid` of each object
+    /// is its index in `objects`. Edges are sorted by their ends in `edges`, where ports are
+    /// written as `tailport` and `headport` attributes. Subgraphs list the `_gvid`s of their
+    /// direct children in `subgraphs`, and of all nodes and edges they hold, including those of
+    /// their descendants, in `nodes` and `edges`.
+    pub fn to_json<W>(&self, writer: &mut W) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        let mut subgraphs = Vec::new();
+        self.preorder(&self.id, &mut subgraphs);
+        let subgraphs = &subgraphs[1..];
+
+        let mut nodes: Vec<&NodeId> = self.nodes.iter().map(|node| &node.id).collect();
+        nodes.sort_unstable();
+        let mut edges: Vec<&EdgeId> = self.edges.iter().map(|edge| &edge.id).collect();
+        edges.sort_unstable_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+
+        let subgraph_gvids: HashMap<&GraphId, usize> =
+            subgraphs.iter().enumerate().map(|(gvid, &id)| (id, gvid)).collect();
+        let node_gvids: HashMap<&NodeId, usize> =
+            nodes.iter().enumerate().map(|(gvid, &id)| (id, subgraphs.len() + gvid)).collect();
+        let edge_gvids: HashMap<&EdgeId, usize> =
+            edges.iter().enumerate().map(|(gvid, &id)| (id, gvid)).collect();
+
+        let root = self.search_subgraph(&self.id).unwrap();
+        let mut fields = vec![
+            (String::from("name"), utils::json_string(&self.id)),
+            (String::from("directed"), String::from("true")),
+            (String::from("strict"), String::from("false")),
+        ];
+        fields.extend(attr_fields(&root.attrs));
+        fields.push((String::from("_subgraph_cnt"), subgraphs.len().to_string()));
+
+        writeln!(writer, "{{")?;
+        for (key, value) in &fields {
+            writeln!(writer, "  {}: {value},", utils::json_string(key))?;
+        }
+
+        writeln!(writer, "  \"objects\": [")?;
+        let objects = subgraphs.len() + nodes.len();
+        for (gvid, &id) in subgraphs.iter().enumerate() {
+            let subgraph = self.search_subgraph(id).unwrap();
+
+            let mut children: Vec<&GraphId> = self.subtree[id].iter().collect();
+            children.sort_unstable();
+            let children = children.into_iter().map(|id| subgraph_gvids[id]);
+            let mut held: Vec<usize> =
+                self.collect_nodes(id).unwrap().into_iter().map(|id| node_gvids[id]).collect();
+            held.sort_unstable();
+            let mut connected: Vec<usize> =
+                self.collect_edges(id).unwrap().into_iter().map(|id| edge_gvids[id]).collect();
+            connected.sort_unstable();
+            connected.dedup();
+
+            let mut fields = vec![
+                (String::from("_gvid"), gvid.to_string()),
+                (String::from("name"), utils::json_string(id)),
+            ];
+            fields.extend(attr_fields(&subgraph.attrs));
+            if !self.subtree[id].is_empty() {
+                fields.push((String::from("subgraphs"), json_array(children)));
+            }
+            fields.push((String::from("nodes"), json_array(held)));
+            fields.push((String::from("edges"), json_array(connected)));
+
+            write_object(&fields, gvid + 1 == objects, writer)?;
+        }
+        for &id in &nodes {
+            let node = self.search_node(id).unwrap();
+
+            let mut fields = vec![
+                (String::from("_gvid"), node_gvids[id].to_string()),
+                (String::from("name"), utils::json_string(id)),
+            ];
+            fields.extend(attr_fields(&node.attrs));
+
+            write_object(&fields, node_gvids[id] + 1 == objects, writer)?;
+        }
+        writeln!(writer, "  ],")?;
+
+        writeln!(writer, "  \"edges\": [")?;
+        for (gvid, &id) in edges.iter().enumerate() {
+            let edge = self.search_edge(id).unwrap();
+
+            let mut fields = vec![
+                (String::from("_gvid"), gvid.to_string()),
+                (String::from("tail"), node_gvids[&id.from].to_string()),
+                (String::from("head"), node_gvids[&id.to].to_string()),
+            ];
+            let mut attrs = edge.attrs.clone();
+            let ports = [("tailport", &id.tailport), ("headport", &id.headport)];
+            for (key, port) in ports {
+                if let Some(port) = port {
+                    attrs.insert(Attr::new(String::from(key), port.clone(), false));
+                }
+            }
+            fields.extend(attr_fields(&attrs));
+
+            write_object(&fields, gvid + 1 == edges.len(), writer)?;
+        }
+        writeln!(writer, "  ]")?;
+        writeln!(writer, "}}")?;
+
+        Ok(())
+    }
+
+    /// Collect `id` and its descendant subgraphs in preorder, visiting children by their ids
+    fn preorder<'a>(&'a self, id: &'a GraphId, order: &mut Vec<&'a GraphId>) {
+        order.push(id);
+
+        let mut children: Vec<&GraphId> = self.subtree[id].iter().collect();
+        children.sort_unstable();
+        for child in children {
+            self.preorder(child, order);
+        }
+    }
+}
+
+/// Encode `attrs` as JSON fields sorted by their keys
+fn attr_fields(attrs: &HashSet<Attr>) -> Vec<(String, String)> {
+    let mut attrs: Vec<&Attr> = attrs.iter().collect();
+    attrs.sort_unstable_by(|a, b| a.key.cmp(&b.key));
+
+    attrs.into_iter().map(|attr| (attr.key.clone(), utils::json_string(&attr.value))).collect()
+}
+
+/// Encode `gvids` as a JSON array, e.g. `[0, 1]`
+fn json_array(gvids: impl IntoIterator<Item = usize>) -> String {
+    let gvids: Vec<String> = gvids.into_iter().map(|gvid| gvid.to_string()).collect();
+
+    format!("[{}]", gvids.join(", "))
+}
+
+/// Write an element of a top level array, as a JSON object of already encoded `fields`
+fn write_object<W>(fields: &[(String, String)], last: bool, writer: &mut W) -> Result<()>
+where
+    W: Write + ?Sized,
+{
+    writeln!(writer, "    {{")?;
+    for (i, (key, value)) in fields.iter().enumerate() {
+        let comma = if i + 1 < fields.len() { "," } else { "" };
+        writeln!(writer, "      {}: {value}{comma}", utils::json_string(key))?;
+    }
+    writeln!(writer, "    }}{}", if last { "" } else { "," })?;
+
+    Ok(())
+}
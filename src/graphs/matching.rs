@@ -0,0 +1,126 @@
+use crate::{
+    error::DotGraphError,
+    graphs::{graph::Graph, traversal::Direction},
+    node::NodeId,
+};
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+impl Graph {
+    /// Match nodes of `left` to nodes of `right` of this `Graph` pairwise through edges,
+    /// regardless of their direction, with as many pairs as possible, e.g., to assign resources
+    /// to their consumers. The two sides are expected to be disjoint, as by `Graph::bipartition`.
+    ///
+    /// Edges within either side, or from or to nodes of neither side, are ignored.
+    /// Matching follows Hopcroft–Karp, trying nodes in the order of their ids.
+    ///
+    /// # Arguments
+    ///
+    /// * `left` - Ids of nodes on one side
+    /// * `right` - Ids of nodes on the other side
+    ///
+    /// # Returns
+    ///
+    /// `Err` if any of the given ids is not a node of this graph,
+    /// `Ok` with the node of `right` each matched node of `left` is paired with otherwise.
+    pub fn max_bipartite_matching(
+        &self,
+        left: &HashSet<NodeId>,
+        right: &HashSet<NodeId>,
+    ) -> Result<HashMap<&NodeId, &NodeId>, DotGraphError> {
+        let mut lefts: Vec<&NodeId> =
+            left.iter().map(|id| self.node_id(id)).collect::<Result<_, _>>()?;
+        lefts.sort_unstable();
+        if let Some(id) = right.iter().find(|id| !self.nodes.contains(*id)) {
+            return Err(DotGraphError::NoSuchNode(id.to_string(), self.id.clone()));
+        }
+
+        let adjacency: HashMap<&NodeId, Vec<&NodeId>> = lefts
+            .iter()
+            .map(|&id| {
+                let mut nexts: Vec<&NodeId> = self
+                    .adjacent(id, Direction::Both)
+                    .filter(|next| right.contains(*next))
+                    .collect();
+                nexts.sort_unstable();
+                nexts.dedup();
+                (id, nexts)
+            })
+            .collect();
+
+        let mut matching = Matching { adjacency, pairs: HashMap::new(), mates: HashMap::new() };
+        while let Some(mut layers) = matching.layers(&lefts) {
+            for &id in &lefts {
+                if !matching.pairs.contains_key(id) {
+                    matching.augment(id, &mut layers);
+                }
+            }
+        }
+
+        Ok(matching.pairs)
+    }
+}
+
+/// State of Hopcroft–Karp over nodes of the left side
+struct Matching<'a> {
+    /// Nodes of the right side adjacent to each node of the left side, sorted
+    adjacency: HashMap<&'a NodeId, Vec<&'a NodeId>>,
+    /// Node of the right side each matched node of the left side is paired with
+    pairs: HashMap<&'a NodeId, &'a NodeId>,
+    /// Node of the left side each matched node of the right side is paired with
+    mates: HashMap<&'a NodeId, &'a NodeId>,
+}
+
+impl<'a> Matching<'a> {
+    /// Layer nodes of the left side by the length of the shortest alternating path
+    /// from a free one, or `None` if no augmenting path is left
+    fn layers(&self, lefts: &[&'a NodeId]) -> Option<HashMap<&'a NodeId, usize>> {
+        let mut layers = HashMap::new();
+        let mut frontier = VecDeque::new();
+        for &id in lefts {
+            if !self.pairs.contains_key(id) {
+                layers.insert(id, 0);
+                frontier.push_back(id);
+            }
+        }
+
+        let mut found = false;
+        while let Some(id) = frontier.pop_front() {
+            let layer = layers[id];
+            for next in &self.adjacency[id] {
+                match self.mates.get(next) {
+                    None => found = true,
+                    Some(&mate) => {
+                        if !layers.contains_key(mate) {
+                            layers.insert(mate, layer + 1);
+                            frontier.push_back(mate);
+                        }
+                    }
+                }
+            }
+        }
+
+        found.then_some(layers)
+    }
+
+    /// Search for an augmenting path from `id` along the layers, flipping it if found
+    fn augment(&mut self, id: &'a NodeId, layers: &mut HashMap<&'a NodeId, usize>) -> bool {
+        let layer = layers[id];
+        for index in 0..self.adjacency[id].len() {
+            let next = self.adjacency[id][index];
+            let augmented = match self.mates.get(next) {
+                None => true,
+                Some(&mate) => layers.get(mate) == Some(&(layer + 1)) && self.augment(mate, layers),
+            };
+            if augmented {
+                self.pairs.insert(id, next);
+                self.mates.insert(next, id);
+                return true;
+            }
+        }
+
+        // no augmenting path goes through this node within this phase
+        layers.remove(id);
+        false
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{edge::Edge, graphs::graph::Graph, node::NodeId};
+
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, PartialEq)]
+/// A `Csr` is the adjacency matrix of a `Graph` in compressed sparse row format,
+/// created by `Graph::to_csr`.
+pub struct Csr<'a> {
+    /// Ids of nodes indexing both rows and columns
+    ids: Vec<&'a NodeId>,
+    /// Offsets of each row into `indices` and `data`, followed by the number of entries
+    indptr: Vec<usize>,
+    /// Column of each entry, sorted within each row
+    indices: Vec<usize>,
+    /// Value of each entry
+    data: Vec<f64>,
+}
+
+impl<'a> Csr<'a> {
+    pub fn ids(&self) -> &[&'a NodeId] {
+        &self.ids
+    }
+
+    pub fn indptr(&self) -> &[usize] {
+        &self.indptr
+    }
+
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    pub fn data(&self) -> &[f64] {
+        &self.data
+    }
+}
+
+impl Graph {
+    /// Build the dense adjacency matrix of this `Graph`, e.g., for spectral analyses.
+    ///
+    /// # Arguments
+    ///
+    /// * `weight` - Key of the attribute to weight edges by, where edges without a numeric
+    ///   value weigh `1.0`, or `None` to count edges
+    ///
+    /// # Returns
+    ///
+    /// Ids of nodes sorted, indexing both rows and columns, and the row-major matrix where
+    /// the entry of row `i` and column `j` sums the weights of edges from node `i` to node `j`.
+    pub fn adjacency_matrix(&self, weight: Option<&str>) -> (Vec<&NodeId>, Vec<f64>) {
+        let (ids, indices) = self.indices();
+
+        let n = ids.len();
+        let mut matrix = vec![0.0; n * n];
+        for edge in &self.edges {
+            let (from, to) = (indices[&edge.id.from], indices[&edge.id.to]);
+            matrix[from * n + to] += edge_weight(edge, weight);
+        }
+
+        (ids, matrix)
+    }
+
+    /// Build the adjacency matrix of this `Graph` as `Graph::adjacency_matrix` does, but in
+    /// compressed sparse row format, so that large graphs take space only for their edges.
+    ///
+    /// # Arguments
+    ///
+    /// * `weight` - Key of the attribute to weight edges by, where edges without a numeric
+    ///   value weigh `1.0`, or `None` to count edges
+    ///
+    /// # Returns
+    ///
+    /// The matrix, with an entry for each pair of nodes connected by edges.
+    pub fn to_csr(&self, weight: Option<&str>) -> Csr<'_> {
+        let (ids, indices) = self.indices();
+
+        let mut rows: Vec<BTreeMap<usize, f64>> = vec![BTreeMap::new(); ids.len()];
+        for edge in &self.edges {
+            let (from, to) = (indices[&edge.id.from], indices[&edge.id.to]);
+            *rows[from].entry(to).or_default() += edge_weight(edge, weight);
+        }
+
+        let mut indptr = Vec::with_capacity(ids.len() + 1);
+        let mut indices = Vec::new();
+        let mut data = Vec::new();
+        indptr.push(0);
+        for row in rows {
+            for (column, value) in row {
+                indices.push(column);
+                data.push(value);
+            }
+            indptr.push(indices.len());
+        }
+
+        Csr { ids, indptr, indices, data }
+    }
+
+    /// Build the adjacency matrix of this `Graph` by `Graph::adjacency_matrix`,
+    /// as a two-dimensional `ndarray` array.
+    #[cfg(feature = "ndarray")]
+    pub fn adjacency_array(&self, weight: Option<&str>) -> (Vec<&NodeId>, ndarray::Array2<f64>) {
+        let (ids, matrix) = self.adjacency_matrix(weight);
+
+        let n = ids.len();
+        (ids, ndarray::Array2::from_shape_vec((n, n), matrix).unwrap())
+    }
+
+    /// Ids of nodes sorted, and the index of each id
+    fn indices(&self) -> (Vec<&NodeId>, HashMap<&NodeId, usize>) {
+        let mut ids: Vec<&NodeId> = self.nodes.iter().map(|node| &node.id).collect();
+        ids.sort_unstable();
+        let indices = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+
+        (ids, indices)
+    }
+}
+
+/// Weight of `edge` by the attribute of `key`, `1.0` if there is no such numeric attribute
+fn edge_weight(edge: &Edge, key: Option<&str>) -> f64 {
+    key.and_then(|key| edge.attrs.get(key)).and_then(|attr| attr.value.parse().ok()).unwrap_or(1.0)
+}
@@ -0,0 +1,193 @@
+use crate::{
+    graphs::{graph::Graph, traversal::Direction},
+    node::NodeId,
+};
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use rayon::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Structural measures of a `Graph`, computed by `Graph::metrics`.
+///
+/// Degrees count the edges from or to each node, where a self-loop counts twice.
+/// Components and the diameter regard edges regardless of their direction.
+pub struct GraphMetrics {
+    nodes: usize,
+    edges: usize,
+    density: f64,
+    min_degree: usize,
+    max_degree: usize,
+    avg_degree: f64,
+    components: usize,
+    diameter: usize,
+}
+
+impl GraphMetrics {
+    pub fn nodes(&self) -> usize {
+        self.nodes
+    }
+
+    pub fn edges(&self) -> usize {
+        self.edges
+    }
+
+    /// Ratio of edges to the pairs of distinct nodes an edge could go from and to
+    pub fn density(&self) -> f64 {
+        self.density
+    }
+
+    pub fn min_degree(&self) -> usize {
+        self.min_degree
+    }
+
+    pub fn max_degree(&self) -> usize {
+        self.max_degree
+    }
+
+    pub fn avg_degree(&self) -> f64 {
+        self.avg_degree
+    }
+
+    /// Number of weakly connected components
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    /// Longest distance in edges found between two nodes in the same component.
+    ///
+    /// It is approximated by a double sweep in each component, i.e., searching from the
+    /// farthest node from an arbitrary one, so it may underestimate the exact diameter.
+    pub fn diameter(&self) -> usize {
+        self.diameter
+    }
+}
+
+impl Graph {
+    /// Measure the structure of this `Graph`.
+    pub fn metrics(&self) -> GraphMetrics {
+        let nodes = self.nodes.len();
+        let edges = self.edges.len();
+
+        let mut degrees: HashMap<&NodeId, usize> =
+            self.nodes.par_iter().map(|node| (&node.id, 0)).collect();
+        for edge in &self.edges {
+            *degrees.get_mut(&edge.id.from).unwrap() += 1;
+            *degrees.get_mut(&edge.id.to).unwrap() += 1;
+        }
+
+        let pairs = nodes * nodes.saturating_sub(1);
+        let density = if pairs == 0 { 0.0 } else { edges as f64 / pairs as f64 };
+        let avg_degree = if nodes == 0 { 0.0 } else { 2.0 * edges as f64 / nodes as f64 };
+
+        let mut starts: Vec<&NodeId> = degrees.keys().copied().collect();
+        starts.sort_unstable();
+
+        let mut visited: HashSet<&NodeId> = HashSet::new();
+        let mut components = 0;
+        let mut diameter = 0;
+        for start in starts {
+            if visited.contains(start) {
+                continue;
+            }
+            components += 1;
+
+            let (component, far, _) = self.farthest(start);
+            let (_, _, eccentricity) = self.farthest(far);
+            diameter = diameter.max(eccentricity);
+            visited.extend(component);
+        }
+
+        GraphMetrics {
+            nodes,
+            edges,
+            density,
+            min_degree: degrees.values().copied().min().unwrap_or(0),
+            max_degree: degrees.values().copied().max().unwrap_or(0),
+            avg_degree,
+            components,
+            diameter,
+        }
+    }
+
+    /// Compute the global clustering coefficient of this `Graph`, i.e., its transitivity,
+    /// regarding edges regardless of their direction and ignoring self-loops.
+    ///
+    /// # Returns
+    ///
+    /// The ratio of pairs of neighbors that are adjacent themselves, over all nodes,
+    /// or zero if no node has two neighbors.
+    pub fn clustering_coefficient(&self) -> f64 {
+        let (links, pairs) = self
+            .nodes
+            .par_iter()
+            .map(|node| self.neighbor_links(&node.id))
+            .reduce(|| (0, 0), |(a, b), (c, d)| (a + c, b + d));
+
+        if pairs == 0 {
+            0.0
+        } else {
+            links as f64 / pairs as f64
+        }
+    }
+
+    /// Compute the local clustering coefficient of every node of this `Graph`,
+    /// regarding edges regardless of their direction and ignoring self-loops.
+    ///
+    /// # Returns
+    ///
+    /// The ratio of pairs of neighbors of each node that are adjacent themselves,
+    /// or zero for nodes with fewer than two neighbors.
+    pub fn clustering_coefficients(&self) -> HashMap<&NodeId, f64> {
+        self.nodes
+            .par_iter()
+            .map(|node| {
+                let (links, pairs) = self.neighbor_links(&node.id);
+                let coefficient = if pairs == 0 { 0.0 } else { links as f64 / pairs as f64 };
+                (&node.id, coefficient)
+            })
+            .collect()
+    }
+
+    /// Numbers of adjacent pairs of neighbors of the node with `id`, and of all such pairs
+    fn neighbor_links(&self, id: &NodeId) -> (usize, usize) {
+        let neighbors: HashSet<&NodeId> =
+            self.adjacent(id, Direction::Both).filter(|&other| other != id).collect();
+
+        let links = neighbors
+            .iter()
+            .map(|&neighbor| {
+                let adjacent: HashSet<&NodeId> = self.adjacent(neighbor, Direction::Both).collect();
+                neighbors
+                    .iter()
+                    .filter(|&&other| other > neighbor && adjacent.contains(other))
+                    .count()
+            })
+            .sum();
+        let degree = neighbors.len();
+
+        (links, degree * degree.saturating_sub(1) / 2)
+    }
+
+    /// Breadth-first search from `start` regardless of the direction of edges.
+    ///
+    /// Returns the visited nodes, the last node reached, and its distance from `start`.
+    fn farthest<'a>(&'a self, start: &'a NodeId) -> (HashSet<&'a NodeId>, &'a NodeId, usize) {
+        let mut visited = HashSet::from([start]);
+        let mut frontier = VecDeque::from([(start, 0)]);
+        let mut last = (start, 0);
+        while let Some((id, distance)) = frontier.pop_front() {
+            last = (id, distance);
+
+            let mut nexts: Vec<&NodeId> = self.adjacent(id, Direction::Both).collect();
+            nexts.sort_unstable();
+            for next in nexts {
+                if visited.insert(next) {
+                    frontier.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        (visited, last.0, last.1)
+    }
+}
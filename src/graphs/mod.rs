@@ -1,7 +1,69 @@
+mod centrality;
+pub mod change;
+pub mod clusters;
+pub mod collapse;
+mod coloring;
+pub mod compress;
+mod csv;
+pub mod cycles;
+pub mod diff;
+pub mod dominators;
+#[cfg(feature = "dot-structures")]
+mod dot_structures;
+pub mod duplicates;
+mod euler;
+pub mod extraction;
+mod fingerprint;
+mod gexf;
 pub mod graph;
 pub(crate) mod igraph;
+mod json;
+mod matching;
+pub mod matrix;
+pub mod metrics;
+mod owners;
+pub mod paths;
+pub mod pattern;
+mod payload;
+#[cfg(feature = "petgraph")]
+mod petgraph;
+mod prune;
+mod quotient;
+mod ranks;
+pub mod reachability;
+pub mod render;
+pub mod search;
+mod spanning;
+pub mod stream;
 pub mod subgraph;
+mod tgf;
+pub mod transaction;
+pub mod traversal;
+mod tree;
+pub mod visit;
+pub mod write;
 
+pub use change::GraphChange;
+pub use clusters::SubGraphStats;
+pub use collapse::CollapsedState;
+pub use compress::Encoder;
+pub use cycles::Cycles;
+pub use diff::{AttrDiff, Changes, GraphDiff};
+pub use dominators::DominatorTree;
+pub use duplicates::Duplicates;
+pub use extraction::{ExtractionMap, Mapping};
 pub use graph::{Graph, GraphId};
 pub(crate) use igraph::IGraph;
+pub use matrix::Csr;
+pub use metrics::GraphMetrics;
+pub use paths::ShortestPaths;
+pub use pattern::Embedding;
+pub use reachability::ReachabilityIndex;
+pub use render::{LayoutEngine, RenderFormat};
+pub use search::TextIndex;
+pub use stream::DotWriter;
 pub use subgraph::SubGraph;
+pub use transaction::Transaction;
+pub use traversal::{Bfs, Dfs, Direction};
+pub use visit::{Control, GraphVisitor};
+pub use write::{AttrAction, BraceStyle, DotElement, DotWriteOptions, Semicolons};
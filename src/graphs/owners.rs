@@ -0,0 +1,167 @@
+use crate::{
+    edge::EdgeId,
+    error::DotGraphError,
+    graphs::{
+        graph::{Graph, GraphId},
+        subgraph::SubGraph,
+    },
+    node::NodeId,
+};
+
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+#[derive(Debug, Default)]
+/// Subgraph holding each node, edge, and subgraph of a graph
+struct Owners {
+    nodes: HashMap<NodeId, GraphId>,
+    edges: HashMap<EdgeId, GraphId>,
+    subgraphs: HashMap<GraphId, GraphId>,
+}
+
+#[derive(Debug, Default)]
+/// An `OwnerIndex` lazily builds the reverse index from the elements of a graph to the
+/// subgraphs holding them. Mutations moving a single element keep a built index current,
+/// while those rewriting subgraphs wholesale invalidate it.
+///
+/// The index does not follow clones of the graph, as a clone is usually about to be modified.
+pub(crate) struct OwnerIndex {
+    owners: OnceLock<Owners>,
+}
+
+impl OwnerIndex {
+    fn get(&self, subgraphs: &HashSet<SubGraph>) -> &Owners {
+        self.owners.get_or_init(|| {
+            let mut owners = Owners::default();
+            for subgraph in subgraphs {
+                for id in &subgraph.node_ids {
+                    owners.nodes.insert(id.clone(), subgraph.id.clone());
+                }
+                for id in &subgraph.edge_ids {
+                    owners.edges.insert(id.clone(), subgraph.id.clone());
+                }
+                for id in &subgraph.subgraph_ids {
+                    owners.subgraphs.insert(id.clone(), subgraph.id.clone());
+                }
+            }
+
+            owners
+        })
+    }
+
+    pub(crate) fn invalidate(&mut self) {
+        self.owners.take();
+    }
+
+    pub(crate) fn insert_node(&mut self, id: &NodeId, owner: &GraphId) {
+        if let Some(owners) = self.owners.get_mut() {
+            owners.nodes.insert(id.clone(), owner.clone());
+        }
+    }
+
+    pub(crate) fn remove_node(&mut self, id: &NodeId) {
+        if let Some(owners) = self.owners.get_mut() {
+            owners.nodes.remove(id);
+        }
+    }
+
+    pub(crate) fn insert_edge(&mut self, id: &EdgeId, owner: &GraphId) {
+        if let Some(owners) = self.owners.get_mut() {
+            owners.edges.insert(id.clone(), owner.clone());
+        }
+    }
+
+    pub(crate) fn remove_edge(&mut self, id: &EdgeId) {
+        if let Some(owners) = self.owners.get_mut() {
+            owners.edges.remove(id);
+        }
+    }
+
+    pub(crate) fn insert_subgraph(&mut self, id: &GraphId, parent: &GraphId) {
+        if let Some(owners) = self.owners.get_mut() {
+            owners.subgraphs.insert(id.clone(), parent.clone());
+        }
+    }
+
+    pub(crate) fn remove_subgraph(&mut self, id: &GraphId) {
+        if let Some(owners) = self.owners.get_mut() {
+            owners.subgraphs.remove(id);
+        }
+    }
+}
+
+impl Clone for OwnerIndex {
+    fn clone(&self) -> OwnerIndex {
+        OwnerIndex::default()
+    }
+}
+
+impl Graph {
+    /// Search for the id of the subgraph directly holding the node with `id`
+    pub fn parent_subgraph(&self, id: &NodeId) -> Option<&GraphId> {
+        self.owners.get(&self.subgraphs).nodes.get(id)
+    }
+
+    /// Search for the id of the parent of the subgraph with `id`,
+    /// which is `None` for the root subgraph
+    pub fn subgraph_parent(&self, id: &GraphId) -> Option<&GraphId> {
+        self.owners.get(&self.subgraphs).subgraphs.get(id)
+    }
+
+    /// Chain of subgraphs from the root down to the subgraph directly holding the node with `id`,
+    /// which is empty if there is no such node
+    pub fn subgraph_path(&self, id: &NodeId) -> Vec<&GraphId> {
+        let mut path = Vec::new();
+
+        let mut owner = self.parent_subgraph(id);
+        while let Some(id) = owner {
+            path.push(id);
+            owner = self.subgraph_parent(id);
+        }
+        path.reverse();
+
+        path
+    }
+
+    /// Depth of the subgraph with `id` in the subgraph tree, where the root lies at zero.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no subgraph with `id`,
+    /// `Ok` with the number of ancestors of the subgraph otherwise.
+    pub fn subtree_depth(&self, id: &GraphId) -> Result<usize, DotGraphError> {
+        if !self.subgraphs.contains(id) {
+            return Err(DotGraphError::NoSuchSubGraph(id.to_string(), self.id.clone()));
+        }
+
+        let mut depth = 0;
+        let mut parent = self.subgraph_parent(id);
+        while let Some(id) = parent {
+            depth += 1;
+            parent = self.subgraph_parent(id);
+        }
+
+        Ok(depth)
+    }
+
+    /// Ids of the subgraphs at `depth` in the subgraph tree, where the root lies at zero,
+    /// in the order of their ids
+    pub fn subgraphs_at_depth(&self, depth: usize) -> Vec<&GraphId> {
+        let Some(root) = self.subgraphs.get(&self.id) else {
+            return Vec::new();
+        };
+
+        let mut level = vec![&root.id];
+        for _ in 0..depth {
+            level = level.into_iter().flat_map(|id| &self.subtree[id]).collect();
+        }
+        level.sort_unstable();
+
+        level
+    }
+
+    /// Search for the id of the subgraph directly holding the edge with `id`
+    pub(crate) fn edge_owner(&self, id: &EdgeId) -> Option<&GraphId> {
+        self.owners.get(&self.subgraphs).edges.get(id)
+    }
+}
@@ -0,0 +1,283 @@
+use crate::{edge::Edge, error::DotGraphError, graphs::graph::Graph, node::NodeId};
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+#[derive(Debug, Clone)]
+/// A `ShortestPaths` holds the distances of nodes reachable from a source node,
+/// along with the predecessor of each node on a shortest path to it.
+pub struct ShortestPaths<'a, D> {
+    /// Id of the node the paths start from
+    source: &'a NodeId,
+    /// Distance from the source to each reachable node
+    distances: HashMap<&'a NodeId, D>,
+    /// Node preceding each reachable node, except the source, on a shortest path
+    predecessors: HashMap<&'a NodeId, &'a NodeId>,
+}
+
+impl<'a, D: Copy> ShortestPaths<'a, D> {
+    pub fn source(&self) -> &'a NodeId {
+        self.source
+    }
+
+    pub fn distances(&self) -> &HashMap<&'a NodeId, D> {
+        &self.distances
+    }
+
+    pub fn predecessors(&self) -> &HashMap<&'a NodeId, &'a NodeId> {
+        &self.predecessors
+    }
+
+    /// Distance from the source to the node with `id`, if it is reachable
+    pub fn distance(&self, id: &NodeId) -> Option<D> {
+        self.distances.get(id).copied()
+    }
+
+    /// Ids of nodes along a shortest path from the source to the node with `id`,
+    /// including both ends, if it is reachable
+    pub fn path_to(&self, id: &NodeId) -> Option<Vec<&'a NodeId>> {
+        let (&to, _) = self.distances.get_key_value(id)?;
+
+        let mut path = vec![to];
+        while let Some(&predecessor) = self.predecessors.get(path.last().unwrap()) {
+            path.push(predecessor);
+        }
+        path.reverse();
+
+        Some(path)
+    }
+}
+
+/// A node to visit at a tentative distance, ordered by the distance and then by the id
+struct Visit<'a>(f64, &'a NodeId);
+
+impl PartialEq for Visit<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Visit<'_> {}
+
+impl PartialOrd for Visit<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Visit<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then_with(|| self.1.cmp(other.1))
+    }
+}
+
+impl Graph {
+    /// Search for a path with the fewest edges from the node `from` to the node `to`.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if either node does not exist,
+    /// `Ok(None)` if `to` is not reachable from `from`,
+    /// otherwise `Ok` with the ids of nodes along the path, including both ends.
+    pub fn find_path(
+        &self,
+        from: &NodeId,
+        to: &NodeId,
+    ) -> Result<Option<Vec<&NodeId>>, DotGraphError> {
+        let from = self.node_id(from)?;
+        let to = self.node_id(to)?;
+
+        let mut parents: HashMap<&NodeId, &NodeId> = HashMap::new();
+        let mut frontier = VecDeque::from([from]);
+        while let Some(id) = frontier.pop_front() {
+            if id == to {
+                let mut path = vec![to];
+                while let Some(parent) = parents.get(path.last().unwrap()) {
+                    path.push(parent);
+                }
+                path.reverse();
+
+                return Ok(Some(path));
+            }
+
+            let mut nexts: Vec<&NodeId> = self.fwdmap[id].iter().collect();
+            nexts.sort_unstable();
+            for next in nexts {
+                if next != from && !parents.contains_key(next) {
+                    parents.insert(next, id);
+                    frontier.push_back(next);
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Search for a path with the fewest edges from the node `from` to the node `to`,
+    /// the same as `Graph::find_path`.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if either node does not exist,
+    /// `Ok(None)` if `to` is not reachable from `from`,
+    /// otherwise `Ok` with the ids of nodes along the path, including both ends.
+    pub fn shortest_path(
+        &self,
+        from: &NodeId,
+        to: &NodeId,
+    ) -> Result<Option<Vec<&NodeId>>, DotGraphError> {
+        self.find_path(from, to)
+    }
+
+    /// Count the fewest edges to traverse from the node `from` to every node reachable from it.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node with `from`,
+    /// `Ok` with the distances and predecessors of the reachable nodes otherwise.
+    pub fn shortest_path_lengths(
+        &self,
+        from: &NodeId,
+    ) -> Result<ShortestPaths<'_, usize>, DotGraphError> {
+        let source = self.node_id(from)?;
+
+        let mut distances = HashMap::from([(source, 0)]);
+        let mut predecessors = HashMap::new();
+        let mut frontier = VecDeque::from([source]);
+        while let Some(id) = frontier.pop_front() {
+            let distance = distances[id] + 1;
+
+            let mut nexts: Vec<&NodeId> = self.fwdmap[id].iter().collect();
+            nexts.sort_unstable();
+            for next in nexts {
+                if !distances.contains_key(next) {
+                    distances.insert(next, distance);
+                    predecessors.insert(next, id);
+                    frontier.push_back(next);
+                }
+            }
+        }
+
+        Ok(ShortestPaths { source, distances, predecessors })
+    }
+
+    /// Compute the lightest paths from the node `from` to every node reachable from it,
+    /// where each edge weighs as much as `weight` returns for it.
+    ///
+    /// Weights must not be negative, and the lightest of parallel edges is taken.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node with `from`,
+    /// `Ok` with the distances and predecessors of the reachable nodes otherwise.
+    pub fn dijkstra<F>(
+        &self,
+        from: &NodeId,
+        weight: F,
+    ) -> Result<ShortestPaths<'_, f64>, DotGraphError>
+    where
+        F: Fn(&Edge) -> f64,
+    {
+        let source = self.node_id(from)?;
+
+        let mut outgoing: HashMap<&NodeId, Vec<(&NodeId, f64)>> = HashMap::new();
+        for edge in &self.edges {
+            outgoing.entry(&edge.id.from).or_default().push((&edge.id.to, weight(edge)));
+        }
+
+        let mut distances = HashMap::new();
+        let mut predecessors = HashMap::new();
+        let mut tentatives = HashMap::from([(source, 0.0)]);
+        let mut heap = BinaryHeap::from([Reverse(Visit(0.0, source))]);
+        while let Some(Reverse(Visit(distance, id))) = heap.pop() {
+            if distances.contains_key(id) {
+                continue;
+            }
+            distances.insert(id, distance);
+
+            for &(next, weight) in outgoing.get(id).into_iter().flatten() {
+                let tentative = distance + weight;
+                if !distances.contains_key(next)
+                    && tentatives.get(next).is_none_or(|&current| tentative < current)
+                {
+                    tentatives.insert(next, tentative);
+                    predecessors.insert(next, id);
+                    heap.push(Reverse(Visit(tentative, next)));
+                }
+            }
+        }
+
+        Ok(ShortestPaths { source, distances, predecessors })
+    }
+
+    /// Compute the lightest paths from the node `from` to every node reachable from it,
+    /// where each edge weighs as much as its `weight` attribute, or 1 if it has none.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node with `from`,
+    /// `Ok` with the distances and predecessors of the reachable nodes otherwise.
+    pub fn dijkstra_by_weight(
+        &self,
+        from: &NodeId,
+    ) -> Result<ShortestPaths<'_, f64>, DotGraphError> {
+        self.dijkstra(from, |edge| edge.weight().unwrap_or(1.0))
+    }
+
+    /// Search for the heaviest path in this acyclic graph, i.e., its critical path,
+    /// where each edge weighs as much as `weight` returns for it.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if this graph has a cycle,
+    /// `Ok` with the ids of nodes along the path, including both ends, and its weight otherwise.
+    /// The path is empty if this graph has no nodes.
+    pub fn longest_path<F>(&self, weight: F) -> Result<(Vec<&NodeId>, f64), DotGraphError>
+    where
+        F: Fn(&Edge) -> f64,
+    {
+        let sorted = self.topsort()?;
+
+        let mut outgoing: HashMap<&NodeId, Vec<(&NodeId, f64)>> = HashMap::new();
+        for edge in &self.edges {
+            outgoing.entry(&edge.id.from).or_default().push((&edge.id.to, weight(edge)));
+        }
+
+        let mut distances: HashMap<&NodeId, f64> = HashMap::new();
+        let mut predecessors: HashMap<&NodeId, &NodeId> = HashMap::new();
+        let mut last: Option<(&NodeId, f64)> = None;
+        for &id in &sorted {
+            let distance = *distances.entry(id).or_insert(0.0);
+            if last.is_none_or(|(_, longest)| distance > longest) {
+                last = Some((id, distance));
+            }
+
+            for &(next, weight) in outgoing.get(id).into_iter().flatten() {
+                let tentative = distance + weight;
+                if distances.get(next).is_none_or(|&current| tentative > current) {
+                    distances.insert(next, tentative);
+                    predecessors.insert(next, id);
+                }
+            }
+        }
+
+        let Some((last, length)) = last else {
+            return Ok((Vec::new(), 0.0));
+        };
+        let mut path = vec![last];
+        while let Some(&predecessor) = predecessors.get(path.last().unwrap()) {
+            path.push(predecessor);
+        }
+        path.reverse();
+
+        Ok((path, length))
+    }
+
+    /// The id of the node with `id`, borrowed from this graph
+    pub(crate) fn node_id(&self, id: &NodeId) -> Result<&NodeId, DotGraphError> {
+        self.nodes
+            .get(id)
+            .map(|node| &node.id)
+            .ok_or_else(|| DotGraphError::NoSuchNode(id.to_string(), self.id.clone()))
+    }
+}
@@ -0,0 +1,172 @@
+use crate::{
+    graphs::graph::Graph,
+    node::{Node, NodeId},
+};
+
+use std::collections::{HashMap, HashSet};
+
+/// An embedding of a pattern graph, from ids of pattern nodes to ids of matched nodes
+pub type Embedding<'p, 'g> = HashMap<&'p NodeId, &'g NodeId>;
+
+impl Graph {
+    /// Search for every embedding of `pattern` in this `Graph`, mapping distinct pattern nodes
+    /// to distinct nodes such that every pattern edge has an edge between their images.
+    ///
+    /// A pattern node only matches nodes holding all its attributes with the same values,
+    /// so a pattern without attributes matches by structure alone.
+    ///
+    /// # Returns
+    ///
+    /// The embeddings, in a stable order. Symmetric patterns embed once per symmetry.
+    pub fn find_pattern<'p>(&self, pattern: &'p Graph) -> Vec<Embedding<'p, '_>> {
+        self.find_pattern_with(pattern, |pattern, node| {
+            pattern
+                .attrs
+                .iter()
+                .all(|attr| node.attrs.get(attr).is_some_and(|other| other.value == attr.value))
+        })
+    }
+
+    /// Search for every embedding of `pattern` in this `Graph`, like `Graph::find_pattern`,
+    /// where a pattern node matches the nodes for which `matches` returns true.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The graph to embed, expected to be much smaller than this graph
+    /// * `matches` - A predicate over a pattern node and a node to map it to
+    pub fn find_pattern_with<'p, F>(&self, pattern: &'p Graph, matches: F) -> Vec<Embedding<'p, '_>>
+    where
+        F: Fn(&Node, &Node) -> bool,
+    {
+        let order = pattern.match_order();
+
+        let mut embeddings = Vec::new();
+        let mut state = Matcher {
+            pattern,
+            graph: self,
+            matches: &matches,
+            order: &order,
+            mapping: HashMap::new(),
+            used: HashSet::new(),
+        };
+        state.extend(&mut embeddings);
+
+        embeddings
+    }
+
+    /// Order nodes to match such that each node is adjacent to an earlier one whenever possible,
+    /// starting every connected component from its node of the highest degree
+    fn match_order(&self) -> Vec<&NodeId> {
+        let degree = |id: &NodeId| self.fwdmap[id].len() + self.bwdmap[id].len();
+        let mut ids: Vec<&NodeId> = self.nodes.iter().map(|node| &node.id).collect();
+        ids.sort_unstable_by(|a, b| degree(b).cmp(&degree(a)).then(a.cmp(b)));
+
+        let mut order = Vec::with_capacity(ids.len());
+        let mut ordered: HashSet<&NodeId> = HashSet::new();
+        for start in ids {
+            if !ordered.insert(start) {
+                continue;
+            }
+
+            let begin = order.len();
+            order.push(start);
+            let mut next = begin;
+            while next < order.len() {
+                let mut adjacent: Vec<&NodeId> =
+                    self.fwdmap[order[next]].iter().chain(&self.bwdmap[order[next]]).collect();
+                adjacent.sort_unstable_by(|a, b| degree(b).cmp(&degree(a)).then(a.cmp(b)));
+                for id in adjacent {
+                    if ordered.insert(id) {
+                        order.push(id);
+                    }
+                }
+                next += 1;
+            }
+        }
+
+        order
+    }
+}
+
+/// State of a depth-first search for embeddings of `pattern` in `graph`
+struct Matcher<'p, 'g, 'f, F> {
+    pattern: &'p Graph,
+    graph: &'g Graph,
+    matches: &'f F,
+    order: &'f [&'p NodeId],
+    /// Pattern nodes matched so far to their images
+    mapping: Embedding<'p, 'g>,
+    /// Images of the matched pattern nodes
+    used: HashSet<&'g NodeId>,
+}
+
+impl<'p, 'g, F> Matcher<'p, 'g, '_, F>
+where
+    F: Fn(&Node, &Node) -> bool,
+{
+    fn extend(&mut self, embeddings: &mut Vec<Embedding<'p, 'g>>) {
+        let Some(&id) = self.order.get(self.mapping.len()) else {
+            embeddings.push(self.mapping.clone());
+            return;
+        };
+
+        for candidate in self.candidates(id) {
+            if self.is_feasible(id, candidate) {
+                self.mapping.insert(id, candidate);
+                self.used.insert(candidate);
+                self.extend(embeddings);
+                self.mapping.remove(id);
+                self.used.remove(candidate);
+            }
+        }
+    }
+
+    /// Nodes the pattern node with `id` may map to, restricted to the neighbors of the image
+    /// of a matched pattern neighbor if there is one
+    fn candidates(&self, id: &NodeId) -> Vec<&'g NodeId> {
+        let from_mapped = self.pattern.bwdmap[id].iter().find_map(|from| self.mapping.get(from));
+        let to_mapped = self.pattern.fwdmap[id].iter().find_map(|to| self.mapping.get(to));
+
+        let mut candidates: Vec<&NodeId> = match (from_mapped, to_mapped) {
+            (Some(&from), _) => self.graph.fwdmap[from].iter().collect(),
+            (None, Some(&to)) => self.graph.bwdmap[to].iter().collect(),
+            (None, None) => self.graph.nodes.iter().map(|node| &node.id).collect(),
+        };
+        candidates.sort_unstable();
+
+        candidates
+    }
+
+    fn is_feasible(&self, id: &'p NodeId, candidate: &'g NodeId) -> bool {
+        let (pattern, graph) = (self.pattern, self.graph);
+        if self.used.contains(candidate)
+            || graph.fwdmap[candidate].len() < pattern.fwdmap[id].len()
+            || graph.bwdmap[candidate].len() < pattern.bwdmap[id].len()
+        {
+            return false;
+        }
+
+        let image = |other: &NodeId| {
+            if other == id {
+                Some(candidate)
+            } else {
+                self.mapping.get(other).copied()
+            }
+        };
+        let tos_ok = pattern.fwdmap[id]
+            .iter()
+            .filter_map(&image)
+            .all(|to| graph.fwdmap[candidate].contains(to));
+        let froms_ok = pattern.bwdmap[id]
+            .iter()
+            .filter_map(image)
+            .all(|from| graph.bwdmap[candidate].contains(from));
+
+        tos_ok
+            && froms_ok
+            && (self.matches)(
+                pattern.search_node(id).unwrap(),
+                graph.search_node(candidate).unwrap(),
+            )
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{edge::EdgeId, error::DotGraphError, graphs::graph::Graph, node::NodeId};
+
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+pub(crate) type Payload = Arc<dyn Any + Send + Sync>;
+
+#[derive(Debug, Clone, Default)]
+/// `Payloads` associate user data of arbitrary types with nodes and edges by their ids.
+///
+/// Payloads are shared, not copied, between a graph and the graphs constructed from it.
+pub(crate) struct Payloads {
+    nodes: HashMap<NodeId, Payload>,
+    edges: HashMap<EdgeId, Payload>,
+}
+
+impl Payloads {
+    /// Payloads of the given nodes and edges only
+    pub(crate) fn extract(&self, nodes: &HashSet<&NodeId>, edges: &HashSet<&EdgeId>) -> Payloads {
+        let nodes = self
+            .nodes
+            .iter()
+            .filter(|(id, _)| nodes.contains(id))
+            .map(|(id, payload)| (id.clone(), payload.clone()))
+            .collect();
+        let edges = self
+            .edges
+            .iter()
+            .filter(|(id, _)| edges.contains(id))
+            .map(|(id, payload)| (id.clone(), payload.clone()))
+            .collect();
+
+        Payloads { nodes, edges }
+    }
+
+    /// Remove the payloads of the given nodes and edges, returning them
+    pub(crate) fn take(&mut self, nodes: &HashSet<&NodeId>, edges: &HashSet<&EdgeId>) -> Payloads {
+        let taken = self.extract(nodes, edges);
+        self.nodes.retain(|id, _| !nodes.contains(id));
+        self.edges.retain(|id, _| !edges.contains(id));
+
+        taken
+    }
+
+    pub(crate) fn extend(&mut self, payloads: Payloads) {
+        self.nodes.extend(payloads.nodes);
+        self.edges.extend(payloads.edges);
+    }
+
+    pub(crate) fn node(&self, id: &NodeId) -> Option<Payload> {
+        self.nodes.get(id).cloned()
+    }
+
+    pub(crate) fn edge(&self, id: &EdgeId) -> Option<Payload> {
+        self.edges.get(id).cloned()
+    }
+
+    pub(crate) fn insert_node(&mut self, id: NodeId, payload: Payload) {
+        self.nodes.insert(id, payload);
+    }
+
+    pub(crate) fn insert_edge(&mut self, id: EdgeId, payload: Payload) {
+        self.edges.insert(id, payload);
+    }
+
+    pub(crate) fn remove_node(&mut self, id: &NodeId) {
+        self.nodes.remove(id);
+    }
+
+    pub(crate) fn remove_edge(&mut self, id: &EdgeId) {
+        self.edges.remove(id);
+    }
+
+    /// Move every payload to the id given by `nodes` or `edges`
+    pub(crate) fn relabel<N, E>(&mut self, nodes: N, edges: E)
+    where
+        N: Fn(&NodeId) -> NodeId,
+        E: Fn(&EdgeId) -> EdgeId,
+    {
+        self.nodes = self.nodes.drain().map(|(id, payload)| (nodes(&id), payload)).collect();
+        self.edges = self.edges.drain().map(|(id, payload)| (edges(&id), payload)).collect();
+    }
+}
+
+impl Graph {
+    /// Attach `payload` to the node with `id`, replacing any payload it had.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node with `id`,
+    /// `Ok` otherwise.
+    pub fn set_node_payload<T>(&mut self, id: &NodeId, payload: T) -> Result<(), DotGraphError>
+    where
+        T: Any + Send + Sync,
+    {
+        if !self.nodes.contains(id) {
+            return Err(DotGraphError::NoSuchNode(id.to_string(), self.id.clone()));
+        }
+
+        self.payloads.nodes.insert(id.clone(), Arc::new(payload));
+
+        Ok(())
+    }
+
+    /// Payload of the node with `id`, if it has one of type `T`
+    pub fn node_payload<T: Any>(&self, id: &NodeId) -> Option<&T> {
+        self.payloads.nodes.get(id).and_then(|payload| payload.downcast_ref())
+    }
+
+    /// Attach `payload` to the edge with `id`, replacing any payload it had.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no edge with `id`,
+    /// `Ok` otherwise.
+    pub fn set_edge_payload<T>(&mut self, id: &EdgeId, payload: T) -> Result<(), DotGraphError>
+    where
+        T: Any + Send + Sync,
+    {
+        if !self.edges.contains(id) {
+            return Err(DotGraphError::NoSuchEdge(id.to_string(), self.id.clone()));
+        }
+
+        self.payloads.edges.insert(id.clone(), Arc::new(payload));
+
+        Ok(())
+    }
+
+    /// Payload of the edge with `id`, if it has one of type `T`
+    pub fn edge_payload<T: Any>(&self, id: &EdgeId) -> Option<&T> {
+        self.payloads.edges.get(id).and_then(|payload| payload.downcast_ref())
+    }
+}
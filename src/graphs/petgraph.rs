@@ -0,0 +1,166 @@
+use crate::{
+    attr::Attr,
+    edge::{Edge, EdgeId},
+    error::DotGraphError,
+    graphs::{
+        graph::{Graph, GraphId},
+        igraph::IGraph,
+    },
+    node::{Node, NodeId},
+};
+
+use std::collections::{HashMap, HashSet};
+
+use ::petgraph::graph::{DiGraph, NodeIndex};
+use ::petgraph::visit::EdgeRef;
+
+impl Graph {
+    /// Convert this `Graph` into a `petgraph` graph borrowing its nodes and edges,
+    /// so that algorithms of `petgraph` can run on it.
+    ///
+    /// Nodes are added in the order of their ids and edges in the order of their ends,
+    /// so that indices are the same for equal graphs. Subgraphs are not kept.
+    ///
+    /// # Returns
+    ///
+    /// The converted graph, and the index of each node in it.
+    /// The id of the node at an index is that of its weight.
+    pub fn to_petgraph(&self) -> (DiGraph<&Node, &Edge>, HashMap<&NodeId, NodeIndex>) {
+        let mut nodes: Vec<&Node> = self.nodes.iter().collect();
+        nodes.sort_unstable_by(|a, b| a.id.cmp(&b.id));
+        let mut edges: Vec<&Edge> = self.edges.iter().collect();
+        edges.sort_unstable_by(|a, b| a.id.sort_key().cmp(&b.id.sort_key()));
+
+        let mut graph = DiGraph::with_capacity(nodes.len(), edges.len());
+        let indices: HashMap<&NodeId, NodeIndex> =
+            nodes.into_iter().map(|node| (&node.id, graph.add_node(node))).collect();
+        for edge in edges {
+            graph.add_edge(indices[&edge.id.from], indices[&edge.id.to], edge);
+        }
+
+        (graph, indices)
+    }
+
+    /// Convert a `petgraph` graph into a `Graph` named `G`, so that it can be written to dot.
+    ///
+    /// Parallel edges are merged into one, where attributes of later edges override those of
+    /// earlier ones, as when parsing them from dot.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph to convert
+    /// * `node_fmt` - Formats the node at an index with its weight
+    /// * `edge_fmt` - Formats attributes of an edge with its weight
+    ///
+    /// # Returns
+    ///
+    /// `Err` if two nodes are formatted with the same id,
+    /// `Ok` with the converted graph otherwise.
+    pub fn from_petgraph<N, E, F, G>(
+        graph: &DiGraph<N, E>,
+        node_fmt: F,
+        edge_fmt: G,
+    ) -> Result<Graph, DotGraphError>
+    where
+        F: Fn(NodeIndex, &N) -> Node,
+        G: Fn(&E) -> HashSet<Attr>,
+    {
+        Graph::from_petgraph_with_clusters(graph, node_fmt, edge_fmt, |_| None)
+    }
+
+    /// Convert a `petgraph` graph into a `Graph` named `G` as `Graph::from_petgraph` does,
+    /// grouping nodes into subgraphs of the root, e.g., with ids prefixed by `cluster` to have
+    /// them drawn as clusters. An edge belongs to the subgraph of its ends if they share one.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph to convert
+    /// * `node_fmt` - Formats the node at an index with its weight
+    /// * `edge_fmt` - Formats attributes of an edge with its weight
+    /// * `cluster` - Id of the subgraph to put a formatted node in, or `None` to leave it
+    ///   in the root
+    ///
+    /// # Returns
+    ///
+    /// `Err` if two nodes are formatted with the same id or if a node is put in a subgraph
+    /// named as the root, `Ok` with the converted graph otherwise.
+    pub fn from_petgraph_with_clusters<N, E, F, G, C>(
+        graph: &DiGraph<N, E>,
+        node_fmt: F,
+        edge_fmt: G,
+        cluster: C,
+    ) -> Result<Graph, DotGraphError>
+    where
+        F: Fn(NodeIndex, &N) -> Node,
+        G: Fn(&E) -> HashSet<Attr>,
+        C: Fn(&Node) -> Option<GraphId>,
+    {
+        let id = String::from("G");
+
+        let mut nodes: HashSet<Node> = HashSet::with_capacity(graph.node_count());
+        let mut ids: HashMap<NodeIndex, NodeId> = HashMap::with_capacity(graph.node_count());
+        let mut clusters: HashMap<NodeId, GraphId> = HashMap::new();
+        for index in graph.node_indices() {
+            let node = node_fmt(index, &graph[index]);
+            if nodes.contains(&node.id) {
+                return Err(DotGraphError::DuplicateNode(node.id, id));
+            }
+
+            if let Some(subgraph) = cluster(&node) {
+                if subgraph == id {
+                    return Err(DotGraphError::DuplicateSubGraph(subgraph, id));
+                }
+                clusters.insert(node.id.clone(), subgraph);
+            }
+            ids.insert(index, node.id.clone());
+            nodes.insert(node);
+        }
+
+        let mut edges: HashSet<Edge> = HashSet::with_capacity(graph.edge_count());
+        for edge in graph.edge_references() {
+            let from = ids[&edge.source()].clone();
+            let to = ids[&edge.target()].clone();
+            let edge_id = EdgeId::new(from, None, to, None);
+
+            let mut attrs = edges.take(&edge_id).map(|edge| edge.attrs).unwrap_or_default();
+            for attr in edge_fmt(edge.weight()) {
+                attrs.replace(attr);
+            }
+            edges.insert(Edge::new(edge_id, attrs));
+        }
+
+        let mut members: HashMap<&GraphId, (HashSet<Node>, HashSet<Edge>)> = HashMap::new();
+        let mut root_nodes = HashSet::new();
+        let mut root_edges = HashSet::new();
+        for node in &nodes {
+            match clusters.get(&node.id) {
+                Some(subgraph) => {
+                    members.entry(subgraph).or_default().0.insert(node.clone());
+                }
+                None => {
+                    root_nodes.insert(node.clone());
+                }
+            }
+        }
+        for edge in &edges {
+            match (clusters.get(&edge.id.from), clusters.get(&edge.id.to)) {
+                (Some(from), Some(to)) if from == to => {
+                    members.entry(from).or_default().1.insert(edge.clone());
+                }
+                _ => {
+                    root_edges.insert(edge.clone());
+                }
+            }
+        }
+
+        let igraphs = members
+            .into_iter()
+            .map(|(subgraph, (nodes, edges))| {
+                IGraph::new(subgraph.clone(), HashSet::new(), nodes, edges, HashSet::new())
+            })
+            .collect();
+        let root = IGraph::new(id.clone(), igraphs, root_nodes, root_edges, HashSet::new());
+
+        Graph::new(id, root, nodes, edges)
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{
+    edge::EdgeId,
+    graphs::graph::{EdgeMap, Graph},
+    node::NodeId,
+};
+
+use std::collections::{HashMap, HashSet};
+
+use rayon::prelude::*;
+
+impl Graph {
+    /// Constructs a new `Graph`, repeatedly removing nodes with no successors.
+    ///
+    /// # Arguments
+    ///
+    /// * `rounds` - Maximum number of removal rounds, where each round removes
+    ///   all nodes that have no successors left
+    ///
+    /// # Returns
+    ///
+    /// The pruned `Graph`, and the ids of removed nodes in order of removal.
+    pub fn prune_leaves(&self, rounds: usize) -> (Graph, Vec<&NodeId>) {
+        self.prune(rounds, &self.fwdmap, &self.bwdmap)
+    }
+
+    /// Constructs a new `Graph`, repeatedly removing nodes with no predecessors.
+    ///
+    /// # Arguments
+    ///
+    /// * `rounds` - Maximum number of removal rounds, where each round removes
+    ///   all nodes that have no predecessors left
+    ///
+    /// # Returns
+    ///
+    /// The pruned `Graph`, and the ids of removed nodes in order of removal.
+    pub fn prune_sources(&self, rounds: usize) -> (Graph, Vec<&NodeId>) {
+        self.prune(rounds, &self.bwdmap, &self.fwdmap)
+    }
+
+    /// Constructs a new `Graph`, removing each edge whose ends are also connected by a path
+    /// through other nodes, as a bounded alternative to the transitive reduction.
+    ///
+    /// Edges are checked one by one in the order of their ends, against the edges remaining,
+    /// so which nodes reach which is kept even on cyclic graphs.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_skip` - Maximum number of nodes on the path in between, beyond which an edge is
+    ///   kept even if its ends are connected by a longer path
+    ///
+    /// # Returns
+    ///
+    /// The simplified `Graph`, and the ids of removed edges in order of removal.
+    pub fn simplify(&self, max_skip: usize) -> (Graph, Vec<&EdgeId>) {
+        let mut ids: Vec<&EdgeId> =
+            self.edges.par_iter().map(|edge| &edge.id).filter(|id| id.from != id.to).collect();
+        ids.sort_unstable_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+
+        // number of remaining edges from each node to each of its successors
+        let mut counts: HashMap<&NodeId, HashMap<&NodeId, usize>> = HashMap::new();
+        for edge in &self.edges {
+            *counts.entry(&edge.id.from).or_default().entry(&edge.id.to).or_default() += 1;
+        }
+
+        let mut removed = Vec::new();
+        for id in ids {
+            let (from, to) = (&id.from, &id.to);
+            let nexts = |id: &NodeId| counts.get(id).into_iter().flat_map(HashMap::keys).copied();
+
+            let mut visited: HashSet<&NodeId> = HashSet::from([from, to]);
+            let mut frontier: Vec<&NodeId> =
+                nexts(from).filter(|next| visited.insert(next)).collect();
+            let mut skipped = 1;
+            let mut bypassed = false;
+            while !frontier.is_empty() && skipped <= max_skip {
+                if frontier.iter().any(|&id| counts.get(id).is_some_and(|tos| tos.contains_key(to)))
+                {
+                    bypassed = true;
+                    break;
+                }
+
+                frontier = frontier
+                    .into_iter()
+                    .flat_map(|id| nexts(id).collect::<Vec<_>>())
+                    .filter(|next| visited.insert(next))
+                    .collect();
+                skipped += 1;
+            }
+
+            if bypassed {
+                let tos = counts.get_mut(from).unwrap();
+                let count = tos.get_mut(to).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    tos.remove(to);
+                }
+                removed.push(id);
+            }
+        }
+
+        let removed_ids: HashSet<&EdgeId> = removed.par_iter().copied().collect();
+        let simplified = self.filter_edges(|edge| !removed_ids.contains(&edge.id));
+
+        (simplified, removed)
+    }
+
+    /// Repeatedly remove nodes with no `nexts`, where `prevs` is the opposite of `nexts`.
+    fn prune<'a>(
+        &'a self,
+        rounds: usize,
+        nexts: &'a EdgeMap,
+        prevs: &'a EdgeMap,
+    ) -> (Graph, Vec<&'a NodeId>) {
+        let mut degrees: HashMap<&NodeId, usize> =
+            nexts.par_iter().map(|(id, nexts)| (id, nexts.len())).collect();
+
+        let mut frontier: Vec<&NodeId> =
+            degrees.par_iter().filter_map(|(&id, &degree)| (degree == 0).then_some(id)).collect();
+        frontier.sort_unstable();
+
+        let mut removed = Vec::new();
+        for _ in 0..rounds {
+            if frontier.is_empty() {
+                break;
+            }
+
+            let mut next_frontier = Vec::new();
+            for &id in &frontier {
+                for prev in &prevs[id] {
+                    let degree = degrees.get_mut(prev).unwrap();
+                    *degree -= 1;
+                    if *degree == 0 {
+                        next_frontier.push(prev);
+                    }
+                }
+            }
+            next_frontier.sort_unstable();
+
+            removed.append(&mut frontier);
+            frontier = next_frontier;
+        }
+
+        let removed_ids: HashSet<&NodeId> = removed.par_iter().copied().collect();
+        let node_ids: Vec<&NodeId> = self
+            .nodes
+            .par_iter()
+            .map(|node| &node.id)
+            .filter(|id| !removed_ids.contains(id))
+            .collect();
+
+        (self.filter(&node_ids), removed)
+    }
+}
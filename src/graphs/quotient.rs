@@ -0,0 +1,104 @@
+use crate::{
+    attr::Attr,
+    edge::{Edge, EdgeId},
+    graphs::{
+        extraction::{ExtractionMap, Mapping},
+        graph::Graph,
+        igraph::IGraph,
+    },
+    node::{Node, NodeId},
+};
+
+use std::collections::{HashMap, HashSet};
+
+use rayon::prelude::*;
+
+impl Graph {
+    /// Constructs a new `Graph` with a node for each group of nodes of this graph, as given by
+    /// `partition`, and an edge for each pair of groups that edges of this graph go between.
+    ///
+    /// Both nodes and edges of the new graph hold the number of nodes or edges they stand for
+    /// as their `count`, and nodes keep the attributes with keys in `keys` that all nodes of the
+    /// group share. Edges within a group are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `partition` - A function mapping the id of each node to the id of its group
+    /// * `keys` - Keys of the node attributes to keep, if their values agree within a group
+    pub fn quotient<F>(&self, partition: F, keys: &[&str]) -> Graph
+    where
+        F: Fn(&NodeId) -> NodeId + Sync,
+    {
+        self.quotient_with_map(partition, keys).0
+    }
+
+    /// Same as [`Graph::quotient`], but also returns an `ExtractionMap`
+    /// relating the elements of the new `Graph` to those of this one,
+    /// where each group maps to its nodes and each edge to the edges it aggregates.
+    pub fn quotient_with_map<F>(&self, partition: F, keys: &[&str]) -> (Graph, ExtractionMap)
+    where
+        F: Fn(&NodeId) -> NodeId + Sync,
+    {
+        let groups: HashMap<&NodeId, NodeId> =
+            self.nodes.par_iter().map(|node| (&node.id, partition(&node.id))).collect();
+
+        let mut members: HashMap<&NodeId, Vec<&Node>> = HashMap::new();
+        for node in &self.nodes {
+            members.entry(&groups[&node.id]).or_default().push(node);
+        }
+
+        let mut node_map = Mapping::new();
+        let nodes: HashSet<Node> = members
+            .iter()
+            .map(|(&group, members)| {
+                let first = &members[0].attrs;
+                let mut attrs: HashSet<Attr> = keys
+                    .iter()
+                    .filter_map(|key| first.get(*key))
+                    .filter(|attr| {
+                        members.iter().all(|member| {
+                            member.attrs.get(&attr.key).is_some_and(|other| {
+                                other.value == attr.value && other.is_html == attr.is_html
+                            })
+                        })
+                    })
+                    .cloned()
+                    .collect();
+                attrs.replace(Attr::new(String::from("count"), members.len().to_string(), false));
+
+                Node::new(group.clone(), attrs)
+            })
+            .collect();
+        for node in &self.nodes {
+            node_map.insert(groups[&node.id].clone(), node.id.clone());
+        }
+
+        let mut counts: HashMap<EdgeId, usize> = HashMap::new();
+        let mut edge_map = Mapping::new();
+        for edge in &self.edges {
+            let (from, to) = (&groups[&edge.id.from], &groups[&edge.id.to]);
+            if from != to {
+                let id = EdgeId::new(from.clone(), None, to.clone(), None);
+                *counts.entry(id.clone()).or_default() += 1;
+                edge_map.insert(id, edge.id.clone());
+            }
+        }
+        let edges: HashSet<Edge> = counts
+            .into_iter()
+            .map(|(id, count)| {
+                let attrs =
+                    HashSet::from([Attr::new(String::from("count"), count.to_string(), false)]);
+                Edge::new(id, attrs)
+            })
+            .collect();
+
+        let root = self.subgraphs.get(&self.id).map(|root| root.attrs.clone()).unwrap_or_default();
+        let root = IGraph::new(self.id.clone(), HashSet::new(), nodes.clone(), edges.clone(), root);
+        let graph = Graph::new(self.id.clone(), root, nodes, edges).unwrap();
+
+        let subgraph_map = Mapping::identity([&self.id]);
+        let map = ExtractionMap::new(subgraph_map, node_map, edge_map);
+
+        (graph, map)
+    }
+}
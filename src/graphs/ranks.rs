@@ -0,0 +1,83 @@
+use crate::{edge::EdgeId, graphs::graph::Graph, node::NodeId};
+
+use std::collections::{HashMap, HashSet};
+
+impl Graph {
+    /// Assign every node of this `Graph` a rank, i.e., a layer of a layered drawing, without
+    /// computing a layout through Graphviz.
+    ///
+    /// Ranks follow the longest path layering, where an edge spans at least as many ranks as
+    /// its `minlen` attribute, or 1 if it has none. Edges chosen by `Graph::feedback_arc_set`
+    /// are ignored, so that cyclic graphs are ranked as well. Afterwards, nodes without edges
+    /// to them are moved down to right above their closest successor, shortening their edges.
+    ///
+    /// # Returns
+    ///
+    /// The rank of each node, where the nodes ranked first are ranked zero.
+    pub fn assign_ranks(&self) -> HashMap<&NodeId, usize> {
+        let ignored: HashSet<&EdgeId> = self.feedback_arc_set();
+        let spans: Vec<(&NodeId, &NodeId, usize)> = self
+            .edges
+            .iter()
+            .filter(|edge| !ignored.contains(&edge.id))
+            .map(|edge| {
+                let minlen = edge.attrs.get("minlen").and_then(|attr| attr.value.parse().ok());
+                (&edge.id.from, &edge.id.to, minlen.unwrap_or(1))
+            })
+            .collect();
+
+        let mut incoming: HashMap<&NodeId, Vec<(&NodeId, usize)>> = HashMap::new();
+        let mut outgoing: HashMap<&NodeId, Vec<(&NodeId, usize)>> = HashMap::new();
+        for &(from, to, minlen) in &spans {
+            incoming.entry(to).or_default().push((from, minlen));
+            outgoing.entry(from).or_default().push((to, minlen));
+        }
+
+        // the remaining edges are acyclic, so every node gets ranked after its predecessors
+        let mut indegrees: HashMap<&NodeId, usize> =
+            self.nodes.iter().map(|node| (&node.id, 0)).collect();
+        for &(_, to, _) in &spans {
+            *indegrees.get_mut(to).unwrap() += 1;
+        }
+        let mut ready: Vec<&NodeId> =
+            indegrees.iter().filter_map(|(&id, &indegree)| (indegree == 0).then_some(id)).collect();
+
+        let mut ranks: HashMap<&NodeId, usize> = HashMap::new();
+        let mut sorted = Vec::with_capacity(self.nodes.len());
+        while let Some(id) = ready.pop() {
+            let rank = incoming
+                .get(id)
+                .into_iter()
+                .flatten()
+                .map(|(from, minlen)| ranks[from] + minlen)
+                .max()
+                .unwrap_or(0);
+            ranks.insert(id, rank);
+            sorted.push(id);
+
+            for (to, _) in outgoing.get(id).into_iter().flatten() {
+                let indegree = indegrees.get_mut(to).unwrap();
+                *indegree -= 1;
+                if *indegree == 0 {
+                    ready.push(to);
+                }
+            }
+        }
+
+        for id in sorted.into_iter().rev() {
+            if incoming.contains_key(id) {
+                continue;
+            }
+            if let Some(rank) =
+                outgoing.get(id).into_iter().flatten().map(|(to, minlen)| ranks[to] - minlen).min()
+            {
+                ranks.insert(id, rank);
+            }
+        }
+
+        let least = ranks.values().copied().min().unwrap_or(0);
+        ranks.values_mut().for_each(|rank| *rank -= least);
+
+        ranks
+    }
+}
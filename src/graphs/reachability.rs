@@ -0,0 +1,84 @@
+use crate::{error::DotGraphError, graphs::graph::Graph, node::NodeId};
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+/// A `ReachabilityIndex` answers whether a node of a DAG reaches another in constant time,
+/// after preprocessing by `Graph::reachability_index`.
+///
+/// Nodes are covered by disjoint chains, i.e. paths, so that for every node it suffices to
+/// keep the earliest position it reaches along each chain.
+pub struct ReachabilityIndex<'a> {
+    /// Index of each node, to `chains` and `reach`
+    indices: HashMap<&'a NodeId, usize>,
+    /// Chain and position in the chain of each node
+    chains: Vec<(usize, usize)>,
+    /// Earliest position reachable along each chain from each node, `usize::MAX` for none
+    reach: Vec<Vec<usize>>,
+}
+
+impl ReachabilityIndex<'_> {
+    /// Whether the node `to` is reachable from the node `from`, which is true if they are the
+    /// same node and false if either is not a node of the indexed graph.
+    pub fn can_reach(&self, from: &NodeId, to: &NodeId) -> bool {
+        let (Some(&from), Some(&to)) = (self.indices.get(from), self.indices.get(to)) else {
+            return false;
+        };
+
+        let (chain, position) = self.chains[to];
+        self.reach[from][chain] <= position
+    }
+
+    /// Number of chains covering the graph, on which the size of the index depends
+    pub fn width(&self) -> usize {
+        self.reach.first().map_or(0, Vec::len)
+    }
+}
+
+impl Graph {
+    /// Build a `ReachabilityIndex` over this graph.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if this graph has a cycle,
+    /// otherwise `Ok` with the index.
+    pub fn reachability_index(&self) -> Result<ReachabilityIndex<'_>, DotGraphError> {
+        let sorted = self.topsort()?;
+        let indices: HashMap<&NodeId, usize> =
+            sorted.iter().enumerate().map(|(index, &id)| (id, index)).collect();
+
+        // greedily extend the chain of a predecessor that ends at it
+        let mut chains = vec![(0, 0); sorted.len()];
+        let mut tails: Vec<usize> = Vec::new();
+        for (index, id) in sorted.iter().enumerate() {
+            let mut froms: Vec<usize> = self.bwdmap[*id].iter().map(|from| indices[from]).collect();
+            froms.sort_unstable();
+
+            let extended = froms.into_iter().find_map(|from| {
+                let (chain, position) = chains[from];
+                (tails[chain] == from).then_some((chain, position + 1))
+            });
+            chains[index] = extended.unwrap_or_else(|| {
+                tails.push(index);
+                (tails.len() - 1, 0)
+            });
+            tails[chains[index].0] = index;
+        }
+
+        let width = tails.len();
+        let mut reach = vec![vec![usize::MAX; width]; sorted.len()];
+        for (index, id) in sorted.iter().enumerate().rev() {
+            let mut earliest = vec![usize::MAX; width];
+            let (chain, position) = chains[index];
+            earliest[chain] = position;
+            for to in &self.fwdmap[*id] {
+                for (earliest, &reached) in earliest.iter_mut().zip(&reach[indices[to]]) {
+                    *earliest = reached.min(*earliest);
+                }
+            }
+            reach[index] = earliest;
+        }
+
+        Ok(ReachabilityIndex { indices, chains, reach })
+    }
+}
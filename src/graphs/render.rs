@@ -0,0 +1,151 @@
+use crate::graphviz::{
+    agclose, agmemread, gvContext, gvFreeContext, gvFreeLayout, gvFreeRenderData, gvLayout,
+    gvRenderData,
+};
+use crate::{error::DotGraphError, graphs::graph::Graph};
+
+use std::ffi::CString;
+use std::io::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A `LayoutEngine` of Graphviz, which positions nodes and edges before rendering.
+pub enum LayoutEngine {
+    /// Hierarchical layout of directed graphs in ranks
+    Dot,
+    /// Spring model layout minimizing a global energy
+    Neato,
+    /// Force-directed spring model layout
+    Fdp,
+    /// Multiscale force-directed layout, for large graphs
+    Sfdp,
+    /// Circular layout
+    Circo,
+    /// Radial layout around a root node
+    Twopi,
+}
+
+impl LayoutEngine {
+    /// Name of the engine, as given to `dot -K`
+    pub fn name(&self) -> &'static str {
+        match self {
+            LayoutEngine::Dot => "dot",
+            LayoutEngine::Neato => "neato",
+            LayoutEngine::Fdp => "fdp",
+            LayoutEngine::Sfdp => "sfdp",
+            LayoutEngine::Circo => "circo",
+            LayoutEngine::Twopi => "twopi",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A `RenderFormat` a laid out graph is rendered to.
+pub enum RenderFormat {
+    Svg,
+    Png,
+    Pdf,
+    /// Dot annotated with the layout, e.g. `pos` of every node
+    Dot,
+    /// JSON annotated with the layout
+    Json,
+    /// Plain text of the layout
+    Plain,
+}
+
+impl RenderFormat {
+    /// Name of the format, as given to `dot -T`
+    pub fn name(&self) -> &'static str {
+        match self {
+            RenderFormat::Svg => "svg",
+            RenderFormat::Png => "png",
+            RenderFormat::Pdf => "pdf",
+            RenderFormat::Dot => "dot",
+            RenderFormat::Json => "json",
+            RenderFormat::Plain => "plain",
+        }
+    }
+}
+
+impl Graph {
+    /// Lay out the graph with `LayoutEngine::Dot` and render it in `format` to `writer`.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if the graph cannot be laid out or rendered, or if writing fails,
+    /// `Ok` otherwise
+    pub fn render<W>(&self, format: RenderFormat, writer: &mut W) -> Result<(), DotGraphError>
+    where
+        W: Write + ?Sized,
+    {
+        self.render_with(LayoutEngine::Dot, format, writer)
+    }
+
+    /// Lay out the graph with `engine` and render it in `format` to `writer`,
+    /// through the `gvc` library rather than the `dot` binary.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine` - Layout engine positioning nodes and edges
+    /// * `format` - Output format of the rendering
+    /// * `writer` - Where the rendered bytes are written
+    ///
+    /// # Returns
+    ///
+    /// `Err` if the graph cannot be laid out or rendered, or if writing fails,
+    /// `Ok` otherwise
+    pub fn render_with<W>(
+        &self,
+        engine: LayoutEngine,
+        format: RenderFormat,
+        writer: &mut W,
+    ) -> Result<(), DotGraphError>
+    where
+        W: Write + ?Sized,
+    {
+        let mut dot = Vec::new();
+        self.to_dot(&mut dot)?;
+        let cdot = CString::new(dot).map_err(|_| DotGraphError::InvalidGraph(self.id.clone()))?;
+        let cengine = CString::new(engine.name()).unwrap();
+        let cformat = CString::new(format.name()).unwrap();
+
+        let rendered = unsafe {
+            let graph = agmemread(cdot.as_ptr());
+            if graph.is_null() {
+                return Err(DotGraphError::InvalidGraph(self.id.clone()));
+            }
+
+            let gvc = gvContext();
+            let rendered = if gvLayout(gvc, graph, cengine.as_ptr()) != 0 {
+                Err(format!("{} layout failed", engine.name()))
+            } else {
+                let mut data = std::ptr::null_mut();
+                let mut length = 0;
+                let rendered = if gvRenderData(gvc, graph, cformat.as_ptr(), &mut data, &mut length)
+                    != 0
+                    || data.is_null()
+                {
+                    Err(format!("{} rendering failed", format.name()))
+                } else {
+                    Ok(std::slice::from_raw_parts(data as *const u8, length as usize).to_vec())
+                };
+
+                if !data.is_null() {
+                    gvFreeRenderData(data);
+                }
+                gvFreeLayout(gvc, graph);
+
+                rendered
+            };
+
+            agclose(graph);
+            gvFreeContext(gvc);
+
+            rendered
+        };
+
+        let rendered = rendered.map_err(|msg| DotGraphError::RenderFailed(self.id.clone(), msg))?;
+        writer.write_all(&rendered)?;
+
+        Ok(())
+    }
+}
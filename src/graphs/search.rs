@@ -0,0 +1,190 @@
+use crate::{graphs::graph::Graph, node::NodeId};
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::OnceLock;
+
+use rayon::prelude::*;
+
+/// Score bonus for a match right after the previous one
+const CONSECUTIVE_BONUS: i64 = 5;
+/// Score bonus for a match at the start of a word, e.g. after `/` or `_`
+const BOUNDARY_BONUS: i64 = 8;
+
+#[derive(Debug, Clone)]
+/// A `TextIndex` holds the tokens of node ids and attribute values of a graph,
+/// to answer repeated text searches without scanning every node.
+pub struct TextIndex<'a> {
+    /// Lowercase alphanumeric tokens to the ids of nodes containing them
+    tokens: BTreeMap<String, HashSet<&'a NodeId>>,
+}
+
+impl<'a> TextIndex<'a> {
+    /// Search for nodes containing every token of `query`, ignoring case,
+    /// where the last token may be a prefix to support search-as-you-type.
+    ///
+    /// # Returns
+    ///
+    /// Ids of matching nodes, empty if `query` has no tokens.
+    pub fn query(&self, query: &str) -> HashSet<&'a NodeId> {
+        let query = tokenize(query);
+        let Some((last, tokens)) = query.split_last() else {
+            return HashSet::new();
+        };
+
+        let mut matches: Option<HashSet<&NodeId>> = None;
+        for token in tokens {
+            let ids = self.tokens.get(token).cloned().unwrap_or_default();
+            matches = Some(match matches {
+                Some(matches) => &matches & &ids,
+                None => ids,
+            });
+        }
+
+        let prefixed: HashSet<&NodeId> = self
+            .tokens
+            .range(last.clone()..)
+            .take_while(|(token, _)| token.starts_with(last.as_str()))
+            .flat_map(|(_, ids)| ids.iter().copied())
+            .collect();
+
+        match matches {
+            Some(matches) => &matches & &prefixed,
+            None => prefixed,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+/// A `PrefixIndex` lazily sorts the node ids of a graph to look them up by prefix,
+/// to be invalidated whenever nodes are added, removed, or renamed.
+///
+/// The index does not follow clones of the graph, as a clone is usually about to be modified.
+pub(crate) struct PrefixIndex {
+    sorted: OnceLock<Vec<NodeId>>,
+}
+
+impl PrefixIndex {
+    pub(crate) fn invalidate(&mut self) {
+        self.sorted.take();
+    }
+}
+
+impl Clone for PrefixIndex {
+    fn clone(&self) -> PrefixIndex {
+        PrefixIndex::default()
+    }
+}
+
+/// Split `text` into lowercase alphanumeric tokens
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+impl Graph {
+    /// Retrieve the ids of all nodes starting with `prefix`, in sorted order,
+    /// e.g. every node under `layer1/` of hierarchically named nodes.
+    pub fn nodes_with_prefix(&self, prefix: &str) -> Vec<&NodeId> {
+        let sorted = self.prefixes.sorted.get_or_init(|| {
+            let mut sorted: Vec<NodeId> =
+                self.nodes.par_iter().map(|node| node.id.clone()).collect();
+            sorted.par_sort_unstable();
+            sorted
+        });
+
+        let start = sorted.partition_point(|id| id.as_str() < prefix);
+        sorted[start..].iter().take_while(|id| id.starts_with(prefix)).collect()
+    }
+
+    /// Build a `TextIndex` over the ids and attribute values of all nodes,
+    /// which stays valid as long as the graph is borrowed.
+    pub fn build_text_index(&self) -> TextIndex<'_> {
+        let mut tokens: BTreeMap<String, HashSet<&NodeId>> = BTreeMap::new();
+        for node in &self.nodes {
+            let texts = std::iter::once(&node.id).chain(node.attrs.iter().map(|attr| &attr.value));
+            for token in texts.flat_map(|text| tokenize(text)) {
+                tokens.entry(token).or_default().insert(&node.id);
+            }
+        }
+
+        TextIndex { tokens }
+    }
+
+    /// Search for nodes whose id or label contains the characters of `query` in order,
+    /// ignoring case, e.g. `l2cv` matches `layer2/conv`.
+    ///
+    /// Matches are ranked higher for consecutive characters and characters starting words,
+    /// and lower for gaps between them and for longer candidates.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Characters to look for
+    /// * `limit` - Maximum number of matches to return
+    ///
+    /// # Returns
+    ///
+    /// Ids of at most `limit` matching nodes, the best match first.
+    pub fn search_fuzzy(&self, query: &str, limit: usize) -> Vec<&NodeId> {
+        let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches: Vec<(&NodeId, i64)> = self
+            .nodes
+            .par_iter()
+            .filter_map(|node| {
+                let label = node.attrs.get("label").map(|attr| attr.value.as_str());
+                let score = [Some(node.id.as_str()), label]
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|candidate| fuzzy_score(&query, candidate))
+                    .max()?;
+
+                Some((&node.id, score))
+            })
+            .collect();
+        matches.sort_unstable_by(|(a, x), (b, y)| y.cmp(x).then_with(|| a.cmp(b)));
+
+        matches.into_iter().take(limit).map(|(id, _)| id).collect()
+    }
+}
+
+/// Score of `candidate` containing the lowercase characters of `query` in order,
+/// `None` if it does not
+fn fuzzy_score(query: &[char], candidate: &str) -> Option<i64> {
+    let chars: Vec<char> = candidate.chars().collect();
+
+    let mut score = 0;
+    let mut matched = 0;
+    let mut last: Option<usize> = None;
+    for (i, &c) in chars.iter().enumerate() {
+        if matched == query.len() {
+            break;
+        }
+        if !c.to_lowercase().eq(std::iter::once(query[matched])) {
+            continue;
+        }
+
+        score += 1;
+        match last {
+            Some(last) if last + 1 == i => score += CONSECUTIVE_BONUS,
+            Some(last) => score -= (i - last - 1) as i64,
+            None => score -= i as i64,
+        }
+        let boundary = match i.checked_sub(1).map(|i| chars[i]) {
+            None => true,
+            Some(prev) => !prev.is_alphanumeric() || (prev.is_lowercase() && c.is_uppercase()),
+        };
+        if boundary {
+            score += BOUNDARY_BONUS;
+        }
+
+        matched += 1;
+        last = Some(i);
+    }
+
+    (matched == query.len()).then(|| score - chars.len() as i64 / 4)
+}
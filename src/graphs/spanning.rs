@@ -0,0 +1,56 @@
+use crate::{
+    edge::{Edge, EdgeId},
+    graphs::graph::Graph,
+    node::NodeId,
+};
+
+use std::collections::{HashMap, HashSet};
+
+impl Graph {
+    /// Constructs a new `Graph`, containing all nodes but only the edges of a minimum spanning
+    /// forest, where edges connect their ends regardless of direction and each weighs as much
+    /// as `weight` returns for it.
+    ///
+    /// Edges are chosen by Kruskal's algorithm, lighter edges first and ties broken by their
+    /// ends, so that every connected component is spanned by a tree. Self-loops are never chosen.
+    pub fn minimum_spanning_tree<F>(&self, weight: F) -> Graph
+    where
+        F: Fn(&Edge) -> f64,
+    {
+        let mut edges: Vec<(f64, &EdgeId)> = self
+            .edges
+            .iter()
+            .filter(|edge| edge.id.from != edge.id.to)
+            .map(|edge| (weight(edge), &edge.id))
+            .collect();
+        edges.sort_unstable_by(|(a, a_id), (b, b_id)| {
+            a.total_cmp(b).then_with(|| a_id.sort_key().cmp(&b_id.sort_key()))
+        });
+
+        // union-find over nodes, where roots have no parents
+        let mut parents: HashMap<&NodeId, &NodeId> = HashMap::new();
+        let mut chosen: HashSet<&EdgeId> = HashSet::new();
+        for (_, id) in edges {
+            let from = find(&mut parents, &id.from);
+            let to = find(&mut parents, &id.to);
+            if from != to {
+                parents.insert(from, to);
+                chosen.insert(id);
+            }
+        }
+
+        self.filter_edges(|edge| chosen.contains(&edge.id))
+    }
+}
+
+/// Root of the set containing `id`, halving the path to it along the way
+fn find<'a>(parents: &mut HashMap<&'a NodeId, &'a NodeId>, mut id: &'a NodeId) -> &'a NodeId {
+    while let Some(&parent) = parents.get(id) {
+        if let Some(&grandparent) = parents.get(parent) {
+            parents.insert(id, grandparent);
+        }
+        id = parent;
+    }
+
+    id
+}
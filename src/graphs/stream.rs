@@ -0,0 +1,270 @@
+use crate::{
+    attr::Attr,
+    edge::{Edge, EdgeId},
+    error::DotGraphError,
+    graphs::{
+        graph::{Graph, GraphId},
+        subgraph::SubGraph,
+        write::DotWriteOptions,
+    },
+    node::{Node, NodeId},
+    utils,
+};
+
+use std::collections::HashSet;
+use std::io::{Error, ErrorKind, Result, Write};
+
+use rayon::prelude::*;
+
+#[derive(Debug)]
+/// A `DotWriter` writes dot statement by statement, as nodes and edges are visited,
+/// without holding the graph they belong to.
+///
+/// Graphs are opened with `begin_graph`, where the first one is the root `digraph` and the
+/// others are subgraphs nested in the innermost open one, and closed with `end_graph`.
+/// Statements are laid out by the `DotWriteOptions` it is given, except that attributes are
+/// never factored into default statements, as that needs all nodes or edges of a subgraph.
+///
+/// ```ignore
+/// let mut writer = DotWriter::new(file, DotWriteOptions::compact()).parallel(4096);
+/// writer.begin_graph("G", &HashSet::new())?;
+/// writer.write_nodes(nodes)?;
+/// writer.write_edges(edges)?;
+/// writer.finish()?;
+/// ```
+pub struct DotWriter<W: Write> {
+    /// Where the dot is written
+    writer: W,
+    /// How statements are laid out
+    options: DotWriteOptions,
+    /// Number of graphs opened but not closed yet
+    depth: usize,
+    /// Number of statements prepared in parallel at once, if any
+    chunk: Option<usize>,
+}
+
+/// A step of writing a subgraph through `DotWriter::write_graph`
+enum Step<'a> {
+    /// Open the subgraph and schedule its children
+    Open(&'a SubGraph),
+    /// Write the nodes and edges of the subgraph and close it
+    Close(&'a SubGraph),
+}
+
+impl<W: Write> DotWriter<W> {
+    /// A `DotWriter` writing to `writer`, laid out by `options`
+    pub fn new(writer: W, options: DotWriteOptions) -> DotWriter<W> {
+        DotWriter { writer, options, depth: 0, chunk: None }
+    }
+
+    /// Prepare up to `chunk` nodes or edges in parallel before writing them in order,
+    /// so that at most `chunk` statements are buffered at once
+    pub fn parallel(mut self, chunk: usize) -> DotWriter<W> {
+        self.chunk = Some(chunk.max(1));
+        self
+    }
+
+    /// Number of graphs opened but not closed yet
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Open a graph of `id` with `attrs`, the root `digraph` if no graph is open,
+    /// otherwise a subgraph of the innermost open one.
+    pub fn begin_graph(&mut self, id: &str, attrs: &HashSet<Attr>) -> Result<()> {
+        let keyword = if self.depth == 0 { "digraph" } else { "subgraph" };
+        self.options.write_indent(self.depth, &mut self.writer)?;
+        write!(self.writer, "{keyword} {}", utils::pretty_id(id))?;
+        self.options.write_open(self.depth, &mut self.writer)?;
+
+        self.depth += 1;
+
+        if !attrs.is_empty() {
+            self.options.write_indent(self.depth, &mut self.writer)?;
+            write!(self.writer, "graph ")?;
+            self.options.write_attrs(attrs, self.depth, &mut self.writer)?;
+            self.options.write_end(false, &mut self.writer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Close the innermost open graph.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if no graph is open, `Ok` otherwise
+    pub fn end_graph(&mut self) -> Result<()> {
+        self.open()?;
+
+        self.depth -= 1;
+        self.options.write_indent(self.depth, &mut self.writer)?;
+        writeln!(self.writer, "}}")
+    }
+
+    /// Write `node` into the innermost open graph.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if no graph is open, `Ok` otherwise
+    pub fn write_node(&mut self, node: &Node) -> Result<()> {
+        self.open()?;
+
+        node.write_dot(self.depth, &self.options, &HashSet::new(), &mut self.writer)
+    }
+
+    /// Write `edge` into the innermost open graph.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if no graph is open, `Ok` otherwise
+    pub fn write_edge(&mut self, edge: &Edge) -> Result<()> {
+        self.open()?;
+
+        edge.write_dot(self.depth, &self.options, &HashSet::new(), &mut self.writer)
+    }
+
+    /// Write `nodes` into the innermost open graph, in parallel-prepared chunks if `parallel`
+    ///
+    /// # Returns
+    ///
+    /// `Err` if no graph is open, `Ok` otherwise
+    pub fn write_nodes<'a>(&mut self, nodes: impl IntoIterator<Item = &'a Node>) -> Result<()> {
+        self.write_chunked(nodes, |node, depth, options, writer| {
+            node.write_dot(depth, options, &HashSet::new(), writer)
+        })
+    }
+
+    /// Write `edges` into the innermost open graph, in parallel-prepared chunks if `parallel`
+    ///
+    /// # Returns
+    ///
+    /// `Err` if no graph is open, `Ok` otherwise
+    pub fn write_edges<'a>(&mut self, edges: impl IntoIterator<Item = &'a Edge>) -> Result<()> {
+        self.write_chunked(edges, |edge, depth, options, writer| {
+            edge.write_dot(depth, options, &HashSet::new(), writer)
+        })
+    }
+
+    /// Write `graph` as a whole into the innermost open graph, or as the root if none is open.
+    ///
+    /// Subgraphs are descended through an explicit stack rather than recursively,
+    /// so that deeply nested graphs are written as well.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if writing fails, or with `ErrorKind::InvalidData` if `graph` refers to a subgraph,
+    /// node, or edge it does not hold, `Ok` otherwise
+    pub fn write_graph(&mut self, graph: &Graph) -> Result<()> {
+        // elements the graph refers to but does not hold fail the write rather than panic
+        let invalid = |error: DotGraphError| Error::new(ErrorKind::InvalidData, error);
+        let find_subgraph = |id: &GraphId| {
+            let error = || DotGraphError::NoSuchSubGraph(id.clone(), graph.id().clone());
+            graph.search_subgraph(id).ok_or_else(|| invalid(error()))
+        };
+        let find_node = |id: &NodeId| {
+            let error = || DotGraphError::NoSuchNode(id.clone(), graph.id().clone());
+            graph.search_node(id).ok_or_else(|| invalid(error()))
+        };
+        let find_edge = |id: &EdgeId| {
+            let error = || DotGraphError::NoSuchEdge(id.to_string(), graph.id().clone());
+            graph.search_edge(id).ok_or_else(|| invalid(error()))
+        };
+
+        let mut steps = vec![Step::Open(find_subgraph(graph.id())?)];
+        while let Some(step) = steps.pop() {
+            match step {
+                Step::Open(subgraph) => {
+                    self.begin_graph(subgraph.id(), subgraph.attrs())?;
+                    steps.push(Step::Close(subgraph));
+
+                    let mut ids: Vec<&GraphId> = subgraph.subgraph_ids.iter().collect();
+                    if self.options.is_sorted() {
+                        ids.sort_unstable();
+                    }
+                    let children: Vec<&SubGraph> =
+                        ids.into_iter().map(find_subgraph).collect::<Result<_>>()?;
+                    steps.extend(children.into_iter().rev().map(Step::Open));
+                }
+                Step::Close(subgraph) => {
+                    let mut node_ids: Vec<&NodeId> = subgraph.node_ids.iter().collect();
+                    let mut edge_ids: Vec<&EdgeId> = subgraph.edge_ids.iter().collect();
+                    if self.options.is_sorted() {
+                        node_ids.sort_unstable();
+                        edge_ids.sort_unstable_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+                    }
+
+                    let nodes: Vec<&Node> =
+                        node_ids.into_iter().map(find_node).collect::<Result<_>>()?;
+                    let edges: Vec<&Edge> =
+                        edge_ids.into_iter().map(find_edge).collect::<Result<_>>()?;
+                    self.write_nodes(nodes)?;
+                    self.write_edges(edges)?;
+                    self.end_graph()?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Close all open graphs and flush the underlying writer
+    ///
+    /// # Returns
+    ///
+    /// `Err` if writing fails, `Ok` with the underlying writer otherwise
+    pub fn finish(mut self) -> Result<W> {
+        while self.depth > 0 {
+            self.end_graph()?;
+        }
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+
+    /// `Err` if no graph is open, so that statements are not written outside of the root
+    fn open(&self) -> Result<()> {
+        if self.depth == 0 {
+            Err(Error::new(ErrorKind::InvalidInput, "no graph is open"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Write `items` with `write`, preparing up to `chunk` of them in parallel at once
+    fn write_chunked<'a, T, F>(
+        &mut self,
+        items: impl IntoIterator<Item = &'a T>,
+        write: F,
+    ) -> Result<()>
+    where
+        T: Sync + 'a,
+        F: Fn(&T, usize, &DotWriteOptions, &mut dyn Write) -> Result<()> + Sync,
+    {
+        self.open()?;
+
+        let Some(chunk) = self.chunk else {
+            for item in items {
+                write(item, self.depth, &self.options, &mut self.writer)?;
+            }
+            return Ok(());
+        };
+
+        let mut items = items.into_iter().peekable();
+        while items.peek().is_some() {
+            let batch: Vec<&T> = items.by_ref().take(chunk).collect();
+            let buffers: Vec<Vec<u8>> = batch
+                .par_iter()
+                .map(|item| {
+                    let mut buffer = Vec::new();
+                    write(item, self.depth, &self.options, &mut buffer).map(|_| buffer)
+                })
+                .collect::<Result<_>>()?;
+            for buffer in buffers {
+                self.writer.write_all(&buffer)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,13 +1,17 @@
 use crate::{
     attr::Attr,
-    edge::EdgeId,
-    graphs::graph::{Graph, GraphId},
-    node::NodeId,
+    edge::{Edge, EdgeId},
+    graphs::{
+        graph::{Graph, GraphId},
+        write::{self, AttrFilter, DotElement, DotWriteOptions},
+    },
+    node::{Node, NodeId},
     utils,
 };
 
-use std::borrow::Borrow;
+use std::borrow::{Borrow, Cow};
 use std::collections::HashSet;
+use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::io::Write;
 
@@ -113,55 +117,173 @@ impl SubGraph {
         }
     }
 
-    /// Write the graph to dot format.
-    pub(super) fn to_dot<W: ?Sized>(
-        &self,
-        graph: &Graph,
+    /// Whether the nodes this subgraph holds are first declared by its own node statements,
+    /// so that node defaults written before them apply, given the nodes `declared` so far.
+    /// That is, none of them is declared yet, e.g. by an edge written earlier elsewhere,
+    /// and the edges this subgraph holds declare no new nodes after the defaults.
+    fn declares_nodes(&self, declared: &HashSet<&NodeId>) -> bool {
+        let declares = |id: &NodeId| declared.contains(id) || self.node_ids.contains(id);
+
+        self.node_ids.iter().all(|id| !declared.contains(id))
+            && self.edge_ids.iter().all(|id| declares(&id.from) && declares(&id.to))
+    }
+
+    /// Write the graph to dot format, laid out by `options`,
+    /// with attributes kept or rewritten by `filter` if any,
+    /// adding the nodes its statements declare to `declared`
+    pub(super) fn to_dot<'a, W>(
+        &'a self,
+        graph: &'a Graph,
         indent: usize,
+        options: &DotWriteOptions,
+        filter: Option<&AttrFilter>,
+        declared: &mut HashSet<&'a NodeId>,
         writer: &mut W,
     ) -> std::io::Result<()>
     where
-        W: Write,
+        W: Write + ?Sized,
     {
         let id = utils::pretty_id(&self.id);
+        options.write_indent(indent, writer)?;
         if indent == 0 {
-            writeln!(writer, "digraph {id} {{")?;
+            write!(writer, "digraph {id}")?;
         } else {
-            (0..indent).try_for_each(|_| write!(writer, "\t"))?;
-            writeln!(writer, "subgraph {id} {{")?;
+            write!(writer, "subgraph {id}")?;
         }
+        options.write_open(indent, writer)?;
 
-        if !self.attrs.is_empty() {
-            (0..=indent).try_for_each(|_| write!(writer, "\t"))?;
-            writeln!(writer, "graph [")?;
-
-            for attr in &self.attrs {
-                attr.to_dot(indent + 1, writer)?;
+        let attrs = match filter {
+            Some(filter) => {
+                Cow::Owned(write::filter_attrs(DotElement::Graph(self), &self.attrs, filter))
             }
+            None => Cow::Borrowed(&self.attrs),
+        };
+        if !attrs.is_empty() {
+            options.write_indent(indent + 1, writer)?;
+            write!(writer, "graph ")?;
+            options.write_attrs(attrs.iter(), indent + 1, writer)?;
+            options.write_end(false, writer)?;
+        }
 
-            (0..=indent).try_for_each(|_| write!(writer, "\t"))?;
-            writeln!(writer, "]")?;
+        let mut subgraph_ids: Vec<&GraphId> = self.subgraph_ids.iter().collect();
+        let mut node_ids: Vec<&NodeId> = self.node_ids.iter().collect();
+        let mut edge_ids: Vec<&EdgeId> = self.edge_ids.iter().collect();
+        if options.is_sorted() {
+            subgraph_ids.sort_unstable();
+            node_ids.sort_unstable();
+            edge_ids.sort_unstable_by(|a, b| a.sort_key().cmp(&b.sort_key()));
         }
 
-        for id in &self.subgraph_ids {
+        for id in subgraph_ids {
             let subgraph = graph.search_subgraph(id).unwrap();
-            subgraph.to_dot(graph, indent + 1, writer)?;
+            subgraph.to_dot(graph, indent + 1, options, filter, declared, writer)?;
         }
 
-        for id in &self.node_ids {
-            let node = graph.search_node(id).unwrap();
-            node.to_dot(indent + 1, writer)?;
-        }
+        let nodes: Vec<Cow<Node>> = node_ids
+            .iter()
+            .map(|id| {
+                let node = graph.search_node(id).unwrap();
+                match filter {
+                    Some(filter) => {
+                        let attrs =
+                            write::filter_attrs(DotElement::Node(node), &node.attrs, filter);
+                        Cow::Owned(Node::new(node.id.clone(), attrs))
+                    }
+                    None => Cow::Borrowed(node),
+                }
+            })
+            .collect();
+        let edges: Vec<Cow<Edge>> = edge_ids
+            .iter()
+            .map(|id| {
+                let edge = graph.search_edge(id).unwrap();
+                match filter {
+                    Some(filter) => {
+                        let attrs =
+                            write::filter_attrs(DotElement::Edge(edge), &edge.attrs, filter);
+                        Cow::Owned(Edge::new(edge.id.clone(), attrs))
+                    }
+                    None => Cow::Borrowed(edge),
+                }
+            })
+            .collect();
 
-        for id in &self.edge_ids {
-            let edge = graph.search_edge(id).unwrap();
-            edge.to_dot(indent + 1, writer)?;
+        // nodes declared before the defaults, or by edges after them, would not take them
+        let node_defaults = if options.factors_defaults() && self.declares_nodes(declared) {
+            write::shared_attrs(nodes.iter().map(|node| &node.attrs))
+        } else {
+            HashSet::new()
+        };
+        let edge_defaults = if options.factors_defaults() {
+            write::shared_attrs(edges.iter().map(|edge| &edge.attrs))
+        } else {
+            HashSet::new()
+        };
+
+        if !node_defaults.is_empty() {
+            options.write_indent(indent + 1, writer)?;
+            write!(writer, "node ")?;
+            options.write_attrs(&node_defaults, indent + 1, writer)?;
+            options.write_end(true, writer)?;
+        }
+        for node in nodes {
+            node.write_dot(indent + 1, options, &node_defaults, writer)?;
         }
+        declared.extend(node_ids);
 
-        (0..indent).try_for_each(|_| write!(writer, "\t"))?;
+        if !edge_defaults.is_empty() {
+            options.write_indent(indent + 1, writer)?;
+            write!(writer, "edge ")?;
+            options.write_attrs(&edge_defaults, indent + 1, writer)?;
+            options.write_end(true, writer)?;
+        }
+        for edge in edges {
+            edge.write_dot(indent + 1, options, &edge_defaults, writer)?;
+        }
+        declared.extend(edge_ids.into_iter().flat_map(|id| [&id.from, &id.to]));
 
+        options.write_indent(indent, writer)?;
         writeln!(writer, "}}")?;
 
         Ok(())
     }
 }
+
+impl fmt::Display for SubGraph {
+    /// Writes the subgraph in dot format with its own attributes, nodes, and edges by their ids,
+    /// but not its children subgraphs, or a summary of it with `{:#}`,
+    /// e.g. `subgraph cluster_0 with 1 subgraph, 2 nodes, and 1 edge`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return write!(
+                f,
+                "subgraph {} with {}, {}, and {}",
+                self.id,
+                utils::count(self.subgraph_ids.len(), "subgraph"),
+                utils::count(self.node_ids.len(), "node"),
+                utils::count(self.edge_ids.len(), "edge"),
+            );
+        }
+
+        utils::fmt_with(f, |writer| {
+            let options = DotWriteOptions::default();
+
+            writeln!(writer, "subgraph {} {{", utils::pretty_id(&self.id))?;
+            if !self.attrs.is_empty() {
+                options.write_indent(1, writer)?;
+                write!(writer, "graph ")?;
+                options.write_attrs(&self.attrs, 1, writer)?;
+                writeln!(writer)?;
+            }
+            for id in &self.node_ids {
+                options.write_indent(1, writer)?;
+                writeln!(writer, "{};", utils::pretty_id(id))?;
+            }
+            for id in &self.edge_ids {
+                id.to_dot(1, writer)?;
+                writeln!(writer)?;
+            }
+            writeln!(writer, "}}")
+        })
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{edge::EdgeId, graphs::graph::Graph, node::NodeId};
+
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind, Result, Write};
+
+impl Graph {
+    /// Write the graph to Trivial Graph Format (TGF), as accepted by yEd.
+    ///
+    /// Nodes are numbered from one in the order of their ids, numeric ids first and in numeric
+    /// order, and labeled by their `label` attributes, or by their ids if those differ from
+    /// their numbers. Edges are written in the order of their ends, labeled by their `label`
+    /// attributes if any. Attributes other than labels, subgraphs, and ports are not kept.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if writing fails or if a label holds a line break, which TGF cannot hold,
+    /// `Ok` otherwise.
+    pub fn to_tgf<W>(&self, writer: &mut W) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        let mut nodes: Vec<&NodeId> = self.nodes.iter().map(|node| &node.id).collect();
+        nodes.sort_by_cached_key(|&id| (id.parse::<usize>().unwrap_or(usize::MAX), id));
+        let mut edges: Vec<&EdgeId> = self.edges.iter().map(|edge| &edge.id).collect();
+        edges.sort_unstable_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+
+        let numbers: HashMap<&NodeId, usize> =
+            nodes.iter().enumerate().map(|(i, &id)| (id, i + 1)).collect();
+        let label = |label: &str| {
+            if label.contains(['\n', '\r']) {
+                let message = format!("TGF label {label:?} holds a line break");
+                return Err(Error::new(ErrorKind::InvalidData, message));
+            }
+            Ok(format!(" {label}"))
+        };
+
+        for id in nodes {
+            let node = self.search_node(id).unwrap();
+            let number = numbers[id];
+
+            write!(writer, "{number}")?;
+            if let Some(attr) = node.attrs.get("label") {
+                write!(writer, "{}", label(&attr.value)?)?;
+            } else if *id != number.to_string() {
+                write!(writer, "{}", label(id)?)?;
+            }
+            writeln!(writer)?;
+        }
+        writeln!(writer, "#")?;
+        for id in edges {
+            let edge = self.search_edge(id).unwrap();
+
+            write!(writer, "{} {}", numbers[&id.from], numbers[&id.to])?;
+            if let Some(attr) = edge.attrs.get("label") {
+                write!(writer, "{}", label(&attr.value)?)?;
+            }
+            writeln!(writer)?;
+        }
+
+        Ok(())
+    }
+}
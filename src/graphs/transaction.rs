@@ -0,0 +1,196 @@
+use crate::{
+    edge::{Edge, EdgeId},
+    error::DotGraphError,
+    graphs::{
+        graph::{Graph, GraphId},
+        payload::Payload,
+    },
+    node::{Node, NodeId},
+};
+
+use std::ops::Deref;
+
+#[derive(Debug)]
+/// An `Undo` reverts one mutation applied through a `Transaction`.
+enum Undo {
+    /// Remove a subgraph added empty
+    RemoveSubGraph(GraphId),
+    /// Move a node back to the subgraph it was held by
+    MoveNode(NodeId, Option<GraphId>),
+    /// Remove an added node
+    RemoveNode(NodeId),
+    /// Put a removed node back, with the subgraph it was held by and its payload
+    RestoreNode(Node, Option<GraphId>, Option<Payload>),
+    /// Remove an added edge
+    RemoveEdge(EdgeId),
+    /// Put a removed edge back, with the subgraph it was held by and its payload
+    RestoreEdge(Edge, Option<GraphId>, Option<Payload>),
+}
+
+#[derive(Debug)]
+/// A `Transaction` applies a batch of mutations to a `Graph` in place,
+/// recording how to undo each of them.
+/// `Graph::transaction` rolls all of them back unless every one succeeds.
+pub struct Transaction<'a> {
+    graph: &'a mut Graph,
+    undos: Vec<Undo>,
+}
+
+impl<'a> Transaction<'a> {
+    pub(super) fn new(graph: &'a mut Graph) -> Transaction<'a> {
+        Transaction { graph, undos: Vec::new() }
+    }
+
+    /// Add a new, empty subgraph `id` as a child of the subgraph `parent`,
+    /// as `Graph::add_subgraph` does.
+    pub fn add_subgraph(&mut self, parent: &GraphId, id: GraphId) -> Result<(), DotGraphError> {
+        self.graph.add_subgraph(parent, id.clone())?;
+        self.undos.push(Undo::RemoveSubGraph(id));
+
+        Ok(())
+    }
+
+    /// Move the node with `id` to `target`, as `Graph::move_node` does.
+    pub fn move_node(&mut self, id: &NodeId, target: &GraphId) -> Result<(), DotGraphError> {
+        let owner = self.graph.parent_subgraph(id).cloned();
+        self.graph.move_node(id, target)?;
+        self.undos.push(Undo::MoveNode(id.clone(), owner));
+
+        Ok(())
+    }
+
+    /// Add `node` to the subgraph `parent`, as `Graph::add_node` does.
+    pub fn add_node(&mut self, node: Node, parent: &GraphId) -> Result<(), DotGraphError> {
+        let id = node.id.clone();
+        self.graph.add_node(node, parent)?;
+        self.undos.push(Undo::RemoveNode(id));
+
+        Ok(())
+    }
+
+    /// Remove the node with `id` along with all edges from or to it,
+    /// as `Graph::remove_node` does.
+    pub fn remove_node(&mut self, id: &NodeId) -> Result<Node, DotGraphError> {
+        let touching: Vec<EdgeId> = self
+            .graph
+            .edges
+            .iter()
+            .filter_map(|edge| (edge.id.from == *id || edge.id.to == *id).then_some(&edge.id))
+            .cloned()
+            .collect();
+        let owner = self.graph.parent_subgraph(id).cloned();
+        let payload = self.graph.payloads.node(id);
+
+        // edges go before the node, and come back after it
+        for edge_id in &touching {
+            self.remove_edge(edge_id)?;
+        }
+        let node = self.graph.remove_node(id)?;
+        self.undos.push(Undo::RestoreNode(node.clone(), owner, payload));
+
+        Ok(node)
+    }
+
+    /// Add `edge` to the subgraph `parent`, as `Graph::add_edge` does.
+    pub fn add_edge(&mut self, edge: Edge, parent: &GraphId) -> Result<(), DotGraphError> {
+        let id = edge.id.clone();
+        self.graph.add_edge(edge, parent)?;
+        self.undos.push(Undo::RemoveEdge(id));
+
+        Ok(())
+    }
+
+    /// Remove the edge with `id`, as `Graph::remove_edge` does.
+    pub fn remove_edge(&mut self, id: &EdgeId) -> Result<Edge, DotGraphError> {
+        let owner = self.graph.edge_owner(id).cloned();
+        let payload = self.graph.payloads.edge(id);
+
+        let edge = self.graph.remove_edge(id)?;
+        self.undos.push(Undo::RestoreEdge(edge.clone(), owner, payload));
+
+        Ok(edge)
+    }
+
+    /// Undo every mutation applied so far, latest first
+    pub(super) fn rollback(self) {
+        let graph = self.graph;
+        for undo in self.undos.into_iter().rev() {
+            match undo {
+                Undo::RemoveSubGraph(id) => graph.remove_empty_subgraph(&id),
+                Undo::MoveNode(id, Some(owner)) => graph.move_node(&id, &owner).unwrap(),
+                Undo::MoveNode(id, None) => {
+                    if let Some(owner) = graph.parent_subgraph(&id).cloned() {
+                        graph.update_subgraph(&owner, |subgraph| {
+                            subgraph.node_ids.remove(&id);
+                        });
+                    }
+                    graph.owners.remove_node(&id);
+                }
+                Undo::RemoveNode(id) => {
+                    graph.remove_node(&id).unwrap();
+                }
+                Undo::RestoreNode(node, owner, payload) => graph.restore_node(node, owner, payload),
+                Undo::RemoveEdge(id) => {
+                    graph.remove_edge(&id).unwrap();
+                }
+                Undo::RestoreEdge(edge, owner, payload) => graph.restore_edge(edge, owner, payload),
+            }
+        }
+    }
+}
+
+impl Deref for Transaction<'_> {
+    type Target = Graph;
+
+    fn deref(&self) -> &Graph {
+        self.graph
+    }
+}
+
+impl Graph {
+    /// Remove the subgraph with `id`, which must hold nothing
+    fn remove_empty_subgraph(&mut self, id: &GraphId) {
+        if let Some(parent) = self.subgraph_parent(id).cloned() {
+            self.update_subgraph(&parent, |subgraph| {
+                subgraph.subgraph_ids.remove(id);
+            });
+            self.subtree.get_mut(&parent).unwrap().remove(id);
+        }
+        self.subgraphs.remove(id);
+        self.subtree.remove(id);
+        self.owners.remove_subgraph(id);
+    }
+
+    /// Put back `node` removed from `owner`, without validating or notifying
+    fn restore_node(&mut self, node: Node, owner: Option<GraphId>, payload: Option<Payload>) {
+        if let Some(owner) = owner {
+            self.update_subgraph(&owner, |subgraph| {
+                subgraph.node_ids.insert(node.id.clone());
+            });
+            self.owners.insert_node(&node.id, &owner);
+        }
+        self.fwdmap.entry(node.id.clone()).or_default();
+        self.bwdmap.entry(node.id.clone()).or_default();
+        if let Some(payload) = payload {
+            self.payloads.insert_node(node.id.clone(), payload);
+        }
+        self.nodes.insert(node);
+        self.prefixes.invalidate();
+    }
+
+    /// Put back `edge` removed from `owner`, without validating or notifying
+    fn restore_edge(&mut self, edge: Edge, owner: Option<GraphId>, payload: Option<Payload>) {
+        if let Some(owner) = owner {
+            self.update_subgraph(&owner, |subgraph| {
+                subgraph.edge_ids.insert(edge.id.clone());
+            });
+            self.owners.insert_edge(&edge.id, &owner);
+        }
+        self.fwdmap.get_mut(&edge.id.from).unwrap().insert(edge.id.to.clone());
+        self.bwdmap.get_mut(&edge.id.to).unwrap().insert(edge.id.from.clone());
+        if let Some(payload) = payload {
+            self.payloads.insert_edge(edge.id.clone(), payload);
+        }
+        self.edges.insert(edge);
+    }
+}
@@ -0,0 +1,305 @@
+use crate::{error::DotGraphError, graphs::graph::Graph, node::NodeId};
+
+use std::collections::{HashSet, VecDeque};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Which edges a traversal follows from a node.
+pub enum Direction {
+    /// Follow edges to successors
+    Forward,
+    /// Follow edges to predecessors
+    Backward,
+    /// Follow edges in both directions
+    Both,
+}
+
+/// A lazy breadth-first traversal over node ids, created by `Graph::bfs`.
+pub struct Bfs<'a> {
+    graph: &'a Graph,
+    direction: Direction,
+    visited: HashSet<&'a NodeId>,
+    frontier: VecDeque<&'a NodeId>,
+}
+
+impl<'a> Iterator for Bfs<'a> {
+    type Item = &'a NodeId;
+
+    fn next(&mut self) -> Option<&'a NodeId> {
+        let id = self.frontier.pop_front()?;
+
+        let mut nexts: Vec<&NodeId> = self.graph.adjacent(id, self.direction).collect();
+        nexts.sort_unstable();
+        for next in nexts {
+            if self.visited.insert(next) {
+                self.frontier.push_back(next);
+            }
+        }
+
+        Some(id)
+    }
+}
+
+/// A lazy depth-first traversal over node ids in preorder, created by `Graph::dfs`.
+pub struct Dfs<'a> {
+    graph: &'a Graph,
+    direction: Direction,
+    visited: HashSet<&'a NodeId>,
+    stack: Vec<&'a NodeId>,
+}
+
+impl<'a> Iterator for Dfs<'a> {
+    type Item = &'a NodeId;
+
+    fn next(&mut self) -> Option<&'a NodeId> {
+        let id = loop {
+            let id = self.stack.pop()?;
+            if self.visited.insert(id) {
+                break id;
+            }
+        };
+
+        let mut nexts: Vec<&NodeId> = self
+            .graph
+            .adjacent(id, self.direction)
+            .filter(|next| !self.visited.contains(*next))
+            .collect();
+        nexts.sort_unstable_by(|a, b| b.cmp(a));
+        self.stack.extend(nexts);
+
+        Some(id)
+    }
+}
+
+impl Graph {
+    /// Traverse the graph breadth-first from `start` in `direction`,
+    /// visiting neighbors of a node in the order of their ids.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node with `start`,
+    /// `Ok` with a lazy iterator over node ids in visit order otherwise.
+    pub fn bfs(&self, start: &NodeId, direction: Direction) -> Result<Bfs<'_>, DotGraphError> {
+        let node = self
+            .nodes
+            .get(start)
+            .ok_or_else(|| DotGraphError::NoSuchNode(start.clone(), self.id.clone()))?;
+
+        let visited = HashSet::from([&node.id]);
+        let frontier = VecDeque::from([&node.id]);
+
+        Ok(Bfs { graph: self, direction, visited, frontier })
+    }
+
+    /// Traverse the graph depth-first from `start` in `direction`,
+    /// visiting neighbors of a node in the order of their ids.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node with `start`,
+    /// `Ok` with a lazy iterator over node ids in visit order otherwise.
+    pub fn dfs(&self, start: &NodeId, direction: Direction) -> Result<Dfs<'_>, DotGraphError> {
+        let node = self
+            .nodes
+            .get(start)
+            .ok_or_else(|| DotGraphError::NoSuchNode(start.clone(), self.id.clone()))?;
+
+        let visited = HashSet::new();
+        let stack = vec![&node.id];
+
+        Ok(Dfs { graph: self, direction, visited, stack })
+    }
+
+    /// Constructs a new `Graph`, given a center node, depth limit, and the direction to explore.
+    ///
+    /// # Arguments
+    ///
+    /// * `center` - Id of the center node
+    /// * `depth` - Depth limit of the desired neighborhood
+    /// * `direction` - Whether to explore successors, predecessors, or both
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node named `center`,
+    /// `Ok` with neighbors `Graph` otherwise.
+    pub fn neighbors_directed(
+        &self,
+        center: &NodeId,
+        depth: usize,
+        direction: Direction,
+    ) -> Result<Graph, DotGraphError> {
+        if !self.nodes.contains(center) {
+            return Err(DotGraphError::NoSuchNode(center.clone(), self.id.clone()));
+        }
+
+        let visited: Vec<&NodeId> =
+            self.reach(&[center], direction, Some(depth)).into_iter().collect();
+
+        Ok(self.filter(&visited))
+    }
+
+    /// Retrieve the ids of the nodes within `depth` hops of `center`, in either direction,
+    /// without extracting a new `Graph`.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node named `center`,
+    /// `Ok` with a set of ids of neighbors, including `center`, otherwise.
+    pub fn neighbor_ids(
+        &self,
+        center: &NodeId,
+        depth: usize,
+    ) -> Result<HashSet<&NodeId>, DotGraphError> {
+        if !self.nodes.contains(center) {
+            return Err(DotGraphError::NoSuchNode(center.clone(), self.id.clone()));
+        }
+
+        Ok(self.reach(&[center], Direction::Both, Some(depth)))
+    }
+
+    /// Constructs a new `Graph`, given several center nodes and a depth limit,
+    /// containing the union of their neighborhoods.
+    ///
+    /// # Arguments
+    ///
+    /// * `centers` - Ids of the center nodes
+    /// * `depth` - Depth limit of the desired neighborhood
+    ///
+    /// # Returns
+    ///
+    /// `Err` if any of `centers` is not a node,
+    /// `Ok` with neighbors `Graph` otherwise.
+    pub fn neighbors_of(&self, centers: &[&NodeId], depth: usize) -> Result<Graph, DotGraphError> {
+        if let Some(center) = centers.iter().find(|center| !self.nodes.contains(**center)) {
+            return Err(DotGraphError::NoSuchNode(center.to_string(), self.id.clone()));
+        }
+
+        let visited: Vec<&NodeId> =
+            self.reach(centers, Direction::Both, Some(depth)).into_iter().collect();
+
+        Ok(self.filter(&visited))
+    }
+
+    /// Constructs a new `Graph`, containing only the nodes reachable from any of `roots`,
+    /// including the roots themselves.
+    pub fn reachable_from(&self, roots: &[&NodeId]) -> Graph {
+        let reached: Vec<&NodeId> =
+            self.reach(roots, Direction::Forward, None).into_iter().collect();
+
+        self.filter(&reached)
+    }
+
+    /// Constructs a new `Graph`, containing only the node with `id` and the nodes downstream of
+    /// it, within `depth` hops if given, i.e. the nodes it affects.
+    ///
+    /// The `Graph` is empty if there is no node with `id`.
+    pub fn forward_slice(&self, id: &NodeId, depth: Option<usize>) -> Graph {
+        let sliced: Vec<&NodeId> =
+            self.reach(&[id], Direction::Forward, depth).into_iter().collect();
+
+        self.filter(&sliced)
+    }
+
+    /// Constructs a new `Graph`, containing only the node with `id` and the nodes upstream of
+    /// it, within `depth` hops if given, i.e. the nodes it depends on.
+    ///
+    /// The `Graph` is empty if there is no node with `id`.
+    pub fn backward_slice(&self, id: &NodeId, depth: Option<usize>) -> Graph {
+        let sliced: Vec<&NodeId> =
+            self.reach(&[id], Direction::Backward, depth).into_iter().collect();
+
+        self.filter(&sliced)
+    }
+
+    /// Constructs a new `Graph`, containing only the nodes lying on any path from the node
+    /// `src` to the node `dst`, both included.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if either node does not exist,
+    /// `Ok` with the `Graph` between them otherwise, which is empty if `dst` is not reachable
+    /// from `src`.
+    pub fn between(&self, src: &NodeId, dst: &NodeId) -> Result<Graph, DotGraphError> {
+        let src = self.node_id(src)?;
+        let dst = self.node_id(dst)?;
+
+        let downstream = self.reach(&[src], Direction::Forward, None);
+        let upstream = self.reach(&[dst], Direction::Backward, None);
+        let between: Vec<&NodeId> = downstream.intersection(&upstream).copied().collect();
+
+        Ok(self.filter(&between))
+    }
+
+    /// Retrieve all nodes from which the node with `id` is reachable.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node with `id`,
+    /// `Ok` with a set of ids of transitive predecessors, which includes `id` only if it lies on
+    /// a cycle.
+    pub fn ancestors(&self, id: &NodeId) -> Result<HashSet<&NodeId>, DotGraphError> {
+        let froms: Vec<&NodeId> = self.froms(id)?.into_iter().collect();
+
+        Ok(self.reach(&froms, Direction::Backward, None))
+    }
+
+    /// Retrieve all nodes reachable from the node with `id`.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node with `id`,
+    /// `Ok` with a set of ids of transitive successors, which includes `id` only if it lies on
+    /// a cycle.
+    pub fn descendants(&self, id: &NodeId) -> Result<HashSet<&NodeId>, DotGraphError> {
+        let tos: Vec<&NodeId> = self.tos(id)?.into_iter().collect();
+
+        Ok(self.reach(&tos, Direction::Forward, None))
+    }
+
+    /// Ids of the nodes adjacent to the node with `id` in `direction`
+    pub(crate) fn adjacent<'a>(
+        &'a self,
+        id: &NodeId,
+        direction: Direction,
+    ) -> impl Iterator<Item = &'a NodeId> {
+        let tos =
+            matches!(direction, Direction::Forward | Direction::Both).then(|| &self.fwdmap[id]);
+        let froms =
+            matches!(direction, Direction::Backward | Direction::Both).then(|| &self.bwdmap[id]);
+
+        tos.into_iter().flatten().chain(froms.into_iter().flatten())
+    }
+
+    /// Breadth-first search from `starts` in `direction`, up to `depth` hops away if given.
+    ///
+    /// Returns the ids of visited nodes, including the starts, ignoring ids that are not nodes.
+    pub(crate) fn reach(
+        &self,
+        starts: &[&NodeId],
+        direction: Direction,
+        depth: Option<usize>,
+    ) -> HashSet<&NodeId> {
+        let mut visited = HashSet::new();
+        let mut frontier = VecDeque::new();
+        for start in starts {
+            if let Some(node) = self.nodes.get(*start) {
+                if visited.insert(&node.id) {
+                    frontier.push_back((&node.id, 0));
+                }
+            }
+        }
+
+        while let Some((id, hops)) = frontier.pop_front() {
+            if depth.is_some_and(|depth| hops >= depth) {
+                continue;
+            }
+
+            for next in self.adjacent(id, direction) {
+                if visited.insert(next) {
+                    frontier.push_back((next, hops + 1));
+                }
+            }
+        }
+
+        visited
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{error::DotGraphError, graphs::graph::Graph, node::NodeId};
+
+use rayon::prelude::*;
+
+impl Graph {
+    /// Whether this `Graph` is a forest, i.e., acyclic with at most one edge to every node,
+    /// where each node without an edge to it is the root of a tree.
+    pub fn is_forest(&self) -> bool {
+        let single_parents = self.bwdmap.par_iter().all(|(_, froms)| froms.len() <= 1);
+
+        // parallel edges to the same node are merged in `bwdmap`, so count edges as well
+        let parents: usize = self.bwdmap.par_iter().map(|(_, froms)| froms.len()).sum();
+
+        single_parents && parents == self.edges.len() && self.is_acyclic()
+    }
+
+    /// Whether this `Graph` is a tree, i.e., a forest with exactly one root.
+    pub fn is_tree(&self) -> bool {
+        self.nodes.len() == self.edges.len() + 1 && self.is_forest()
+    }
+
+    /// The root of this `Graph`, `None` if it is not a tree.
+    pub fn root(&self) -> Option<&NodeId> {
+        if !self.is_tree() {
+            return None;
+        }
+
+        self.bwdmap.iter().find_map(|(id, froms)| froms.is_empty().then_some(id))
+    }
+
+    /// The roots of this `Graph` in the order of their ids, empty if it is not a forest.
+    pub fn roots(&self) -> Vec<&NodeId> {
+        if !self.is_forest() {
+            return Vec::new();
+        }
+
+        let mut roots: Vec<&NodeId> = self
+            .bwdmap
+            .par_iter()
+            .filter_map(|(id, froms)| froms.is_empty().then_some(id))
+            .collect();
+        roots.sort_unstable();
+
+        roots
+    }
+
+    /// Ids of the nodes with an edge from the node with `id`, in the order of their ids.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node with `id`, `Ok` with the ids otherwise.
+    pub fn children(&self, id: &NodeId) -> Result<Vec<&NodeId>, DotGraphError> {
+        let mut children: Vec<&NodeId> = self.tos(id)?.into_iter().collect();
+        children.sort_unstable();
+
+        Ok(children)
+    }
+
+    /// The id of the node with an edge to the node with `id`, if it has exactly one.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node with `id`, `Ok` with the id otherwise.
+    pub fn parent(&self, id: &NodeId) -> Result<Option<&NodeId>, DotGraphError> {
+        let froms = self.froms(id)?;
+
+        Ok(if froms.len() == 1 { froms.into_iter().next() } else { None })
+    }
+
+    /// Constructs a new `Graph`, containing the node with `id` and all its descendants,
+    /// which is the subtree rooted at it if this graph is a forest.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node with `id`, `Ok` with the subtree otherwise.
+    pub fn subtree(&self, id: &NodeId) -> Result<Graph, DotGraphError> {
+        let id = self.node_id(id)?;
+
+        Ok(self.forward_slice(id, None))
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{
+    edge::Edge,
+    error::DotGraphError,
+    graphs::{graph::Graph, traversal::Direction},
+    node::NodeId,
+};
+
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// How a traversal proceeds after a `GraphVisitor` callback.
+pub enum Control {
+    /// Keep traversing
+    Continue,
+    /// Do not follow the edges of the discovered node, or the examined edge
+    Prune,
+    /// Stop the traversal
+    Break,
+}
+
+/// A `GraphVisitor` is driven through a depth-first traversal by `Graph::visit`.
+///
+/// Every callback continues the traversal by default.
+pub trait GraphVisitor {
+    /// Called when a node is reached for the first time
+    fn discover_node(&mut self, _id: &NodeId) -> Control {
+        Control::Continue
+    }
+
+    /// Called for every edge followed out of a discovered node,
+    /// whether or not the node at its other end has been discovered
+    fn examine_edge(&mut self, _edge: &Edge) -> Control {
+        Control::Continue
+    }
+
+    /// Called when all edges of a node have been examined; `Control::Prune` has no effect
+    fn finish_node(&mut self, _id: &NodeId) -> Control {
+        Control::Continue
+    }
+}
+
+impl Graph {
+    /// Traverse the graph depth-first from `start` in `direction`, calling back `visitor`.
+    /// Edges of a node are examined in the order of the ids of the nodes at their other ends.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if there is no node with `start`,
+    /// `Ok` with `Control::Break` if the visitor stopped the traversal,
+    /// `Ok` with `Control::Continue` otherwise.
+    pub fn visit<V>(
+        &self,
+        start: &NodeId,
+        direction: Direction,
+        visitor: &mut V,
+    ) -> Result<Control, DotGraphError>
+    where
+        V: GraphVisitor + ?Sized,
+    {
+        let start = &self
+            .nodes
+            .get(start)
+            .ok_or_else(|| DotGraphError::NoSuchNode(start.clone(), self.id.clone()))?
+            .id;
+
+        let index = self.incident_edges(direction);
+        let none = Vec::new();
+        let edges = |id: &NodeId| index.get(id).unwrap_or(&none).iter();
+
+        let mut discovered = HashSet::from([start]);
+        let mut stack = Vec::new();
+        match visitor.discover_node(start) {
+            Control::Break => return Ok(Control::Break),
+            Control::Prune => stack.push((start, none.iter())),
+            Control::Continue => stack.push((start, edges(start))),
+        }
+
+        while let Some((id, nexts)) = stack.last_mut() {
+            let id = *id;
+            let Some(&(edge, next)) = nexts.next() else {
+                stack.pop();
+                if visitor.finish_node(id) == Control::Break {
+                    return Ok(Control::Break);
+                }
+                continue;
+            };
+
+            match visitor.examine_edge(edge) {
+                Control::Break => return Ok(Control::Break),
+                Control::Prune => continue,
+                Control::Continue => {}
+            }
+            if discovered.insert(next) {
+                match visitor.discover_node(next) {
+                    Control::Break => return Ok(Control::Break),
+                    Control::Prune => stack.push((next, none.iter())),
+                    Control::Continue => stack.push((next, edges(next))),
+                }
+            }
+        }
+
+        Ok(Control::Continue)
+    }
+
+    /// Edges of each node in `direction`, paired with the nodes at their other ends
+    fn incident_edges(&self, direction: Direction) -> HashMap<&NodeId, Vec<(&Edge, &NodeId)>> {
+        let mut index: HashMap<&NodeId, Vec<(&Edge, &NodeId)>> = HashMap::new();
+        for edge in &self.edges {
+            if matches!(direction, Direction::Forward | Direction::Both) {
+                index.entry(&edge.id.from).or_default().push((edge, &edge.id.to));
+            }
+            if matches!(direction, Direction::Backward | Direction::Both) {
+                index.entry(&edge.id.to).or_default().push((edge, &edge.id.from));
+            }
+        }
+        for edges in index.values_mut() {
+            edges.sort_by_cached_key(|(edge, next)| (*next, edge.id.to_string()));
+        }
+
+        index
+    }
+}
@@ -0,0 +1,288 @@
+use crate::{attr::Attr, edge::Edge, graphs::subgraph::SubGraph, node::Node};
+
+use std::collections::HashSet;
+use std::io::{Result, Write};
+
+#[derive(Debug, Clone, Copy)]
+/// A `DotElement` is what an attribute given to the filter of `Graph::to_dot_with` belongs to.
+pub enum DotElement<'a> {
+    /// The root graph or a subgraph, of its `graph [...]` statement
+    Graph(&'a SubGraph),
+    Node(&'a Node),
+    Edge(&'a Edge),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An `AttrAction` tells `Graph::to_dot_with` what to write of an attribute.
+pub enum AttrAction {
+    /// Write the attribute as it is
+    Keep,
+    /// Do not write the attribute
+    Skip,
+    /// Write the given attribute in place of it
+    Rewrite(Attr),
+}
+
+/// A write-time filter of attributes, deciding what to write of each of them
+pub(crate) type AttrFilter<'f> = dyn Fn(DotElement, &Attr) -> AttrAction + 'f;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Where the opening brace of a graph or subgraph is written
+pub enum BraceStyle {
+    /// At the end of its header, as in `subgraph x {`
+    SameLine,
+    /// In its own line below its header, at the same level of indentation
+    NextLine,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Which statements end with a semicolon
+pub enum Semicolons {
+    /// Node statements and `node [...]` or `edge [...]` default statements, as `Graph::to_dot` writes
+    Declarations,
+    /// Every statement, including edge and `graph [...]` statements
+    Always,
+    /// None of the statements
+    Never,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A `DotWriteOptions` configures how `Graph::to_dot_with_options` lays out dot.
+///
+/// By default, as `Graph::to_dot` writes, every attribute takes its own line and each level
+/// of nesting is indented by a tab, opening braces end the headers of graphs and subgraphs,
+/// and only node and default statements end with semicolons.
+pub struct DotWriteOptions {
+    /// Whether attribute lists are written in a single line, as in `[a="1", b="2"]`
+    inline: bool,
+    /// String written once per level of nesting
+    indent: String,
+    /// Whether subgraphs, nodes, edges, and attributes are sorted by their ids or keys
+    sorted: bool,
+    /// Whether attributes shared by all nodes or edges of a subgraph are written once
+    /// as its default attribute statements
+    defaults: bool,
+    /// Where opening braces are written
+    braces: BraceStyle,
+    /// Which statements end with a semicolon
+    semicolons: Semicolons,
+}
+
+impl Default for DotWriteOptions {
+    fn default() -> DotWriteOptions {
+        DotWriteOptions::pretty()
+    }
+}
+
+impl DotWriteOptions {
+    /// Options writing every attribute in its own line, indented by tabs
+    pub fn pretty() -> DotWriteOptions {
+        DotWriteOptions {
+            inline: false,
+            indent: String::from("\t"),
+            sorted: false,
+            defaults: false,
+            braces: BraceStyle::SameLine,
+            semicolons: Semicolons::Declarations,
+        }
+    }
+
+    /// Options writing attribute lists in single lines without any indentation,
+    /// to keep large graphs small
+    pub fn compact() -> DotWriteOptions {
+        DotWriteOptions { inline: true, indent: String::new(), ..DotWriteOptions::pretty() }
+    }
+
+    /// Options writing attribute lists in single lines, sorting subgraphs, nodes, edges,
+    /// and attributes, and ending every statement with a semicolon, as
+    /// `Graph::to_canonical_dot` writes
+    pub fn canonical() -> DotWriteOptions {
+        DotWriteOptions {
+            inline: true,
+            sorted: true,
+            semicolons: Semicolons::Always,
+            ..DotWriteOptions::pretty()
+        }
+    }
+
+    /// Write attribute lists in single lines if `inline`, otherwise each attribute in its own line
+    pub fn inline_attrs(mut self, inline: bool) -> DotWriteOptions {
+        self.inline = inline;
+        self
+    }
+
+    /// Indent each level of nesting by `indent`, e.g. `"  "`
+    pub fn indent(mut self, indent: &str) -> DotWriteOptions {
+        self.indent = String::from(indent);
+        self
+    }
+
+    /// Indent each level of nesting by `width` spaces
+    pub fn spaces(self, width: usize) -> DotWriteOptions {
+        self.indent(&" ".repeat(width))
+    }
+
+    /// Indent each level of nesting by a tab
+    pub fn tabs(self) -> DotWriteOptions {
+        self.indent("\t")
+    }
+
+    /// Sort subgraphs, nodes, edges, and attributes by their ids or keys if `sorted`,
+    /// so that equal graphs are always written the same
+    pub fn sorted(mut self, sorted: bool) -> DotWriteOptions {
+        self.sorted = sorted;
+        self
+    }
+
+    /// Write attributes shared by all nodes, or all edges, a subgraph holds once as its
+    /// `node [...]` or `edge [...]` statement if `defaults`, instead of on every one of them
+    pub fn factor_defaults(mut self, defaults: bool) -> DotWriteOptions {
+        self.defaults = defaults;
+        self
+    }
+
+    /// Write opening braces of graphs and subgraphs as `braces` places them
+    pub fn braces(mut self, braces: BraceStyle) -> DotWriteOptions {
+        self.braces = braces;
+        self
+    }
+
+    /// End statements with semicolons as `semicolons` requires
+    pub fn semicolons(mut self, semicolons: Semicolons) -> DotWriteOptions {
+        self.semicolons = semicolons;
+        self
+    }
+
+    pub fn is_inline(&self) -> bool {
+        self.inline
+    }
+
+    pub fn is_sorted(&self) -> bool {
+        self.sorted
+    }
+
+    pub fn factors_defaults(&self) -> bool {
+        self.defaults
+    }
+
+    pub fn brace_style(&self) -> BraceStyle {
+        self.braces
+    }
+
+    pub fn semicolon_style(&self) -> Semicolons {
+        self.semicolons
+    }
+
+    /// Write the opening brace after the header of a graph written at `depth` levels of nesting
+    pub(crate) fn write_open<W>(&self, depth: usize, writer: &mut W) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        match self.braces {
+            BraceStyle::SameLine => writeln!(writer, " {{"),
+            BraceStyle::NextLine => {
+                writeln!(writer)?;
+                self.write_indent(depth, writer)?;
+                writeln!(writer, "{{")
+            }
+        }
+    }
+
+    /// End a statement, which is a node or default statement if `declaration`
+    pub(crate) fn write_end<W>(&self, declaration: bool, writer: &mut W) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        match self.semicolons {
+            Semicolons::Always => writeln!(writer, ";"),
+            Semicolons::Declarations if declaration => writeln!(writer, ";"),
+            _ => writeln!(writer),
+        }
+    }
+
+    /// Write the indentation of `depth` levels of nesting
+    pub(crate) fn write_indent<W>(&self, depth: usize, writer: &mut W) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        (0..depth).try_for_each(|_| write!(writer, "{}", self.indent))
+    }
+
+    /// Write `attrs` as a bracketed list, of an element written at `depth` levels of nesting
+    pub(crate) fn write_attrs<'a, W>(
+        &self,
+        attrs: impl IntoIterator<Item = &'a Attr>,
+        depth: usize,
+        writer: &mut W,
+    ) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        let mut attrs: Vec<&Attr> = attrs.into_iter().collect();
+        if self.sorted {
+            attrs.sort_unstable_by(|a, b| a.key.cmp(&b.key));
+        }
+
+        if self.inline {
+            write!(writer, "[")?;
+            for (i, attr) in attrs.into_iter().enumerate() {
+                if i > 0 {
+                    write!(writer, ", ")?;
+                }
+                attr.write_value(writer)?;
+            }
+            write!(writer, "]")
+        } else {
+            writeln!(writer, "[")?;
+            for attr in attrs {
+                self.write_indent(depth + 1, writer)?;
+                attr.write_value(writer)?;
+                writeln!(writer)?;
+            }
+            self.write_indent(depth, writer)?;
+            write!(writer, "]")
+        }
+    }
+}
+
+/// Attributes of `element` to write, out of its `attrs`, as `filter` keeps or rewrites them
+pub(crate) fn filter_attrs(
+    element: DotElement,
+    attrs: &HashSet<Attr>,
+    filter: &AttrFilter,
+) -> HashSet<Attr> {
+    attrs
+        .iter()
+        .filter_map(|attr| match filter(element, attr) {
+            AttrAction::Keep => Some(attr.clone()),
+            AttrAction::Skip => None,
+            AttrAction::Rewrite(attr) => Some(attr),
+        })
+        .collect()
+}
+
+/// Attributes with the same values in all of `attrs`, empty if there are less than two of them
+pub(crate) fn shared_attrs<'a>(
+    mut attrs: impl Iterator<Item = &'a HashSet<Attr>>,
+) -> HashSet<Attr> {
+    let Some(first) = attrs.next() else {
+        return HashSet::new();
+    };
+
+    let mut shared = first.clone();
+    let mut count = 1;
+    for attrs in attrs {
+        shared.retain(|attr| {
+            attrs
+                .get(attr)
+                .is_some_and(|other| other.value == attr.value && other.is_html == attr.is_html)
+        });
+        count += 1;
+    }
+
+    if count < 2 {
+        shared.clear();
+    }
+
+    shared
+}
@@ -0,0 +1,87 @@
+/// Default separator characters between layer names in `layers`, and between the ends of a
+/// range in `layer`
+pub(crate) const DEFAULT_LAYERSEP: &str = ":\t ";
+/// Default separator characters between layer ranges in `layer`
+pub(crate) const DEFAULT_LAYERLISTSEP: &str = ",";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A reference to a layer declared in the graph's `layers` attribute.
+pub enum LayerId {
+    /// The special `all` layer
+    All,
+    /// A layer by its name
+    Name(String),
+    /// A layer by its 1-based position in `layers`
+    Index(usize),
+}
+
+impl LayerId {
+    fn parse(id: &str) -> LayerId {
+        if id == "all" {
+            LayerId::All
+        } else if let Ok(index) = id.parse() {
+            LayerId::Index(index)
+        } else {
+            LayerId::Name(id.to_string())
+        }
+    }
+
+    /// 1-based position of the layer in `layers`, `None` for `all` or an undeclared name
+    fn position(&self, layers: &[&str]) -> Option<usize> {
+        match self {
+            LayerId::All => None,
+            LayerId::Name(name) => layers.iter().position(|layer| layer == name).map(|i| i + 1),
+            LayerId::Index(index) => Some(*index),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An entry of a `layer` attribute, either a single layer or an inclusive span of layers.
+pub enum LayerRange {
+    Single(LayerId),
+    Span(LayerId, LayerId),
+}
+
+impl LayerRange {
+    /// Whether this range covers the layer `name` among the declared `layers`.
+    pub fn contains(&self, layers: &[&str], name: &str) -> bool {
+        let Some(target) = layers.iter().position(|&layer| layer == name).map(|i| i + 1) else {
+            return false;
+        };
+
+        match self {
+            LayerRange::Single(LayerId::All) => true,
+            LayerRange::Single(id) => id.position(layers) == Some(target),
+            LayerRange::Span(lo, hi) => {
+                let lo = if *lo == LayerId::All { Some(1) } else { lo.position(layers) };
+                let hi = if *hi == LayerId::All { Some(layers.len()) } else { hi.position(layers) };
+
+                match (lo, hi) {
+                    (Some(lo), Some(hi)) => (lo.min(hi)..=lo.max(hi)).contains(&target),
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+/// Split a `layers` declaration into layer names.
+pub(crate) fn parse_layers<'a>(layers: &'a str, layersep: &str) -> Vec<&'a str> {
+    layers.split(|c| layersep.contains(c)).filter(|layer| !layer.is_empty()).collect()
+}
+
+/// Split a `layer` attribute into layer ranges.
+pub(crate) fn parse_ranges(layer: &str, layersep: &str, layerlistsep: &str) -> Vec<LayerRange> {
+    layer
+        .split(|c| layerlistsep.contains(c))
+        .filter_map(|range| {
+            let ids = parse_layers(range, layersep);
+            match ids[..] {
+                [] => None,
+                [id] => Some(LayerRange::Single(LayerId::parse(id))),
+                [lo, .., hi] => Some(LayerRange::Span(LayerId::parse(lo), LayerId::parse(hi))),
+            }
+        })
+        .collect()
+}
@@ -3,15 +3,23 @@ pub mod edge;
 pub mod error;
 pub mod graphs;
 mod graphviz;
+pub mod layer;
 pub mod node;
 pub mod parser;
+pub mod query;
 mod utils;
 
 pub mod prelude {
     pub use crate::attr::Attr;
     pub use crate::edge::{Edge, EdgeId};
     pub use crate::error::DotGraphError;
-    pub use crate::graphs::{Graph, GraphId, SubGraph};
-    pub use crate::node::{Node, NodeId};
+    pub use crate::graphs::{
+        AttrAction, BraceStyle, Control, Direction, DotElement, DotWriteOptions, DotWriter,
+        Encoder, ExtractionMap, Graph, GraphChange, GraphId, GraphVisitor, LayoutEngine,
+        RenderFormat, Semicolons, SubGraph,
+    };
+    pub use crate::layer::{LayerId, LayerRange};
+    pub use crate::node::{Node, NodeId, Port, PortKind};
     pub use crate::parser;
+    pub use crate::query;
 }
@@ -1,7 +1,16 @@
-use crate::{attr::Attr, utils};
+pub mod port;
+
+use crate::{
+    attr::Attr,
+    graphs::DotWriteOptions,
+    layer::{self, LayerRange},
+    utils,
+};
+pub use port::{Port, PortKind};
 
 use std::borrow::Borrow;
 use std::collections::HashSet;
+use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::io::{Result, Write};
 
@@ -35,7 +44,7 @@ impl Borrow<NodeId> for Node {
 }
 
 impl Node {
-    pub(crate) fn new(id: NodeId, attrs: HashSet<Attr>) -> Node {
+    pub fn new(id: NodeId, attrs: HashSet<Attr>) -> Node {
         Node { id, attrs }
     }
 
@@ -47,22 +56,72 @@ impl Node {
         &self.attrs
     }
 
+    /// Ports declared in the label of the node, either as fields of a record label
+    /// or as `PORT` attributes of an HTML-like label, in order of declaration.
+    pub fn ports(&self) -> Vec<Port> {
+        let is_record = self
+            .attrs
+            .get("shape")
+            .is_some_and(|shape| shape.value == "record" || shape.value == "Mrecord");
+
+        match self.attrs.get("label") {
+            Some(label) if label.is_html => port::parse_html(&label.value),
+            Some(label) if is_record => port::parse_record(&label.value),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Layers of the node as declared in its `layer` attribute,
+    /// `None` if it has none and thus belongs to all layers.
+    ///
+    /// The attribute is split with the default `layersep` and `layerlistsep`.
+    pub fn layers(&self) -> Option<Vec<LayerRange>> {
+        self.attrs.get("layer").map(|attr| {
+            layer::parse_ranges(&attr.value, layer::DEFAULT_LAYERSEP, layer::DEFAULT_LAYERLISTSEP)
+        })
+    }
+
     /// Write the node to dot format
-    pub fn to_dot<W: ?Sized>(&self, indent: usize, writer: &mut W) -> Result<()>
+    pub fn to_dot<W>(&self, indent: usize, writer: &mut W) -> Result<()>
     where
-        W: Write,
+        W: Write + ?Sized,
     {
-        let id = utils::pretty_id(&self.id);
-        (0..indent).try_for_each(|_| write!(writer, "\t"))?;
-        writeln!(writer, "{id} [")?;
+        self.write_dot(indent, &DotWriteOptions::default(), &HashSet::new(), writer)
+    }
 
-        for attr in &self.attrs {
-            attr.to_dot(indent + 1, writer)?;
+    /// Write the node to dot format, laid out by `options`,
+    /// omitting `defaults` declared by the subgraph holding it
+    pub(crate) fn write_dot<W>(
+        &self,
+        indent: usize,
+        options: &DotWriteOptions,
+        defaults: &HashSet<Attr>,
+        writer: &mut W,
+    ) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        let attrs: Vec<&Attr> =
+            self.attrs.iter().filter(|attr| !defaults.contains(*attr)).collect();
+        options.write_indent(indent, writer)?;
+        write!(writer, "{}", utils::pretty_id(&self.id))?;
+        if !(options.is_inline() && attrs.is_empty()) {
+            write!(writer, " ")?;
+            options.write_attrs(attrs, indent, writer)?;
         }
-
-        (0..indent).try_for_each(|_| write!(writer, "\t"))?;
-        writeln!(writer, "];")?;
+        options.write_end(true, writer)?;
 
         Ok(())
     }
 }
+
+impl fmt::Display for Node {
+    /// Writes the node in dot format, or a summary of it with `{:#}`, e.g. `node a with 2 attrs`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "node {} with {}", self.id, utils::count(self.attrs.len(), "attr"))
+        } else {
+            utils::fmt_with(f, |writer| self.to_dot(0, writer))
+        }
+    }
+}
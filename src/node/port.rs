@@ -0,0 +1,104 @@
+/// Compass points, which are valid ports of any node
+const COMPASS_POINTS: [&str; 10] = ["n", "ne", "e", "se", "s", "sw", "w", "nw", "c", "_"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Where a port is declared in a node's label.
+pub enum PortKind {
+    /// A `<port>` field of a record label
+    Record,
+    /// A `PORT` attribute of an HTML-like label element
+    Html,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A named `Port` of a node, to which edges can be attached.
+pub struct Port {
+    /// Name of the port
+    pub(crate) name: String,
+    /// Kind of label declaring the port
+    pub(crate) kind: PortKind,
+}
+
+impl Port {
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn kind(&self) -> PortKind {
+        self.kind
+    }
+}
+
+/// Whether an edge's `port` (e.g. `f0`, `f0:ne`, or `ne`) refers to one of `ports`.
+pub(crate) fn is_valid(port: &str, ports: &[Port]) -> bool {
+    let (name, compass) = port.rsplit_once(':').unwrap_or((port, ""));
+    if !compass.is_empty() && !COMPASS_POINTS.contains(&compass) {
+        return false;
+    }
+    if compass.is_empty() && COMPASS_POINTS.contains(&name) {
+        return true;
+    }
+
+    ports.iter().any(|port| port.name == name)
+}
+
+/// Ports declared as `<port>` fields in a record `label`, in order.
+pub(crate) fn parse_record(label: &str) -> Vec<Port> {
+    let mut ports = Vec::new();
+
+    let mut chars = label.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '<' => {
+                let mut name = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => name.extend(chars.next()),
+                        '>' => break,
+                        c => name.push(c),
+                    }
+                }
+                let name = name.trim().to_string();
+                if !name.is_empty() {
+                    ports.push(Port { name, kind: PortKind::Record });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    ports
+}
+
+/// Ports declared as `PORT` attributes of elements in an HTML-like `label`, in order.
+pub(crate) fn parse_html(label: &str) -> Vec<Port> {
+    let mut ports = Vec::new();
+
+    let bytes = label.as_bytes();
+    let mut in_tag = false;
+    for i in 0..bytes.len() {
+        match bytes[i] {
+            b'<' => in_tag = true,
+            b'>' => in_tag = false,
+            _ if in_tag && bytes[i..].get(..4).is_some_and(|s| s.eq_ignore_ascii_case(b"port")) => {
+                let preceded = bytes[i - 1].is_ascii_whitespace();
+                let rest = label[i + 4..].trim_start();
+                if let (true, Some(rest)) = (preceded, rest.strip_prefix('=')) {
+                    let rest = rest.trim_start();
+                    if let Some(quote) = rest.chars().next().filter(|&c| c == '"' || c == '\'') {
+                        if let Some(end) = rest[1..].find(quote) {
+                            let name = rest[1..1 + end].to_string();
+                            ports.push(Port { name, kind: PortKind::Html });
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    ports
+}
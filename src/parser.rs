@@ -12,6 +12,8 @@ use crate::{
 
 use std::collections::HashSet;
 use std::ffi::{CStr, CString};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
 
 unsafe fn c_to_rust_string(ptr: *const i8) -> String {
@@ -29,25 +31,7 @@ unsafe fn c_to_rust_string(ptr: *const i8) -> String {
 /// `Err` if the given file is not a graph or is not a DAG,
 /// otherwise `Ok` with the parsed graph.
 pub fn parse_from_file(path: &str) -> Result<Graph, DotGraphError> {
-    if !Path::new(path).exists() {
-        return Err(DotGraphError::InvalidGraph(String::from(path)));
-    }
-
-    let cpath = CString::new(path).unwrap();
-    let coption = CString::new("r").unwrap();
-    unsafe {
-        let fp = fopen(cpath.as_ptr(), coption.as_ptr());
-
-        let graph = agread(fp as _, 0 as _);
-        if graph.is_null() {
-            return Err(DotGraphError::InvalidGraph(String::from(path)));
-        }
-        if agisdirected(graph) == 0 {
-            return Err(DotGraphError::UndirectedGraph(String::from(path)));
-        }
-
-        parse_graph(graph)
-    }
+    Parser::new().parse_from_file(path)
 }
 
 /// Parse the given dot format file from memory.
@@ -61,31 +45,469 @@ pub fn parse_from_file(path: &str) -> Result<Graph, DotGraphError> {
 /// `Err` if the given file is not a graph or is not a DAG,
 /// otherwise `Ok` with the parsed graph.
 pub fn parse_from_memory(contents: &str) -> Result<Graph, DotGraphError> {
-    let ccontents = CString::new(contents).unwrap();
+    Parser::new().parse_from_memory(contents)
+}
 
-    unsafe {
-        let graph = agmemread(ccontents.as_ptr());
-        if graph.is_null() {
-            return Err(DotGraphError::InvalidGraph(String::from(contents)));
+/// Parse the given Trivial Graph Format (TGF) contents into a graph named `G`.
+///
+/// Nodes are named after their numbers, and node and edge labels are kept as `label`
+/// attributes, so that what `Graph::to_tgf` writes is read back. An edge listed again, as
+/// yEd does for multigraphs, gets its label updated by the later line, as a `Graph` holds no
+/// parallel edges between the same ports.
+///
+/// # Arguments
+///
+/// * `contents` - Lines of `number [label]` declaring nodes, then a `#` line, then lines of
+///   `from to [label]` declaring edges between node numbers
+///
+/// # Returns
+///
+/// `Err` if a line is malformed, if two nodes have the same number, or if an edge refers to an
+/// undeclared node, otherwise `Ok` with the parsed graph.
+pub fn parse_tgf(contents: &str) -> Result<Graph, DotGraphError> {
+    let id = String::from("G");
+    let invalid = |line: &str| DotGraphError::InvalidGraph(String::from(line));
+    let label = |label: &str| match label.trim() {
+        "" => None,
+        label => Some(Attr::new(String::from("label"), String::from(label), false)),
+    };
+
+    let mut lines = contents.lines().map(str::trim).filter(|line| !line.is_empty());
+    let mut nodes = HashSet::new();
+    for line in lines.by_ref().take_while(|line| !line.starts_with('#')) {
+        let (number, text) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let node = Node::new(String::from(number), label(text).into_iter().collect());
+        if !nodes.insert(node) {
+            return Err(DotGraphError::DuplicateNode(String::from(number), id));
         }
-        if agisdirected(graph) == 0 {
-            return Err(DotGraphError::UndirectedGraph(String::from(contents)));
+    }
+
+    let mut edges: HashSet<Edge> = HashSet::new();
+    for line in lines {
+        let (from, rest) = line.split_once(char::is_whitespace).ok_or_else(|| invalid(line))?;
+        let rest = rest.trim_start();
+        let (to, text) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        let node = |number: &str| {
+            let number = String::from(number);
+            if nodes.contains(&number) {
+                Ok(number)
+            } else {
+                Err(DotGraphError::NoSuchNode(number, id.clone()))
+            }
+        };
+
+        let edge_id = EdgeId::new(node(from)?, None, node(to)?, None);
+        let mut attrs = edges.take(&edge_id).map(|edge| edge.attrs).unwrap_or_default();
+        if let Some(label) = label(text) {
+            attrs.replace(label);
         }
+        edges.insert(Edge::new(edge_id, attrs));
+    }
+
+    let root =
+        IGraph::new(id.clone(), HashSet::new(), nodes.clone(), edges.clone(), HashSet::new());
+    Graph::new(id, root, nodes, edges)
+}
 
-        parse_graph(graph)
+#[derive(Debug, Clone)]
+/// An `EdgeListOptions` configures how `parse_edge_list` splits lines of an edge list
+/// and names its attribute columns.
+pub struct EdgeListOptions {
+    /// Character separating fields of a line
+    delimiter: char,
+    /// Whether the first line is a header naming the columns
+    header: bool,
+    /// Keys of attributes in columns after the ends of each edge
+    columns: Vec<String>,
+}
+
+impl Default for EdgeListOptions {
+    fn default() -> EdgeListOptions {
+        EdgeListOptions { delimiter: ',', header: false, columns: Vec::new() }
     }
 }
 
-fn parse_graph(graph: *mut Agraph_s) -> Result<Graph, DotGraphError> {
-    let id = parse_name(graph as _);
+impl EdgeListOptions {
+    pub fn new() -> EdgeListOptions {
+        EdgeListOptions::default()
+    }
+
+    /// Separate fields by `delimiter` instead of `,`, e.g. `\t` for TSV
+    pub fn delimiter(mut self, delimiter: char) -> EdgeListOptions {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Take the first line as a header, naming attribute columns after its fields
+    pub fn header(mut self, header: bool) -> EdgeListOptions {
+        self.header = header;
+        self
+    }
+
+    /// Name attribute columns, following the ends of each edge, by `columns`
+    pub fn columns(mut self, columns: Vec<String>) -> EdgeListOptions {
+        self.columns = columns;
+        self
+    }
+}
+
+/// Parse an edge list of `from,to[,attr...]` lines into a graph named `G`, e.g., as in CSV.
+///
+/// Fields may be quoted by `"`, with `""` escaping a quote, keeping delimiters, line breaks,
+/// and surrounding whitespace within them, while unquoted fields are trimmed.
+/// Columns named `tailport` or `headport` give the ports of edges, so that what
+/// `Graph::to_csv` writes is read back. Blank lines and lines starting with `#` are skipped.
+/// Nodes are created for the ends of edges, and attribute columns without a name or with
+/// an empty field are not kept. An edge listed again gets its attributes updated by the later line.
+///
+/// # Arguments
+///
+/// * `reader` - Source of the edge list
+/// * `options` - How to split lines and name attribute columns
+///
+/// # Returns
+///
+/// `Err` if reading fails, if a line does not have both ends, or if a quoted field is not closed,
+/// otherwise `Ok` with the parsed graph.
+pub fn parse_edge_list<R: Read>(
+    reader: R,
+    options: &EdgeListOptions,
+) -> Result<Graph, DotGraphError> {
+    let id = String::from("G");
+
+    let mut lines = BufReader::new(reader).lines();
+    let mut columns = options.columns.clone();
+    if options.header {
+        if let Some(line) = lines.next() {
+            let fields = read_record(&line?, &mut lines, options.delimiter)?;
+            columns = fields.into_iter().skip(2).collect();
+        }
+    }
 
     let mut nodes = HashSet::new();
-    let mut edges = HashSet::new();
-    let root = parse_igraph(graph, &mut nodes, &mut edges);
+    let mut edges: HashSet<Edge> = HashSet::new();
+    while let Some(line) = lines.next() {
+        let line = line?;
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = read_record(&line, &mut lines, options.delimiter)?.into_iter();
+        let (Some(from), Some(to)) = (fields.next(), fields.next()) else {
+            return Err(DotGraphError::InvalidGraph(line));
+        };
+
+        let mut tailport = None;
+        let mut headport = None;
+        let mut values = Vec::new();
+        for (key, value) in columns.iter().zip(fields) {
+            if key.is_empty() || value.is_empty() {
+                continue;
+            }
+            match key.as_str() {
+                "tailport" => tailport = Some(value),
+                "headport" => headport = Some(value),
+                _ => values.push(Attr::new(key.clone(), value, false)),
+            }
+        }
 
+        let edge_id = EdgeId::new(from.clone(), tailport, to.clone(), headport);
+        let mut attrs = edges.take(&edge_id).map(|edge| edge.attrs).unwrap_or_default();
+        for attr in values {
+            attrs.replace(attr);
+        }
+
+        nodes.insert(Node::new(from, HashSet::new()));
+        nodes.insert(Node::new(to, HashSet::new()));
+        edges.insert(Edge::new(edge_id, attrs));
+    }
+
+    let root =
+        IGraph::new(id.clone(), HashSet::new(), nodes.clone(), edges.clone(), HashSet::new());
     Graph::new(id, root, nodes, edges)
 }
 
+/// Split the record starting with `line` into fields separated by `delimiter`,
+/// taking the following lines of `lines` into it while a quoted field goes on
+fn read_record<I>(line: &str, lines: &mut I, delimiter: char) -> Result<Vec<String>, DotGraphError>
+where
+    I: Iterator<Item = std::io::Result<String>>,
+{
+    if let Some(fields) = split_fields(line, delimiter) {
+        return Ok(fields);
+    }
+
+    let mut record = String::from(line);
+    loop {
+        let Some(line) = lines.next() else {
+            return Err(DotGraphError::InvalidGraph(record));
+        };
+        record.push('\n');
+        record.push_str(&line?);
+
+        if let Some(fields) = split_fields(&record, delimiter) {
+            return Ok(fields);
+        }
+    }
+}
+
+/// Split `record` into fields separated by `delimiter`, unquoting those quoted by `"`
+/// and trimming those not quoted.
+///
+/// `None` if a quoted field is not closed by the end of `record`.
+fn split_fields(record: &str, delimiter: char) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    // whether the field started with a quote, and whether the quote is still open
+    let mut quoted = false;
+    let mut open = false;
+
+    let finish =
+        |field: String, quoted: bool| if quoted { field } else { field.trim().to_string() };
+
+    let mut chars = record.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if open && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if open => open = false,
+            '"' if !quoted && field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+                open = true;
+            }
+            c if c == delimiter && !open => {
+                fields.push(finish(std::mem::take(&mut field), quoted));
+                quoted = false;
+            }
+            // whitespace between a closing quote and the delimiter
+            c if quoted && !open && c.is_whitespace() => {}
+            c => field.push(c),
+        }
+    }
+    if open {
+        return None;
+    }
+    fields.push(finish(field, quoted));
+
+    Some(fields)
+}
+
+#[derive(Debug, Default)]
+/// A `Parser` keeps its buffers across repeated parses,
+/// to reduce allocations when parsing many similar graphs.
+pub struct Parser {
+    /// Expected number of nodes in a graph
+    nodes: usize,
+    /// Expected number of edges in a graph
+    edges: usize,
+    /// Nul-terminated contents handed to cgraph
+    buffer: Vec<u8>,
+}
+
+impl Parser {
+    pub fn new() -> Parser {
+        Parser::default()
+    }
+
+    /// Constructs a new `Parser`, expecting graphs of about `nodes` nodes and `edges` edges.
+    /// The hints grow to fit the largest graph parsed so far.
+    pub fn with_capacity_hint(nodes: usize, edges: usize) -> Parser {
+        Parser { nodes, edges, buffer: Vec::new() }
+    }
+
+    /// Parse the given dot format file in `path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the dot file in `&str`
+    ///
+    /// # Returns
+    ///
+    /// `Err` if the given file is not a graph or is not a DAG,
+    /// otherwise `Ok` with the parsed graph.
+    pub fn parse_from_file(&mut self, path: &str) -> Result<Graph, DotGraphError> {
+        if !Path::new(path).exists() {
+            return Err(DotGraphError::InvalidGraph(String::from(path)));
+        }
+
+        let cpath = CString::new(path).unwrap();
+        let coption = CString::new("r").unwrap();
+        unsafe {
+            let fp = fopen(cpath.as_ptr(), coption.as_ptr());
+
+            let graph = agread(fp as _, 0 as _);
+            if graph.is_null() {
+                return Err(DotGraphError::InvalidGraph(String::from(path)));
+            }
+            if agisdirected(graph) == 0 {
+                return Err(DotGraphError::UndirectedGraph(String::from(path)));
+            }
+
+            self.parse_graph(graph)
+        }
+    }
+
+    /// Parse the given dot format file from memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - Contents of the dot file in `&str`
+    ///
+    /// # Returns
+    ///
+    /// `Err` if the given file is not a graph or is not a DAG,
+    /// otherwise `Ok` with the parsed graph.
+    pub fn parse_from_memory(&mut self, contents: &str) -> Result<Graph, DotGraphError> {
+        self.buffer.clear();
+        self.buffer.extend_from_slice(contents.as_bytes());
+        self.buffer.push(0);
+
+        let ccontents = CStr::from_bytes_with_nul(&self.buffer)
+            .map_err(|_| DotGraphError::InvalidGraph(String::from(contents)))?;
+
+        unsafe {
+            let graph = agmemread(ccontents.as_ptr());
+            if graph.is_null() {
+                return Err(DotGraphError::InvalidGraph(String::from(contents)));
+            }
+            if agisdirected(graph) == 0 {
+                return Err(DotGraphError::UndirectedGraph(String::from(contents)));
+            }
+
+            self.parse_graph(graph)
+        }
+    }
+
+    fn parse_graph(&mut self, graph: *mut Agraph_s) -> Result<Graph, DotGraphError> {
+        let id = parse_name(graph as _);
+
+        let mut nodes = HashSet::with_capacity(self.nodes);
+        let mut edges = HashSet::with_capacity(self.edges);
+        let root = parse_igraph(graph, &mut nodes, &mut edges);
+
+        self.nodes = self.nodes.max(nodes.len());
+        self.edges = self.edges.max(edges.len());
+
+        Graph::new(id, root, nodes, edges)
+    }
+}
+
+/// An incremental parser for a dot file that keeps growing,
+/// e.g. by a producer appending a digraph per iteration.
+pub struct Incremental {
+    /// The file being parsed
+    file: File,
+    /// Contents read from the file but not yet parsed
+    pending: Vec<u8>,
+    /// Parser reused across the appended graphs
+    parser: Parser,
+}
+
+impl Incremental {
+    /// Open the dot file in `path` to be parsed incrementally.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the dot file in `&str`
+    ///
+    /// # Returns
+    ///
+    /// `Err` if the given file cannot be opened,
+    /// otherwise `Ok` with the incremental parser.
+    pub fn open(path: &str) -> Result<Incremental, DotGraphError> {
+        let file = File::open(path)?;
+
+        Ok(Incremental { file, pending: Vec::new(), parser: Parser::new() })
+    }
+
+    /// Parse the next complete graph appended to the file.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if the next graph is not a graph or is not a DAG,
+    /// `Ok(None)` if no complete graph has been appended yet,
+    /// otherwise `Ok` with the parsed graph.
+    pub fn poll(&mut self) -> Result<Option<Graph>, DotGraphError> {
+        self.file.read_to_end(&mut self.pending)?;
+
+        let Some(end) = graph_end(&self.pending) else {
+            return Ok(None);
+        };
+        let contents: Vec<u8> = self.pending.drain(..end).collect();
+        let contents = String::from_utf8_lossy(&contents);
+
+        self.parser.parse_from_memory(&contents).map(Some)
+    }
+}
+
+/// Find the end of the first complete graph in `contents`,
+/// skipping braces within strings and comments.
+fn graph_end(contents: &[u8]) -> Option<usize> {
+    let len = contents.len();
+    let mut depth = 0;
+
+    let mut i = 0;
+    while i < len {
+        match contents[i] {
+            b'"' => {
+                i += 1;
+                while i < len && contents[i] != b'"' {
+                    if contents[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            b'<' => {
+                let mut nested = 1;
+                i += 1;
+                while i < len {
+                    match contents[i] {
+                        b'<' => nested += 1,
+                        b'>' => nested -= 1,
+                        _ => {}
+                    }
+                    if nested == 0 {
+                        break;
+                    }
+                    i += 1;
+                }
+            }
+            b'/' if contents.get(i + 1) == Some(&b'/') => {
+                while i < len && contents[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if contents.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i < len && !contents[i..].starts_with(b"*/") {
+                    i += 1;
+                }
+                i += 1;
+            }
+            b'#' if i == 0 || contents[i - 1] == b'\n' => {
+                while i < len && contents[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    None
+}
+
 fn parse_igraph(
     graph: *mut Agraph_s,
     nodes_visited: &mut HashSet<Node>,
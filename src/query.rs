@@ -0,0 +1,428 @@
+//! A small selector language over the nodes of a `Graph`.
+//!
+//! A query combines selectors with `&` (intersection), `|` (union), `-` (difference),
+//! `!` (complement), and parentheses, where `&` and `!` bind tighter than `|` and `-`.
+//!
+//! * `node` selects every node, and `node[key=value, ...]` those whose attributes match;
+//!   a test is either `key` (present), `key="value"` (equal), `key!="value"` (not equal),
+//!   or `key~="value"` (contains)
+//! * `in_degree`, `out_degree`, and `degree` compared by `=`, `!=`, `<`, `<=`, `>`, `>=`
+//!   with a number select nodes by their numbers of distinct neighbors
+//! * `id(x)`, `cluster(x)`, `reachable_from(x)`, `descendants(x)`, `ancestors(x)`,
+//!   and `neighbors(x, depth)` select nodes relative to the node or subgraph `x`
+//!
+//! For example, `node[label~="conv"] & out_degree > 2` or `reachable_from(foo) - cluster(bar)`.
+//! Ids that are not plain words, e.g. containing `-`, are written in double quotes.
+use crate::{
+    error::DotGraphError,
+    graphs::{Direction, Graph},
+    node::NodeId,
+};
+
+use std::collections::HashSet;
+
+use rayon::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A parsed query, to be evaluated against any number of graphs.
+pub struct Query {
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Nodes(Vec<AttrTest>),
+    Degree(Degree, Cmp, usize),
+    Call(Func, Vec<String>),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Minus(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum AttrTest {
+    Has(String),
+    Eq(String, String),
+    Ne(String, String),
+    Contains(String, String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Degree {
+    In,
+    Out,
+    Both,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Cmp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Func {
+    Id,
+    Cluster,
+    ReachableFrom,
+    Descendants,
+    Ancestors,
+    Neighbors,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Str(String),
+    Punct(&'static str),
+}
+
+/// Punctuations, longest first to be matched greedily
+const PUNCTS: [&str; 16] =
+    ["!=", "~=", "<=", ">=", "&", "|", "-", "!", "(", ")", "[", "]", ",", "=", "<", ">"];
+
+/// Parse a query.
+///
+/// # Arguments
+///
+/// * `query` - The query in `&str`
+///
+/// # Returns
+///
+/// `Err` if the given query is malformed,
+/// otherwise `Ok` with the parsed query.
+pub fn parse(query: &str) -> Result<Query, DotGraphError> {
+    let invalid = |reason: &str| DotGraphError::InvalidQuery(query.to_string(), reason.to_string());
+
+    let tokens = tokenize(query).map_err(|reason| invalid(&reason))?;
+    let mut parser = Parser { tokens: &tokens, pos: 0 };
+    let expr = parser.expr().map_err(|reason| invalid(&reason))?;
+    if let Some(token) = parser.peek() {
+        return Err(invalid(&format!("unexpected {}", describe(token))));
+    }
+
+    Ok(Query { expr })
+}
+
+impl Query {
+    /// Evaluate the query against `graph`.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if the query refers to a node or subgraph missing in `graph`,
+    /// otherwise `Ok` with the ids of selected nodes.
+    pub fn select<'a>(&self, graph: &'a Graph) -> Result<HashSet<&'a NodeId>, DotGraphError> {
+        eval(&self.expr, graph)
+    }
+}
+
+impl Graph {
+    /// Select nodes by `query`, as described in the `query` module.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if the query is malformed or refers to a node or subgraph missing in this graph,
+    /// otherwise `Ok` with the ids of selected nodes.
+    pub fn select(&self, query: &str) -> Result<HashSet<&NodeId>, DotGraphError> {
+        parse(query)?.select(self)
+    }
+
+    /// Constructs a new `Graph`, containing only the nodes selected by `query`.
+    ///
+    /// # Returns
+    ///
+    /// `Err` if the query is malformed or refers to a node or subgraph missing in this graph,
+    /// otherwise `Ok` with the selected `Graph`.
+    pub fn query(&self, query: &str) -> Result<Graph, DotGraphError> {
+        let selected: Vec<&NodeId> = self.select(query)?.into_iter().collect();
+
+        Ok(self.filter(&selected))
+    }
+}
+
+fn eval<'a>(expr: &Expr, graph: &'a Graph) -> Result<HashSet<&'a NodeId>, DotGraphError> {
+    let selected = match expr {
+        Expr::Nodes(tests) => graph
+            .nodes
+            .par_iter()
+            .filter(|node| {
+                tests.iter().all(|test| match test {
+                    AttrTest::Has(key) => node.attrs.contains(key),
+                    AttrTest::Eq(key, value) => {
+                        node.attrs.get(key).is_some_and(|attr| attr.value == *value)
+                    }
+                    AttrTest::Ne(key, value) => {
+                        node.attrs.get(key).is_none_or(|attr| attr.value != *value)
+                    }
+                    AttrTest::Contains(key, value) => {
+                        node.attrs.get(key).is_some_and(|attr| attr.value.contains(value.as_str()))
+                    }
+                })
+            })
+            .map(|node| &node.id)
+            .collect(),
+        Expr::Degree(degree, cmp, n) => graph
+            .nodes
+            .par_iter()
+            .filter(|node| {
+                let tos = &graph.fwdmap[&node.id];
+                let froms = &graph.bwdmap[&node.id];
+                let value = match degree {
+                    Degree::In => froms.len(),
+                    Degree::Out => tos.len(),
+                    Degree::Both => tos.union(froms).count(),
+                };
+                match cmp {
+                    Cmp::Eq => value == *n,
+                    Cmp::Ne => value != *n,
+                    Cmp::Lt => value < *n,
+                    Cmp::Le => value <= *n,
+                    Cmp::Gt => value > *n,
+                    Cmp::Ge => value >= *n,
+                }
+            })
+            .map(|node| &node.id)
+            .collect(),
+        Expr::Call(func, args) => {
+            let id = &args[0];
+            let node = || {
+                graph
+                    .nodes
+                    .get(id)
+                    .map(|node| &node.id)
+                    .ok_or_else(|| DotGraphError::NoSuchNode(id.clone(), graph.id.clone()))
+            };
+            match func {
+                Func::Id => HashSet::from([node()?]),
+                Func::Cluster => graph.collect_nodes(id)?.into_iter().collect(),
+                Func::ReachableFrom => graph.reach(&[node()?], Direction::Forward, None),
+                Func::Descendants => graph.descendants(id)?,
+                Func::Ancestors => graph.ancestors(id)?,
+                Func::Neighbors => {
+                    // the depth was validated while parsing
+                    let depth = args[1].parse().unwrap();
+                    graph.neighbor_ids(id, depth)?
+                }
+            }
+        }
+        Expr::Not(expr) => {
+            let excluded = eval(expr, graph)?;
+            graph
+                .nodes
+                .par_iter()
+                .map(|node| &node.id)
+                .filter(|id| !excluded.contains(id))
+                .collect()
+        }
+        Expr::And(lhs, rhs) => &eval(lhs, graph)? & &eval(rhs, graph)?,
+        Expr::Or(lhs, rhs) => &eval(lhs, graph)? | &eval(rhs, graph)?,
+        Expr::Minus(lhs, rhs) => &eval(lhs, graph)? - &eval(rhs, graph)?,
+    };
+
+    Ok(selected)
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+
+    let mut rest = query.trim_start();
+    while let Some(c) = rest.chars().next() {
+        if c == '"' {
+            let mut value = String::new();
+            let mut chars = rest[1..].char_indices();
+            let end = loop {
+                match chars.next() {
+                    Some((i, '"')) => break i + 2,
+                    Some((_, '\\')) => value.extend(chars.next().map(|(_, c)| c)),
+                    Some((_, c)) => value.push(c),
+                    None => return Err(String::from("unterminated string")),
+                }
+            };
+            tokens.push(Token::Str(value));
+            rest = &rest[end..];
+        } else if is_word(c) {
+            let end = rest.find(|c| !is_word(c)).unwrap_or(rest.len());
+            tokens.push(Token::Word(rest[..end].to_string()));
+            rest = &rest[end..];
+        } else if let Some(punct) = PUNCTS.iter().find(|punct| rest.starts_with(**punct)) {
+            tokens.push(Token::Punct(punct));
+            rest = &rest[punct.len()..];
+        } else {
+            return Err(format!("unexpected `{c}`"));
+        }
+        rest = rest.trim_start();
+    }
+
+    Ok(tokens)
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '.' | '/' | ':')
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Word(word) => format!("`{word}`"),
+        Token::Str(value) => format!("\"{value}\""),
+        Token::Punct(punct) => format!("`{punct}`"),
+    }
+}
+
+/// A recursive descent parser over the tokens of a query
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<&Token, String> {
+        let token = self.tokens.get(self.pos).ok_or_else(|| String::from("unexpected end"))?;
+        self.pos += 1;
+
+        Ok(token)
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        let matched = matches!(self.peek(), Some(Token::Punct(p)) if *p == punct);
+        if matched {
+            self.pos += 1;
+        }
+
+        matched
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<(), String> {
+        let token = self.next()?;
+        if matches!(token, Token::Punct(p) if *p == punct) {
+            Ok(())
+        } else {
+            Err(format!("expected `{punct}`, found {}", describe(token)))
+        }
+    }
+
+    /// A word or a quoted string
+    fn id(&mut self) -> Result<String, String> {
+        match self.next()? {
+            Token::Word(id) | Token::Str(id) => Ok(id.clone()),
+            token => Err(format!("expected an id, found {}", describe(token))),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.and()?;
+        loop {
+            if self.eat("|") {
+                lhs = Expr::Or(Box::new(lhs), Box::new(self.and()?));
+            } else if self.eat("-") {
+                lhs = Expr::Minus(Box::new(lhs), Box::new(self.and()?));
+            } else {
+                return Ok(lhs);
+            }
+        }
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.unary()?;
+        while self.eat("&") {
+            lhs = Expr::And(Box::new(lhs), Box::new(self.unary()?));
+        }
+
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat("!") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("(") {
+            let expr = self.expr()?;
+            self.expect(")")?;
+            return Ok(expr);
+        }
+
+        let word = match self.next()? {
+            Token::Word(word) => word.clone(),
+            token => return Err(format!("expected a selector, found {}", describe(token))),
+        };
+        match word.as_str() {
+            "node" => self.nodes(),
+            "in_degree" => self.degree(Degree::In),
+            "out_degree" => self.degree(Degree::Out),
+            "degree" => self.degree(Degree::Both),
+            "id" => self.call(Func::Id),
+            "cluster" => self.call(Func::Cluster),
+            "reachable_from" => self.call(Func::ReachableFrom),
+            "descendants" => self.call(Func::Descendants),
+            "ancestors" => self.call(Func::Ancestors),
+            "neighbors" => self.call(Func::Neighbors),
+            _ => Err(format!("unknown selector `{word}`")),
+        }
+    }
+
+    fn nodes(&mut self) -> Result<Expr, String> {
+        let mut tests = Vec::new();
+        if self.eat("[") {
+            loop {
+                let key = self.id()?;
+                let test = if self.eat("=") {
+                    AttrTest::Eq(key, self.id()?)
+                } else if self.eat("!=") {
+                    AttrTest::Ne(key, self.id()?)
+                } else if self.eat("~=") {
+                    AttrTest::Contains(key, self.id()?)
+                } else {
+                    AttrTest::Has(key)
+                };
+                tests.push(test);
+
+                if !self.eat(",") {
+                    break;
+                }
+            }
+            self.expect("]")?;
+        }
+
+        Ok(Expr::Nodes(tests))
+    }
+
+    fn degree(&mut self, degree: Degree) -> Result<Expr, String> {
+        let cmp = match self.next()? {
+            Token::Punct("=") => Cmp::Eq,
+            Token::Punct("!=") => Cmp::Ne,
+            Token::Punct("<") => Cmp::Lt,
+            Token::Punct("<=") => Cmp::Le,
+            Token::Punct(">") => Cmp::Gt,
+            Token::Punct(">=") => Cmp::Ge,
+            token => return Err(format!("expected a comparison, found {}", describe(token))),
+        };
+        let n = match self.next()? {
+            Token::Word(word) => word.parse().map_err(|_| format!("`{word}` is not a number"))?,
+            token => return Err(format!("expected a number, found {}", describe(token))),
+        };
+
+        Ok(Expr::Degree(degree, cmp, n))
+    }
+
+    fn call(&mut self, func: Func) -> Result<Expr, String> {
+        self.expect("(")?;
+        let mut args = vec![self.id()?];
+        if func == Func::Neighbors {
+            self.expect(",")?;
+            let depth = self.id()?;
+            depth.parse::<usize>().map_err(|_| format!("`{depth}` is not a number"))?;
+            args.push(depth);
+        }
+        self.expect(")")?;
+
+        Ok(Expr::Call(func, args))
+    }
+}
@@ -1,7 +1,145 @@
+/// Keywords of dot, which must be quoted to be taken as ids regardless of their case
+const KEYWORDS: [&str; 6] = ["node", "edge", "graph", "digraph", "subgraph", "strict"];
+
+/// Write `id` as a dot id, quoting it unless it is a plain name or a numeral.
+/// Names consist of letters, digits, underscores, and non-ASCII characters, not starting with
+/// a digit, and must not be keywords.
 pub(crate) fn pretty_id(id: &str) -> String {
-    if id.chars().all(char::is_alphanumeric) {
+    if is_plain_id(id) {
         id.to_string()
     } else {
-        format!("\"{id}\"")
+        quote(id)
     }
 }
+
+/// Write `port` as that of an edge end, as in `p` or `p:n`, quoting its name if needed
+pub(crate) fn pretty_port(port: &str) -> String {
+    match port.split_once(':') {
+        Some((id, compass)) => format!("{}:{}", pretty_id(id), pretty_id(compass)),
+        None => pretty_id(port),
+    }
+}
+
+/// Whether `id` can be written in dot without quotes
+pub(crate) fn is_plain_id(id: &str) -> bool {
+    let is_name = id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || !c.is_ascii())
+        && !id.starts_with(|c: char| c.is_ascii_digit())
+        && !KEYWORDS.iter().any(|keyword| id.eq_ignore_ascii_case(keyword));
+
+    !id.is_empty() && (is_name || is_numeral(id))
+}
+
+/// Whether `id` is a dot numeral, e.g. `-1`, `.5`, or `2.`
+fn is_numeral(id: &str) -> bool {
+    let digits = id.strip_prefix('-').unwrap_or(id);
+    let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+    let is_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    !(integer.is_empty() && fraction.is_empty()) && is_digits(integer) && is_digits(fraction)
+}
+
+/// Quote `s` as a dot string, escaping quotes within it.
+///
+/// Backslashes are kept as they are to keep escape sequences of labels like `\n` or `\l`,
+/// except for one ending `s`, which is doubled so as not to escape the closing quote.
+pub(crate) fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+
+    let mut escaped = false;
+    for c in s.chars() {
+        if c == '"' && !escaped {
+            quoted.push('\\');
+        }
+        escaped = c == '\\' && !escaped;
+        quoted.push(c);
+    }
+    if escaped {
+        quoted.push('\\');
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+/// Format `n` with `,` separating groups of thousands, e.g. `1,243`
+pub(crate) fn thousands(n: usize) -> String {
+    let digits = n.to_string();
+
+    let mut formatted = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+
+    formatted
+}
+
+/// Quote `s` as a JSON string, escaping quotes, backslashes, and control characters
+pub(crate) fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+/// Escape `s` to be put in XML text or attribute values
+pub(crate) fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Quote `s` as a CSV field if it holds a comma, a quote, or a line break,
+/// or if it starts or ends with whitespace, which readers may trim otherwise
+pub(crate) fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) || s.trim() != s {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Count `n` of `noun`, e.g. `1 node` or `1,243 nodes`
+pub(crate) fn count(n: usize, noun: &str) -> String {
+    if n == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{} {noun}s", thousands(n))
+    }
+}
+
+/// Write into `f` what `write` writes to a buffer, to implement `Display` by writers of dot
+pub(crate) fn fmt_with<F>(f: &mut std::fmt::Formatter<'_>, write: F) -> std::fmt::Result
+where
+    F: FnOnce(&mut Vec<u8>) -> std::io::Result<()>,
+{
+    let mut buffer = Vec::new();
+    write(&mut buffer).map_err(|_| std::fmt::Error)?;
+
+    f.write_str(&String::from_utf8_lossy(&buffer))
+}
@@ -1,3 +1,7 @@
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::str;
 
 use graphviz_rs::prelude::*;
@@ -43,6 +47,49 @@ fn world_dynamics() -> Result<(), DotGraphError> {
     parse_print_parse("world_dynamics.dot")
 }
 
+#[test]
+#[serial]
+fn incremental() -> Result<(), DotGraphError> {
+    let path = TempPath::new("incremental.dot");
+    fs::write(&path, "")?;
+    let mut file = OpenOptions::new().append(true).open(&path)?;
+
+    let mut parser = parser::Incremental::open(path.as_ref().to_str().unwrap())?;
+    assert!(parser.poll()?.is_none());
+
+    write!(file, "digraph first {{ a -> b; c [label=\"}}\"] ")?;
+    assert!(parser.poll()?.is_none());
+
+    writeln!(file, "}}\ndigraph second {{ d -> e }}")?;
+    assert_eq!(parser.poll()?.unwrap().id(), "first");
+    assert_eq!(parser.poll()?.unwrap().id(), "second");
+    assert!(parser.poll()?.is_none());
+
+    Ok(())
+}
+
+/// A path in the temporary directory unique to this process, removed once dropped
+struct TempPath(PathBuf);
+
+impl TempPath {
+    fn new(filename: &str) -> TempPath {
+        let filename = format!("dot_graph_{}_{filename}", std::process::id());
+        TempPath(std::env::temp_dir().join(filename))
+    }
+}
+
+impl AsRef<Path> for TempPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
 fn parse_print_parse(filename: &str) -> Result<(), DotGraphError> {
     let path = &format!("./tests/examples/{filename}");
 
@@ -59,3 +106,972 @@ fn parse_print_parse(filename: &str) -> Result<(), DotGraphError> {
 
     Ok(())
 }
+
+#[test]
+#[serial]
+fn parser_reuse() -> Result<(), DotGraphError> {
+    let mut parser = parser::Parser::with_capacity_hint(4, 4);
+
+    for n in 1..4 {
+        let edges: Vec<String> = (0..n).map(|i| format!("n{i} -> n{}", i + 1)).collect();
+        let graph = parser.parse_from_memory(&format!("digraph G {{ {} }}", edges.join("; ")))?;
+        assert_eq!(graph.nodes().len(), n + 1);
+        assert_eq!(graph.edges().len(), n);
+    }
+
+    assert!(parser.parse_from_memory("digraph G { a \0 }").is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn canonical_dot() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            rankdir = LR
+            subgraph cluster_1 { d; c -> d }
+            subgraph cluster_0 { label = "first"; b; a -> b [ weight = 2, color = red ] }
+            b -> c
+        }
+        "#,
+    )?;
+
+    let mut dot = Vec::new();
+    graph.to_canonical_dot(&mut dot)?;
+    let dot = String::from_utf8(dot).unwrap();
+    assert_eq!(
+        dot,
+        "digraph G {\n\
+         \tgraph [rankdir=\"LR\"];\n\
+         \tsubgraph cluster_0 {\n\
+         \t\tgraph [label=\"first\"];\n\
+         \t\ta;\n\
+         \t\tb;\n\
+         \t\ta -> b [color=\"red\", weight=\"2\"];\n\
+         \t}\n\
+         \tsubgraph cluster_1 {\n\
+         \t\tc;\n\
+         \t\td;\n\
+         \t\tc -> d;\n\
+         \t}\n\
+         \tb -> c;\n\
+         }\n"
+    );
+
+    // writing what was parsed back from the output gives the same output
+    let mut again = Vec::new();
+    parser::parse_from_memory(&dot)?.to_canonical_dot(&mut again)?;
+    assert_eq!(dot.as_bytes(), again);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn json() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            rankdir = LR
+            subgraph cluster_0 { label = "first"; a -> b }
+            b -> c [ color = red ]
+        }
+        "#,
+    )?;
+
+    let mut json = Vec::new();
+    graph.to_json(&mut json)?;
+    let json = String::from_utf8(json).unwrap();
+    assert_eq!(
+        json,
+        r#"{
+  "name": "G",
+  "directed": true,
+  "strict": false,
+  "rankdir": "LR",
+  "_subgraph_cnt": 1,
+  "objects": [
+    {
+      "_gvid": 0,
+      "name": "cluster_0",
+      "label": "first",
+      "nodes": [1, 2],
+      "edges": [0]
+    },
+    {
+      "_gvid": 1,
+      "name": "a"
+    },
+    {
+      "_gvid": 2,
+      "name": "b"
+    },
+    {
+      "_gvid": 3,
+      "name": "c"
+    }
+  ],
+  "edges": [
+    {
+      "_gvid": 0,
+      "tail": 1,
+      "head": 2
+    },
+    {
+      "_gvid": 1,
+      "tail": 2,
+      "head": 3,
+      "color": "red"
+    }
+  ]
+}
+"#
+    );
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn gexf() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a [ label = "A & B", pos = "1,2!" ]
+            a -> b [ weight = 2 ]
+        }
+        "#,
+    )?;
+
+    let mut gexf = Vec::new();
+    graph.to_gexf(&mut gexf)?;
+    let gexf = String::from_utf8(gexf).unwrap();
+    assert_eq!(
+        gexf,
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<gexf xmlns="http://gexf.net/1.3" xmlns:viz="http://gexf.net/1.3/viz" version="1.3">
+  <graph mode="static" defaultedgetype="directed">
+    <attributes class="node">
+      <attribute id="0" title="label" type="string"/>
+      <attribute id="1" title="pos" type="string"/>
+    </attributes>
+    <attributes class="edge">
+      <attribute id="0" title="weight" type="string"/>
+    </attributes>
+    <nodes>
+      <node id="a" label="A &amp; B">
+        <attvalues>
+          <attvalue for="0" value="A &amp; B"/>
+          <attvalue for="1" value="1,2!"/>
+        </attvalues>
+        <viz:position x="1" y="2" z="0"/>
+      </node>
+      <node id="b" label="b"/>
+    </nodes>
+    <edges>
+      <edge id="0" source="a" target="b" weight="2">
+        <attvalues>
+          <attvalue for="0" value="2"/>
+        </attvalues>
+      </edge>
+    </edges>
+  </graph>
+</gexf>
+"#
+    );
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn tgf() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            subgraph cluster_0 { a -> b [ label = "first edge" ] }
+            b -> c [ color = red ]
+        }
+        "#,
+    )?;
+
+    let mut tgf = Vec::new();
+    graph.to_tgf(&mut tgf)?;
+    let tgf = String::from_utf8(tgf).unwrap();
+    assert_eq!(tgf, "1 a\n2 b\n3 c\n#\n1 2 first edge\n2 3\n");
+
+    // nodes are read back named after their numbers, labeled by their ids
+    let parsed = parser::parse_tgf(&tgf)?;
+    assert_eq!(parsed.nodes().len(), 3);
+    let node = parsed.search_node(&String::from("1")).unwrap();
+    assert_eq!(node.attrs().get("label").unwrap().value(), "a");
+    let edge = parsed.search_edge(&EdgeId::new(String::from("1"), None, String::from("2"), None));
+    assert_eq!(edge.unwrap().attrs().get("label").unwrap().value(), "first edge");
+    let mut again = Vec::new();
+    parsed.to_tgf(&mut again)?;
+    assert_eq!(String::from_utf8(again).unwrap(), tgf);
+
+    let unnamed = parser::parse_tgf("1\n2 two\n#\n1 2\n")?;
+    assert_eq!(unnamed.nodes().len(), 2);
+    assert!(unnamed.search_node(&String::from("1")).unwrap().attrs().is_empty());
+
+    assert!(parser::parse_tgf("1 a\n#\n1 2\n").is_err());
+    assert!(parser::parse_tgf("1 a\n1 b\n#\n").is_err());
+    assert!(parser::parse_tgf("1 a\n#\n1\n").is_err());
+
+    // labels may be shared, and edges repeated as in multigraphs
+    let tgf = "1 a\n2   a\n#\n1   2\t  spaced label\n1 2\n1 2 again\n";
+    let parsed = parser::parse_tgf(tgf)?;
+    assert_eq!(parsed.nodes().len(), 2);
+    assert_eq!(parsed.edges().len(), 1);
+    let edge = parsed.search_edge(&EdgeId::new(String::from("1"), None, String::from("2"), None));
+    assert_eq!(edge.unwrap().attrs().get("label").unwrap().value(), "again");
+
+    // edges differing only by ports are read back as one
+    let graph = parser::parse_from_memory("digraph G { a:n -> b; a:s -> b }")?;
+    let mut tgf = Vec::new();
+    graph.to_tgf(&mut tgf)?;
+    assert_eq!(parser::parse_tgf(str::from_utf8(&tgf).unwrap())?.edges().len(), 1);
+
+    // labels with line breaks are not written
+    let graph = parser::parse_from_memory("digraph G { a [ label = \"x\ny\" ] }")?;
+    assert!(graph.to_tgf(&mut Vec::new()).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn csv() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a [ label = "x, \"y\"", shape = box ]
+            a -> b [ weight = 2 ]
+        }
+        "#,
+    )?;
+
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    graph.to_csv(&mut nodes, &mut edges, &["label", "weight"])?;
+    let nodes = String::from_utf8(nodes).unwrap();
+    let edges = String::from_utf8(edges).unwrap();
+    assert_eq!(nodes, "id,label,weight\na,\"x, \"\"y\"\"\",\nb,,\n");
+    assert_eq!(edges, "from,to,tailport,headport,label,weight\na,b,,,,2\n");
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn edge_list() -> Result<(), DotGraphError> {
+    let id = |id: &str| String::from(id);
+    let attr = |graph: &Graph, from: &str, to: &str, key: &str| {
+        let edge = graph.search_edge(&EdgeId::new(id(from), None, id(to), None)).unwrap();
+        edge.attrs().get(key).map(|attr| attr.value().clone())
+    };
+
+    let contents = "src,dst,label,weight\n\
+                    # a comment\n\
+                    a,b,\"x, \"\"y\"\"\",2\n\
+                    b,c,,3\n\
+                    \n\
+                    a,b,,5\n";
+    let options = parser::EdgeListOptions::new().header(true);
+    let graph = parser::parse_edge_list(contents.as_bytes(), &options)?;
+    assert_eq!(graph.nodes().len(), 3);
+    assert_eq!(graph.edges().len(), 2);
+    assert_eq!(attr(&graph, "a", "b", "label"), Some(id("x, \"y\"")));
+    assert_eq!(attr(&graph, "a", "b", "weight"), Some(id("5")));
+    assert_eq!(attr(&graph, "b", "c", "label"), None);
+
+    // what to_csv writes is read back
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    graph.to_csv(&mut nodes, &mut edges, &["label", "weight"])?;
+    let options = parser::EdgeListOptions::new().header(true);
+    let parsed = parser::parse_edge_list(&edges[..], &options)?;
+    assert_eq!(parsed.edges(), graph.edges());
+    assert_eq!(attr(&parsed, "a", "b", "label"), Some(id("x, \"y\"")));
+
+    let options = parser::EdgeListOptions::new().delimiter('\t').columns(vec![id("color")]);
+    let graph = parser::parse_edge_list("a\tb\tred\n".as_bytes(), &options)?;
+    assert_eq!(attr(&graph, "a", "b", "color"), Some(id("red")));
+
+    // quoted fields keep line breaks and surrounding whitespace, unquoted ones are trimmed
+    let contents = "a, b ,\"x\ny\",\" z \"\n";
+    let options = parser::EdgeListOptions::new().columns(vec![id("label"), id("comment")]);
+    let graph = parser::parse_edge_list(contents.as_bytes(), &options)?;
+    assert_eq!(attr(&graph, "a", "b", "label"), Some(id("x\ny")));
+    assert_eq!(attr(&graph, "a", "b", "comment"), Some(id(" z ")));
+
+    // ports are read back as ports
+    let contents = "from,to,tailport,headport,label\na,b,n,s,x\n";
+    let options = parser::EdgeListOptions::new().header(true);
+    let graph = parser::parse_edge_list(contents.as_bytes(), &options)?;
+    let edge_id = EdgeId::new(id("a"), Some(id("n")), id("b"), Some(id("s")));
+    let edge = graph.search_edge(&edge_id).unwrap();
+    assert!(edge.attrs().get("tailport").is_none());
+    assert_eq!(edge.attrs().get("label").map(|attr| attr.value().clone()), Some(id("x")));
+
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    graph.to_csv(&mut nodes, &mut edges, &["label"])?;
+    let parsed = parser::parse_edge_list(&edges[..], &options)?;
+    assert_eq!(parsed.edges(), graph.edges());
+
+    assert!(parser::parse_edge_list("a\n".as_bytes(), &parser::EdgeListOptions::new()).is_err());
+    assert!(
+        parser::parse_edge_list("a,b,\"x\n".as_bytes(), &parser::EdgeListOptions::new()).is_err()
+    );
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "dot-structures")]
+fn dot_structures() -> Result<(), DotGraphError> {
+    use dot_structures as ast;
+
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            rankdir = LR
+            subgraph cluster_0 { label = "first"; a -> b [ color = red ] }
+            b -> c
+        }
+        "#,
+    )?;
+
+    let converted = ast::Graph::from(&graph);
+    let back = Graph::try_from(&converted)?;
+    assert_eq!(back.subgraphs(), graph.subgraphs());
+    assert_eq!(back.nodes(), graph.nodes());
+    assert_eq!(back.edges(), graph.edges());
+    assert_eq!(back.collect_nodes(&String::from("cluster_0"))?.len(), 2);
+
+    let plain = |id: &str| ast::Id::Plain(String::from(id));
+    let node = |id: &str| ast::Vertex::N(ast::NodeId(plain(id), None));
+    let built = ast::Graph::DiGraph {
+        id: plain("H"),
+        strict: false,
+        stmts: vec![
+            ast::Stmt::GAttribute(ast::GraphAttributes::Node(vec![ast::Attribute(
+                plain("shape"),
+                plain("box"),
+            )])),
+            ast::Stmt::Edge(ast::Edge {
+                ty: ast::EdgeTy::Chain(vec![
+                    node("x"),
+                    ast::Vertex::S(ast::Subgraph {
+                        id: ast::Id::Anonymous(String::new()),
+                        stmts: vec![
+                            ast::Stmt::Node(ast::Node {
+                                id: ast::NodeId(plain("y"), None),
+                                attributes: vec![],
+                            }),
+                            ast::Stmt::Node(ast::Node {
+                                id: ast::NodeId(plain("z"), None),
+                                attributes: vec![],
+                            }),
+                        ],
+                    }),
+                ]),
+                attributes: vec![],
+            }),
+        ],
+    };
+    let graph = Graph::try_from(&built)?;
+    assert_eq!(graph.edges().len(), 2);
+    let shape = graph.search_node(&String::from("z")).unwrap().attrs().get("shape").unwrap();
+    assert_eq!(shape.value(), "box");
+
+    let undirected = ast::Graph::Graph { id: plain("U"), strict: false, stmts: vec![] };
+    assert!(Graph::try_from(&undirected).is_err());
+
+    // subgraphs may be reopened under the same parent, but not nested within themselves
+    // or under another parent
+    let subgraph = |id: &str, stmts| ast::Stmt::Subgraph(ast::Subgraph { id: plain(id), stmts });
+    let node = |id: &str| {
+        ast::Stmt::Node(ast::Node { id: ast::NodeId(plain(id), None), attributes: vec![] })
+    };
+    let digraph = |stmts| ast::Graph::DiGraph { id: plain("G"), strict: false, stmts };
+    let reopened = digraph(vec![subgraph("s", vec![node("a")]), subgraph("s", vec![node("b")])]);
+    assert_eq!(Graph::try_from(&reopened)?.collect_nodes(&String::from("s"))?.len(), 2);
+    assert!(matches!(
+        Graph::try_from(&digraph(vec![subgraph("G", vec![node("a")])])),
+        Err(DotGraphError::CyclicSubGraph(..))
+    ));
+    assert!(matches!(
+        Graph::try_from(&digraph(vec![subgraph(
+            "s",
+            vec![subgraph("t", vec![subgraph("s", vec![])])]
+        )])),
+        Err(DotGraphError::CyclicSubGraph(..))
+    ));
+    assert!(matches!(
+        Graph::try_from(&digraph(vec![
+            subgraph("s", vec![subgraph("u", vec![node("a")])]),
+            subgraph("t", vec![subgraph("u", vec![node("b")])]),
+        ])),
+        Err(DotGraphError::DuplicateSubGraph(..))
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn dot_write_options() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            subgraph cluster_0 { label = "first"; a [ shape = box ] }
+            a -> b [ color = red ]
+        }
+        "#,
+    )?;
+    let write = |options: &DotWriteOptions| {
+        let mut dot = Vec::new();
+        graph.to_dot_with_options(options, &mut dot).unwrap();
+        String::from_utf8(dot).unwrap()
+    };
+
+    let mut dot = Vec::new();
+    graph.to_dot(&mut dot)?;
+    assert_eq!(write(&DotWriteOptions::pretty()), String::from_utf8(dot).unwrap());
+
+    let compact = write(&DotWriteOptions::compact());
+    assert!(
+        compact.contains("subgraph cluster_0 {\ngraph [label=\"first\"]\na [shape=\"box\"];\n}\n")
+    );
+    assert!(compact.contains("\nb;\n"));
+    assert!(compact.contains("\na -> b [color=\"red\"]\n"));
+
+    let spaced = write(&DotWriteOptions::compact().indent("  "));
+    assert!(spaced.contains("\n    a [shape=\"box\"];\n"));
+
+    let pretty = write(&DotWriteOptions::pretty().indent("  "));
+    assert!(pretty.contains("\n    a [\n      shape=\"box\"\n    ];\n"));
+
+    // what is written compactly is parsed back the same
+    let parsed = parser::parse_from_memory(&compact)?;
+    assert_eq!(parsed.nodes(), graph.nodes());
+    assert_eq!(parsed.edges(), graph.edges());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn dot_write_styles() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            subgraph cluster_0 { label = "first"; a }
+            a -> b
+        }
+        "#,
+    )?;
+    let write = |options: &DotWriteOptions| {
+        let mut dot = Vec::new();
+        graph.to_dot_with_options(options, &mut dot).unwrap();
+        String::from_utf8(dot).unwrap()
+    };
+
+    let options = DotWriteOptions::compact().sorted(true).spaces(2);
+    assert_eq!(options, DotWriteOptions::compact().sorted(true).indent("  "));
+    assert_eq!(options.clone().tabs(), DotWriteOptions::compact().sorted(true).indent("\t"));
+
+    let next_line = write(&options.clone().braces(BraceStyle::NextLine));
+    assert_eq!(
+        next_line,
+        "digraph G\n{\n  subgraph cluster_0\n  {\n    graph [label=\"first\"]\n    a;\n  }\n  b;\n  a -> b\n}\n"
+    );
+
+    let always = write(&options.clone().semicolons(Semicolons::Always));
+    assert_eq!(
+        always,
+        "digraph G {\n  subgraph cluster_0 {\n    graph [label=\"first\"];\n    a;\n  }\n  b;\n  a -> b;\n}\n"
+    );
+
+    let never = write(&options.semicolons(Semicolons::Never));
+    assert_eq!(
+        never,
+        "digraph G {\n  subgraph cluster_0 {\n    graph [label=\"first\"]\n    a\n  }\n  b\n  a -> b\n}\n"
+    );
+
+    // every style is parsed back the same
+    for dot in [next_line, always, never] {
+        let parsed = parser::parse_from_memory(&dot)?;
+        assert_eq!(parsed.nodes(), graph.nodes());
+        assert_eq!(parsed.edges(), graph.edges());
+    }
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn dot_sorted() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            subgraph cluster_1 { d; c -> d }
+            subgraph cluster_0 { b [ shape = box, color = red ]; a -> b }
+            b -> c
+        }
+        "#,
+    )?;
+
+    let mut dot = Vec::new();
+    graph.to_dot_sorted(&mut dot)?;
+    let dot = String::from_utf8(dot).unwrap();
+    assert_eq!(
+        dot,
+        "digraph G {\n\
+         \tsubgraph cluster_0 {\n\
+         \t\ta [\n\
+         \t\t];\n\
+         \t\tb [\n\
+         \t\t\tcolor=\"red\"\n\
+         \t\t\tshape=\"box\"\n\
+         \t\t];\n\
+         \t\ta -> b [\n\
+         \t\t]\n\
+         \t}\n\
+         \tsubgraph cluster_1 {\n\
+         \t\tc [\n\
+         \t\t];\n\
+         \t\td [\n\
+         \t\t];\n\
+         \t\tc -> d [\n\
+         \t\t]\n\
+         \t}\n\
+         \tb -> c [\n\
+         \t]\n\
+         }\n"
+    );
+
+    // parsing what was written and writing it again gives the same output
+    let mut again = Vec::new();
+    parser::parse_from_memory(&dot)?.to_dot_sorted(&mut again)?;
+    assert_eq!(dot.as_bytes(), again);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn dot_attr_filter() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            bb = "0,0,100,100";
+            subgraph cluster_0 { label = "secret"; a [ pos = "1,2", label = "secret" ] }
+            a -> b [ pos = "e,1,2", color = red ]
+        }
+        "#,
+    )?;
+
+    let mut dot = Vec::new();
+    graph.to_dot_with(&mut dot, |element, attr| match attr.key().as_str() {
+        "pos" | "bb" => AttrAction::Skip,
+        "label" if matches!(element, DotElement::Node(_)) => {
+            AttrAction::Rewrite(Attr::new("label".to_string(), "***".to_string(), false))
+        }
+        _ => AttrAction::Keep,
+    })?;
+    let dot = String::from_utf8(dot).unwrap();
+
+    let filtered = parser::parse_from_memory(&dot)?;
+    assert!(filtered.search_subgraph(filtered.id()).unwrap().attrs().is_empty());
+    let node = filtered.search_node(&"a".to_string()).unwrap();
+    assert_eq!(node.attrs().len(), 1);
+    assert_eq!(node.attrs().get("label").unwrap().value(), "***");
+    let edge = filtered.search_edge(&EdgeId::new("a".to_string(), None, "b".to_string(), None));
+    assert_eq!(edge.unwrap().attrs().len(), 1);
+    // subgraph labels are kept
+    assert!(dot.contains("\"secret\""));
+
+    // the graph itself is untouched
+    assert!(graph.search_node(&"a".to_string()).unwrap().attrs().get("pos").is_some());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn display() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            subgraph cluster_0 { a -> b }
+            b [ shape = box ]
+            b -> c [ color = red ]
+        }
+        "#,
+    )?;
+
+    let mut dot = Vec::new();
+    graph.to_dot(&mut dot)?;
+    assert_eq!(graph.to_string(), String::from_utf8(dot).unwrap());
+    assert_eq!(format!("{graph:#}"), "digraph G with 1 subgraph, 3 nodes, and 2 edges");
+
+    let node = graph.search_node(&String::from("b")).unwrap();
+    assert_eq!(node.to_string(), "b [\n\tshape=\"box\"\n];\n");
+    assert_eq!(format!("{node:#}"), "node b with 1 attr");
+
+    let edge = graph.search_edge(&EdgeId::new(String::from("b"), None, String::from("c"), None));
+    let edge = edge.unwrap();
+    assert_eq!(edge.to_string(), "b -> c [\n\tcolor=\"red\"\n]\n");
+    assert_eq!(format!("{edge:#}"), "edge b -> c with 1 attr");
+
+    let subgraph = graph.search_subgraph(&String::from("cluster_0")).unwrap();
+    let dot = subgraph.to_string();
+    assert!(dot.starts_with("subgraph cluster_0 {\n"));
+    assert!(dot.contains("\n\ta;\n") && dot.contains("\n\tb;\n"));
+    assert!(dot.ends_with("\n\ta -> b\n}\n"));
+    assert_eq!(format!("{subgraph:#}"), "subgraph cluster_0 with 0 subgraphs, 2 nodes, and 1 edge");
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn from_str() -> Result<(), DotGraphError> {
+    let graph: Graph = "digraph G { a -> b }".parse()?;
+    assert_eq!(graph.nodes().len(), 2);
+    assert!("digraph G { a -> ".parse::<Graph>().is_err());
+
+    let path = TempPath::new("try_from.dot");
+    fs::write(&path, "digraph G { a -> b -> c }")?;
+    let graph = Graph::try_from(path.as_ref())?;
+    assert_eq!(graph.edges().len(), 2);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn root_attrs() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            rankdir = LR
+            graph [ ranksep = 2, bgcolor = "gray" ]
+            a -> b
+        }
+        "#,
+    )?;
+    let attrs = |graph: &Graph| {
+        let mut attrs: Vec<(String, String)> = graph
+            .attrs()
+            .into_iter()
+            .map(|attr| (attr.key().clone(), attr.value().clone()))
+            .collect();
+        attrs.sort_unstable();
+        attrs
+    };
+
+    let expected = vec![
+        (String::from("bgcolor"), String::from("gray")),
+        (String::from("rankdir"), String::from("LR")),
+        (String::from("ranksep"), String::from("2")),
+    ];
+    assert_eq!(attrs(&graph), expected);
+
+    // attributes of the graph are written back
+    let written = parser::parse_from_memory(&graph.to_string())?;
+    assert_eq!(attrs(&written), expected);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn subgraph_attrs() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            subgraph cluster_0 {
+                label = "first"
+                style = filled
+                color = lightgrey
+                subgraph cluster_1 { label = "nested"; a }
+                a -> b
+            }
+        }
+        "#,
+    )?;
+    let attrs = |graph: &Graph, id: &str| {
+        let subgraph = graph.search_subgraph(&String::from(id)).unwrap();
+        let mut attrs: Vec<(String, String)> = subgraph
+            .attrs()
+            .iter()
+            .map(|attr| (attr.key().clone(), attr.value().clone()))
+            .collect();
+        attrs.sort_unstable();
+        attrs
+    };
+
+    let written = parser::parse_from_memory(&graph.to_string())?;
+    for id in ["cluster_0", "cluster_1"] {
+        assert!(!attrs(&graph, id).is_empty());
+        assert_eq!(attrs(&written, id), attrs(&graph, id));
+    }
+    assert_eq!(
+        attrs(&graph, "cluster_0"),
+        vec![
+            (String::from("color"), String::from("lightgrey")),
+            (String::from("label"), String::from("first")),
+            (String::from("style"), String::from("filled")),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn dot_defaults() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            node [ shape = box ]
+            edge [ color = red ]
+            subgraph cluster_0 { x [ shape = circle ]; y [ shape = circle ]; x -> y }
+            a [ color = blue ]
+            b
+            a -> b
+            b -> x
+        }
+        "#,
+    )?;
+
+    let mut dot = Vec::new();
+    let options = DotWriteOptions::compact().sorted(true).factor_defaults(true);
+    graph.to_dot_with_options(&options, &mut dot)?;
+    let dot = String::from_utf8(dot).unwrap();
+    assert_eq!(
+        dot,
+        "digraph G {\n\
+         subgraph cluster_0 {\n\
+         node [shape=\"circle\"];\n\
+         x;\n\
+         y;\n\
+         x -> y [color=\"red\"]\n\
+         }\n\
+         node [shape=\"box\"];\n\
+         a [color=\"blue\"];\n\
+         b;\n\
+         edge [color=\"red\"];\n\
+         a -> b\n\
+         b -> x\n\
+         }\n"
+    );
+
+    // parsing what was written gives back the same attributes
+    let parsed = parser::parse_from_memory(&dot)?;
+    for node in graph.iter_nodes() {
+        assert_eq!(parsed.search_node(node.id()).unwrap().attrs(), node.attrs());
+    }
+
+    // defaults are not factored where edges would declare nodes outside of the subgraph
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            subgraph cluster_0 { x [ shape = box ]; y [ shape = box ]; x -> z }
+            z
+        }
+        "#,
+    )?;
+    let mut dot = Vec::new();
+    graph.to_dot_with_options(&options, &mut dot)?;
+    let dot = String::from_utf8(dot).unwrap();
+    assert!(!dot.contains("node ["));
+
+    // nor where edges written earlier, by a sibling or a child subgraph, declare its nodes
+    let mut graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            subgraph s2 { n [ shape = box ]; m [ shape = box ]; subgraph s3 { y -> m } }
+            subgraph s1 { x -> n }
+        }
+        "#,
+    )?;
+    graph.move_node(&String::from("m"), &String::from("s2"))?;
+    let mut dot = Vec::new();
+    graph.to_dot_with_options(&options, &mut dot)?;
+    let parsed = parser::parse_from_memory(&String::from_utf8(dot).unwrap())?;
+    for node in graph.iter_nodes() {
+        assert_eq!(parsed.search_node(node.id()).unwrap().attrs(), node.attrs());
+    }
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn dot_quoting() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            "node" -> "1st" -> "-1.5" -> "a b" -> "é_2"
+            "a b" [ label = "say \"hi\"\l", "my key" = "x" ]
+            "a b":"p 1":n -> "node"
+        }
+        "#,
+    )?;
+
+    let mut dot = Vec::new();
+    graph.to_dot_with_options(&DotWriteOptions::compact().sorted(true), &mut dot)?;
+    let dot = String::from_utf8(dot).unwrap();
+    assert!(dot.contains("\n\"node\" -> \"1st\"\n"));
+    assert!(dot.contains("\n\"1st\" -> -1.5\n"));
+    assert!(dot.contains("\n-1.5 -> \"a b\"\n"));
+    assert!(dot.contains("\n\"a b\" -> é_2\n"));
+    assert!(dot.contains("\n\"a b\":\"p 1\":n -> \"node\"\n"));
+    assert!(dot.contains("[label=\"say \\\"hi\\\"\\l\", \"my key\"=\"x\"]"));
+
+    // what was written is parsed back the same
+    let parsed = parser::parse_from_memory(&dot)?;
+    assert_eq!(parsed.nodes(), graph.nodes());
+    assert_eq!(parsed.edges(), graph.edges());
+    let id = String::from("a b");
+    assert_eq!(parsed.search_node(&id).unwrap().attrs(), graph.search_node(&id).unwrap().attrs());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn dot_writer() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            label = "top";
+            subgraph cluster_0 { subgraph cluster_1 { a [ shape = box ] } b }
+            a -> b [ color = red ]
+            b -> c
+            c -> d
+        }
+        "#,
+    )?;
+
+    // the whole graph is written as `to_dot_with_options` writes it
+    for options in [DotWriteOptions::pretty().sorted(true), DotWriteOptions::compact().sorted(true)]
+    {
+        let mut dot = Vec::new();
+        graph.to_dot_with_options(&options, &mut dot)?;
+
+        let mut writer = DotWriter::new(Vec::new(), options.clone());
+        writer.write_graph(&graph)?;
+        assert_eq!(writer.finish()?, dot);
+
+        let mut writer = DotWriter::new(Vec::new(), options).parallel(2);
+        writer.write_graph(&graph)?;
+        assert_eq!(writer.finish()?, dot);
+    }
+
+    // statements are written as they are visited, and open graphs are closed by `finish`
+    let mut ids: Vec<&NodeId> = graph.nodes().into_iter().collect();
+    ids.sort_unstable();
+    let nodes: Vec<&Node> = ids.into_iter().map(|id| graph.search_node(id).unwrap()).collect();
+    let mut writer = DotWriter::new(Vec::new(), DotWriteOptions::compact()).parallel(3);
+    assert!(writer.write_nodes(nodes.clone()).is_err());
+    writer.begin_graph("H", &HashSet::new())?;
+    writer.begin_graph(
+        "cluster_0",
+        &HashSet::from([Attr::new("label".to_string(), "x".to_string(), false)]),
+    )?;
+    assert_eq!(writer.depth(), 2);
+    writer.write_nodes(nodes)?;
+    writer.end_graph()?;
+    writer.write_edge(
+        graph.search_edge(&EdgeId::new("a".to_string(), None, "b".to_string(), None)).unwrap(),
+    )?;
+    let dot = String::from_utf8(writer.finish()?).unwrap();
+    assert_eq!(
+        dot,
+        "digraph H {\nsubgraph cluster_0 {\ngraph [label=\"x\"]\na [shape=\"box\"];\nb;\nc;\nd;\n}\n\
+         a -> b [color=\"red\"]\n}\n"
+    );
+
+    Ok(())
+}
+
+/// Reverses everything written to it once finished, to stand in for a compression format
+struct Reverse(Vec<u8>);
+
+impl Write for Reverse {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Encoder for Reverse {
+    type Inner = Vec<u8>;
+
+    fn finish(mut self) -> std::io::Result<Vec<u8>> {
+        self.0.reverse();
+        Ok(self.0)
+    }
+}
+
+#[test]
+#[serial]
+fn dot_encoded() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory("digraph G { a -> b [ color = red ] }")?;
+    let options = DotWriteOptions::compact();
+
+    let mut dot = Vec::new();
+    graph.to_dot_with_options(&options, &mut dot)?;
+    dot.reverse();
+    assert_eq!(graph.to_dot_encoded(&options, Reverse(Vec::new()))?, dot);
+
+    Ok(())
+}
+
+#[cfg(feature = "flate2")]
+#[test]
+#[serial]
+fn dot_gz() -> Result<(), DotGraphError> {
+    use std::io::Read;
+
+    let graph = parser::parse_from_memory("digraph G { a -> b [ color = red ] }")?;
+    let gz = graph.to_dot_gz(Vec::new())?;
+
+    let mut dot = String::new();
+    flate2::read::GzDecoder::new(&gz[..]).read_to_string(&mut dot)?;
+    assert_eq!(dot, graph.to_string());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn render() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory("digraph G { a -> b [ color = red ] }")?;
+
+    let mut svg = Vec::new();
+    graph.render(RenderFormat::Svg, &mut svg)?;
+    assert!(str::from_utf8(&svg).unwrap().contains("<svg"));
+
+    let mut svg = Vec::new();
+    graph.render_with(LayoutEngine::Neato, RenderFormat::Svg, &mut svg)?;
+    assert!(str::from_utf8(&svg).unwrap().contains("<svg"));
+
+    assert_eq!(LayoutEngine::Fdp.name(), "fdp");
+    assert_eq!(RenderFormat::Png.name(), "png");
+
+    Ok(())
+}
@@ -0,0 +1,522 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use graphviz_rs::prelude::*;
+
+use serial_test::serial;
+
+const CLUSTERED: &str = r#"
+digraph G {
+    subgraph cluster_0 {
+        a -> b
+    }
+    subgraph cluster_1 {
+        c
+    }
+    b -> c
+}
+"#;
+
+#[test]
+#[serial]
+fn add_subgraph_and_move_node() -> Result<(), DotGraphError> {
+    let mut graph = parser::parse_from_memory(CLUSTERED)?;
+
+    let parent = String::from("cluster_0");
+    let cluster = String::from("cluster_2");
+    graph.add_subgraph(&parent, cluster.clone())?;
+    assert!(graph.collect_subgraphs(&parent)?.contains(&&cluster));
+    assert!(graph.add_subgraph(&parent, cluster.clone()).is_err());
+
+    let node = String::from("c");
+    graph.move_node(&node, &cluster)?;
+    assert_eq!(graph.collect_nodes(&cluster)?, vec![&node]);
+    assert!(graph.collect_nodes(&String::from("cluster_1"))?.is_empty());
+    assert_eq!(graph.collect_nodes(&parent)?.len(), 3);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn reparent_subgraph() -> Result<(), DotGraphError> {
+    let mut graph = parser::parse_from_memory(CLUSTERED)?;
+
+    let root = String::from("G");
+    let outer = String::from("cluster_0");
+    let inner = String::from("cluster_1");
+    graph.reparent_subgraph(&inner, &outer)?;
+    assert_eq!(graph.collect_subgraphs(&outer)?, vec![&inner]);
+    assert!(!graph.collect_subgraphs(&root)?.contains(&&inner));
+    assert_eq!(graph.collect_nodes(&outer)?.len(), 3);
+
+    assert!(matches!(
+        graph.reparent_subgraph(&outer, &inner),
+        Err(DotGraphError::CyclicSubGraph(..))
+    ));
+    assert!(matches!(graph.reparent_subgraph(&root, &outer), Err(DotGraphError::RootSubGraph(..))));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn parent_subgraph_and_path() -> Result<(), DotGraphError> {
+    let mut graph = parser::parse_from_memory(CLUSTERED)?;
+
+    let root = String::from("G");
+    let cluster = String::from("cluster_0");
+    let a = String::from("a");
+    assert_eq!(graph.parent_subgraph(&a), Some(&cluster));
+    assert_eq!(graph.subgraph_parent(&cluster), Some(&root));
+    assert_eq!(graph.subgraph_parent(&root), None);
+    assert_eq!(graph.parent_subgraph(&String::from("e")), None);
+
+    graph.move_node(&a, &root)?;
+    assert_eq!(graph.parent_subgraph(&a), Some(&root));
+    graph.reparent_subgraph(&cluster, &String::from("cluster_1"))?;
+    assert_eq!(graph.subgraph_parent(&cluster), Some(&String::from("cluster_1")));
+    assert_eq!(graph.subgraph_path(&String::from("b")), vec!["G", "cluster_1", "cluster_0"]);
+    assert_eq!(graph.subgraph_path(&a), vec!["G"]);
+    assert!(graph.subgraph_path(&String::from("e")).is_empty());
+
+    // the index follows nodes and subgraphs added or removed after it was built
+    let d = String::from("d");
+    graph.add_subgraph(&cluster, String::from("cluster_2"))?;
+    graph.add_node(Node::new(d.clone(), HashSet::new()), &String::from("cluster_2"))?;
+    assert_eq!(graph.subgraph_path(&d), vec!["G", "cluster_1", "cluster_0", "cluster_2"]);
+    graph.remove_node(&d)?;
+    assert_eq!(graph.parent_subgraph(&d), None);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn rename_node() -> Result<(), DotGraphError> {
+    let mut graph = parser::parse_from_memory(CLUSTERED)?;
+
+    let b = String::from("b");
+    let e = String::from("e");
+    graph.rename_node(&b, e.clone())?;
+    assert!(graph.search_node(&b).is_none());
+    assert!(graph.search_node(&e).is_some());
+    assert_eq!(graph.froms(&e)?, [&String::from("a")].into());
+    assert_eq!(graph.tos(&e)?, [&String::from("c")].into());
+    assert!(graph.collect_nodes(&String::from("cluster_0"))?.contains(&&e));
+    assert!(graph.edges().iter().all(|id| id.from() != &b && id.to() != &b));
+
+    assert!(matches!(
+        graph.rename_node(&e, String::from("a")),
+        Err(DotGraphError::DuplicateNode(..))
+    ));
+    graph.rename_node(&e, e.clone())?;
+    assert_eq!(graph.froms(&e)?, [&String::from("a")].into());
+    assert!(graph.rename_node(&b, b.clone()).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn relabel() -> Result<(), DotGraphError> {
+    let mut graph = parser::parse_from_memory(CLUSTERED)?;
+
+    graph.relabel(|id| id.to_uppercase())?;
+    let mut nodes: Vec<&NodeId> = graph.nodes().into_iter().collect();
+    nodes.sort();
+    assert_eq!(nodes, vec!["A", "B", "C"]);
+    assert_eq!(graph.tos(&String::from("B"))?, [&String::from("C")].into());
+    assert!(graph.collect_nodes(&String::from("cluster_1"))?.contains(&&String::from("C")));
+    assert!(graph.collect_edges(&String::from("cluster_0"))?.iter().all(|id| id.from() == "A"));
+
+    assert!(matches!(
+        graph.relabel(|id| if id == "C" { String::from("A") } else { id.clone() }),
+        Err(DotGraphError::DuplicateNode(..))
+    ));
+    assert!(graph.search_node(&String::from("C")).is_some());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn rename_subgraph_and_graph() -> Result<(), DotGraphError> {
+    let mut graph = parser::parse_from_memory(CLUSTERED)?;
+
+    let old = String::from("cluster_0");
+    let new = String::from("cluster_a");
+    graph.rename_subgraph(&old, new.clone())?;
+    assert!(graph.search_subgraph(&old).is_none());
+    assert_eq!(graph.collect_nodes(&new)?.len(), 2);
+    assert!(graph.collect_subgraphs(&String::from("G"))?.contains(&&new));
+
+    let id = String::from("H");
+    graph.set_id(id.clone())?;
+    assert_eq!(graph.id(), &id);
+    assert_eq!(graph.collect_nodes(&id)?.len(), 3);
+    assert!(graph.set_id(new).is_err());
+
+    let mut dot = Vec::new();
+    graph.to_dot(&mut dot).expect("to_dot should succeed");
+    parser::parse_from_memory(std::str::from_utf8(&dot).unwrap())?;
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn transaction() -> Result<(), DotGraphError> {
+    let mut graph = parser::parse_from_memory(CLUSTERED)?;
+
+    let a = String::from("a");
+    let b = String::from("b");
+    let c = String::from("c");
+    let root = String::from("G");
+
+    // delete b and reconnect its neighbors
+    graph.transaction(|tx| {
+        tx.remove_node(&b)?;
+        tx.add_edge(Edge::new(EdgeId::new(a.clone(), None, c.clone(), None), HashSet::new()), &root)
+    })?;
+    assert!(graph.search_node(&b).is_none());
+    assert_eq!(graph.tos(&a)?, [&c].into());
+    assert_eq!(graph.froms(&c)?, [&a].into());
+
+    // a failing transaction leaves the graph untouched
+    let result = graph.transaction(|tx| {
+        tx.remove_node(&a)?;
+        tx.add_edge(Edge::new(EdgeId::new(a.clone(), None, c.clone(), None), HashSet::new()), &root)
+    });
+    assert!(matches!(result, Err(DotGraphError::NoSuchNode(..))));
+    assert!(graph.search_node(&a).is_some());
+    assert_eq!(graph.tos(&a)?, [&c].into());
+
+    // rolling back restores subgraphs, parents, and payloads
+    let cluster = String::from("cluster_2");
+    graph.set_node_payload(&a, 42_u32)?;
+    let result = graph.transaction(|tx| {
+        tx.add_subgraph(&root, cluster.clone())?;
+        tx.move_node(&c, &cluster)?;
+        tx.remove_node(&a)?;
+        tx.remove_node(&a)
+    });
+    assert!(result.is_err());
+    assert!(graph.search_subgraph(&cluster).is_none());
+    assert_eq!(graph.parent_subgraph(&c), Some(&String::from("cluster_1")));
+    assert_eq!(graph.parent_subgraph(&a), Some(&String::from("cluster_0")));
+    assert_eq!(graph.node_payload::<u32>(&a), Some(&42));
+    assert_eq!(graph.froms(&c)?, [&a].into());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn payloads() -> Result<(), DotGraphError> {
+    let mut graph = parser::parse_from_memory(CLUSTERED)?;
+
+    let a = String::from("a");
+    let b = String::from("b");
+    let e = String::from("e");
+    let ab = EdgeId::new(a.clone(), None, b.clone(), None);
+    graph.set_node_payload(&a, 42_u32)?;
+    graph.set_node_payload(&b, String::from("element"))?;
+    graph.set_edge_payload(&ab, vec![1.0_f64])?;
+    assert!(graph.set_node_payload(&e, ()).is_err());
+
+    assert_eq!(graph.node_payload::<u32>(&a), Some(&42));
+    assert_eq!(graph.node_payload::<String>(&a), None);
+    assert_eq!(graph.edge_payload::<Vec<f64>>(&ab), Some(&vec![1.0]));
+
+    let filtered = graph.filter(&[&a]);
+    assert_eq!(filtered.node_payload::<u32>(&a), Some(&42));
+    assert!(filtered.node_payload::<String>(&b).is_none());
+
+    graph.rename_node(&b, e.clone())?;
+    assert_eq!(graph.node_payload::<String>(&e).map(String::as_str), Some("element"));
+    let ae = EdgeId::new(a.clone(), None, e.clone(), None);
+    assert!(graph.edge_payload::<Vec<f64>>(&ae).is_some());
+
+    graph.remove_node(&a)?;
+    graph.add_node(Node::new(a.clone(), HashSet::new()), &String::from("G"))?;
+    assert!(graph.node_payload::<u32>(&a).is_none());
+
+    // payloads leave with their nodes on collapse, and come back on expand
+    let state = graph.collapse_subgraph(&String::from("cluster_0"))?;
+    graph.add_node(Node::new(e.clone(), HashSet::new()), &String::from("G"))?;
+    assert!(graph.node_payload::<String>(&e).is_none());
+    graph.remove_node(&e)?;
+    graph.expand(state)?;
+    assert_eq!(graph.node_payload::<String>(&e).map(String::as_str), Some("element"));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn subscribe() -> Result<(), DotGraphError> {
+    let mut graph = parser::parse_from_memory(CLUSTERED)?;
+
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    let log = changes.clone();
+    graph.subscribe(move |change| log.lock().unwrap().push(change.clone()));
+
+    let a = String::from("a");
+    let b = String::from("b");
+    let d = String::from("d");
+    let root = String::from("G");
+    let attrs = HashSet::from([Attr::new(String::from("color"), String::from("red"), false)]);
+    graph.add_node(Node::new(d.clone(), attrs), &root)?;
+    graph.map_node_attrs(|_, attr| Some(attr));
+    graph.map_node_attrs(|_, attr| {
+        Attr::new(attr.key().clone(), String::from("blue"), false).into()
+    });
+    graph.remove_edge(&EdgeId::new(a.clone(), None, b.clone(), None))?;
+    graph.remove_node(&b)?;
+
+    let expected = vec![
+        GraphChange::NodeAdded(d.clone(), root.clone()),
+        GraphChange::NodeAttrChanged(d.clone()),
+        GraphChange::EdgeRemoved(EdgeId::new(a.clone(), None, b.clone(), None)),
+        GraphChange::EdgeRemoved(EdgeId::new(b.clone(), None, String::from("c"), None)),
+        GraphChange::NodeRemoved(b.clone()),
+    ];
+    assert_eq!(std::mem::take(&mut *changes.lock().unwrap()), expected);
+
+    // changes in a transaction are delivered only once it commits
+    let result = graph.transaction(|tx| {
+        tx.remove_node(&a)?;
+        tx.remove_node(&a)
+    });
+    assert!(result.is_err());
+    assert!(changes.lock().unwrap().is_empty());
+
+    graph.transaction(|tx| tx.move_node(&a, &String::from("cluster_1")))?;
+    assert_eq!(
+        *changes.lock().unwrap(),
+        vec![GraphChange::NodeMoved(a.clone(), String::from("cluster_1"))]
+    );
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn ports() -> Result<(), DotGraphError> {
+    let mut graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            r [shape=record, label="<f0> left|{<f1> mid\ dle|<f2> right}"]
+            h [label=<<table><tr><td port="in">in</td><td PORT='out'>out</td></tr></table>>]
+            p
+        }
+        "#,
+    )?;
+
+    let names = |id: &str| -> Vec<(String, PortKind)> {
+        let node = graph.search_node(&String::from(id)).unwrap();
+        node.ports().iter().map(|port| (port.name().clone(), port.kind())).collect()
+    };
+    assert_eq!(
+        names("r"),
+        vec![
+            (String::from("f0"), PortKind::Record),
+            (String::from("f1"), PortKind::Record),
+            (String::from("f2"), PortKind::Record),
+        ]
+    );
+    assert_eq!(
+        names("h"),
+        vec![(String::from("in"), PortKind::Html), (String::from("out"), PortKind::Html)]
+    );
+    assert!(names("p").is_empty());
+
+    let root = String::from("G");
+    let edge = |tailport: &str, headport: &str| {
+        let id = EdgeId::new(
+            String::from("r"),
+            Some(String::from(tailport)),
+            String::from("h"),
+            Some(String::from(headport)),
+        );
+        Edge::new(id, HashSet::new())
+    };
+    graph.add_edge(edge("f2:e", "in"), &root)?;
+    graph.add_edge(edge("s", "out:n"), &root)?;
+    assert!(matches!(graph.add_edge(edge("f3", "in"), &root), Err(DotGraphError::NoSuchPort(..))));
+    assert!(matches!(
+        graph.add_edge(edge("f0", "in:x"), &root),
+        Err(DotGraphError::NoSuchPort(..))
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn map_attrs() -> Result<(), DotGraphError> {
+    let mut graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a [label="first", pos="0,0"]
+            b [pos="1,1"]
+            a -> b [label="edge", pos="0,0 1,1"]
+        }
+        "#,
+    )?;
+
+    graph.map_node_attrs(|id, attr| match attr.key().as_str() {
+        "pos" => None,
+        "label" => Some(Attr::new(attr.key().clone(), format!("{id}: {}", attr.value()), false)),
+        _ => Some(attr),
+    });
+    graph.map_edge_attrs(|_, attr| (attr.key() != "pos").then_some(attr));
+
+    let a = graph.search_node(&String::from("a")).unwrap();
+    assert_eq!(a.attrs().len(), 1);
+    assert_eq!(a.attrs().get("label").unwrap().value(), "a: first");
+    assert!(graph.search_node(&String::from("b")).unwrap().attrs().is_empty());
+
+    let id = EdgeId::new(String::from("a"), None, String::from("b"), None);
+    let edge = graph.search_edge(&id).unwrap();
+    assert_eq!(edge.attrs().len(), 1);
+    assert!(edge.attrs().contains("label"));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn reverse() -> Result<(), DotGraphError> {
+    let mut graph = parser::parse_from_memory("digraph G { a:p -> b; b -> c:q }")?;
+    graph.reverse();
+
+    let a = String::from("a");
+    let b = String::from("b");
+    let c = String::from("c");
+    assert_eq!(graph.tos(&b)?, [&a].into());
+    assert_eq!(graph.froms(&b)?, [&c].into());
+    assert_eq!(graph.topsort()?, vec![&c, &b, &a]);
+
+    let id = EdgeId::new(c.clone(), Some(String::from("q")), b.clone(), None);
+    assert!(graph.search_edge(&id).is_some());
+    assert!(graph.collect_edges(&String::from("G"))?.contains(&&id));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn collapse_subgraph() -> Result<(), DotGraphError> {
+    let mut graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            subgraph cluster_0 {
+                label="inner"
+                a -> b
+                subgraph cluster_1 {
+                    c
+                }
+                b -> c
+            }
+            x -> a
+            x -> c
+            b -> y
+        }
+        "#,
+    )?;
+
+    let cluster = String::from("cluster_0");
+    let state = graph.collapse_subgraph(&cluster)?;
+    let collapsed = state.node().clone();
+    assert_eq!(collapsed, cluster);
+    assert!(graph.search_subgraph(&cluster).is_none());
+    assert!(graph.search_subgraph(&String::from("cluster_1")).is_none());
+
+    let mut nodes: Vec<&NodeId> = graph.nodes().into_iter().collect();
+    nodes.sort();
+    assert_eq!(nodes, vec!["cluster_0", "x", "y"]);
+
+    let x = String::from("x");
+    let incoming = graph.search_edge(&EdgeId::new(x.clone(), None, collapsed.clone(), None));
+    assert_eq!(incoming.unwrap().attrs().get("count").unwrap().value(), "2");
+    assert_eq!(graph.tos(&collapsed)?, [&String::from("y")].into());
+
+    let node = graph.search_node(&collapsed).unwrap();
+    assert_eq!(node.attrs().get("label").unwrap().value(), "inner");
+
+    assert!(matches!(
+        graph.collapse_subgraph(&String::from("G")),
+        Err(DotGraphError::RootSubGraph(..))
+    ));
+
+    // only nodes outside the subgraph clash with the collapsed node
+    let mut graph = parser::parse_from_memory(
+        "digraph G { subgraph cluster_0 { cluster_0 -> a } subgraph cluster_1 { b } cluster_1 }",
+    )?;
+    let state = graph.collapse_subgraph(&cluster)?;
+    assert_eq!(state.node(), "cluster_0");
+    graph.expand(state)?;
+    assert_eq!(graph.collect_nodes(&cluster)?.len(), 2);
+    let state = graph.collapse_subgraph(&String::from("cluster_1"))?;
+    assert_eq!(state.node(), "cluster_1_");
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn expand() -> Result<(), DotGraphError> {
+    let original = parser::parse_from_memory(
+        r#"
+        digraph G {
+            subgraph cluster_0 {
+                a -> b
+                subgraph cluster_1 {
+                    c [color=red]
+                }
+            }
+            b -> c
+            x -> a
+            x -> c
+            b:p -> y
+        }
+        "#,
+    )?;
+
+    let mut graph = original.clone();
+    let state = graph.collapse_subgraph(&String::from("cluster_0"))?;
+    graph.expand(state)?;
+
+    assert_eq!(graph.subgraphs(), original.subgraphs());
+    assert_eq!(graph.nodes(), original.nodes());
+    assert_eq!(graph.edges(), original.edges());
+    for id in original.subgraphs() {
+        let mut edges = graph.collect_edges(id)?;
+        let mut expected = original.collect_edges(id)?;
+        edges.sort_by_key(|id| id.to_string());
+        expected.sort_by_key(|id| id.to_string());
+        assert_eq!(edges, expected);
+        assert_eq!(graph.collect_subgraphs(id)?.len(), original.collect_subgraphs(id)?.len());
+    }
+    let c = String::from("c");
+    assert_eq!(graph.froms(&c)?, original.froms(&c)?);
+    assert!(graph.search_node(&c).unwrap().attrs().contains("color"));
+
+    // an edge to a node removed while collapsed is not restored, nor held by its subgraph
+    let mut graph =
+        parser::parse_from_memory("digraph G { subgraph cluster_0 { a -> b; b -> x } }")?;
+    let x = String::from("x");
+    graph.move_node(&x, &String::from("G"))?;
+    let state = graph.collapse_subgraph(&String::from("cluster_0"))?;
+    graph.remove_node(&x)?;
+    graph.expand(state)?;
+
+    assert_eq!(graph.edges().len(), 1);
+    assert_eq!(graph.collect_edges(&String::from("cluster_0"))?.len(), 1);
+    let mut dot = Vec::new();
+    graph.to_dot(&mut dot)?;
+
+    Ok(())
+}
@@ -0,0 +1,1793 @@
+use graphviz_rs::prelude::*;
+
+use rayon::prelude::*;
+
+use serial_test::serial;
+
+const CLUSTERED: &str = r#"
+digraph G {
+    subgraph cluster_0 {
+        a -> b
+    }
+    subgraph cluster_1 {
+        c -> d
+    }
+    b -> c
+}
+"#;
+
+const CLUSTERED_DIAMOND: &str = r#"
+digraph G {
+    subgraph cluster_0 {
+        b
+        c
+    }
+    a -> b -> d
+    a -> c -> d
+    x -> b
+    d -> y
+}
+"#;
+
+#[test]
+#[serial]
+fn extraction_map() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(CLUSTERED)?;
+
+    let (subgraph, map) = graph.subgraph_with_map(&String::from("cluster_1"))?;
+    for id in subgraph.nodes() {
+        assert!(map.nodes().originals(id).unwrap().contains(id));
+    }
+    assert_eq!(map.nodes().derived(&String::from("c")), Some(&String::from("c")));
+    assert_eq!(map.nodes().derived(&String::from("a")), None);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn select_layer() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            layers="local:pvt:test"
+            a [layer="all"]
+            b [layer="pvt"]
+            c [layer="1:2"]
+            d
+            a -> b
+            a -> c [layer="local"]
+            a -> d
+        }
+        "#,
+    )?;
+
+    assert_eq!(graph.layers(), vec!["local", "pvt", "test"]);
+    assert_eq!(
+        graph.search_node(&String::from("c")).unwrap().layers(),
+        Some(vec![LayerRange::Span(LayerId::Index(1), LayerId::Index(2))])
+    );
+
+    let pvt = graph.select_layer("pvt")?;
+    let mut nodes: Vec<&NodeId> = pvt.nodes().into_iter().collect();
+    nodes.sort();
+    assert_eq!(nodes, vec!["a", "b", "c", "d"]);
+    assert_eq!(pvt.edges().len(), 2);
+
+    let test = graph.select_layer("test")?;
+    assert_eq!(test.nodes().len(), 2);
+
+    assert!(graph.select_layer("missing").is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn ranked_neighbors() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a -> b [weight=3]
+            c -> a [weight=5]
+            a -> d
+            a:p -> b [weight=4]
+        }
+        "#,
+    )?;
+
+    let ranked = graph.ranked_neighbors(&String::from("a"), "weight")?;
+    let ranked: Vec<(&str, f64)> = ranked.into_iter().map(|(id, w)| (id.as_str(), w)).collect();
+    assert_eq!(ranked, vec![("b", 7.0), ("c", 5.0), ("d", 0.0)]);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn window() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            subgraph cluster_0 {
+                a [step=0]
+                b [step=1]
+            }
+            c [step=2]
+            d
+            a -> b [step=1]
+            b -> c [step=2]
+            a -> c [step=5]
+        }
+        "#,
+    )?;
+
+    let window = graph.window("step", 1.0..=2.0);
+    let mut nodes: Vec<&NodeId> = window.nodes().into_iter().collect();
+    nodes.sort();
+    assert_eq!(nodes, vec!["b", "c"]);
+    assert_eq!(window.edges().len(), 1);
+    assert!(window.search_subgraph(&String::from("cluster_0")).is_some());
+
+    let window = graph.window("step", ..1.0);
+    assert_eq!(window.nodes().len(), 1);
+    assert!(window.edges().is_empty());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn difference() -> Result<(), DotGraphError> {
+    let before =
+        parser::parse_from_memory("digraph G { a -> b; b -> c; subgraph cluster_0 { d } }")?;
+    let after = parser::parse_from_memory("digraph G { a -> b; b -> e; c }")?;
+
+    let removed = before.difference(&after);
+    let mut nodes: Vec<&NodeId> = removed.nodes().into_iter().collect();
+    nodes.sort();
+    assert_eq!(nodes, vec!["b", "c", "d"]);
+    assert_eq!(removed.edges().len(), 1);
+
+    let changed = before.symmetric_difference(&after);
+    let mut nodes: Vec<&NodeId> = changed.nodes().into_iter().collect();
+    nodes.sort();
+    assert_eq!(nodes, vec!["b", "c", "d", "e"]);
+    assert_eq!(changed.edges().len(), 2);
+    assert_eq!(changed.collect_nodes(&String::from("cluster_0"))?, vec!["d"]);
+
+    let mut dot = Vec::new();
+    changed.to_dot(&mut dot).expect("to_dot should succeed");
+    parser::parse_from_memory(std::str::from_utf8(&dot).unwrap())?;
+
+    // a cluster moved to another parent stays under its parent in the first graph
+    let before = parser::parse_from_memory(
+        "digraph G { subgraph cluster_p { subgraph cluster_x { a } } subgraph cluster_q { q } }",
+    )?;
+    let after = parser::parse_from_memory(
+        "digraph G { subgraph cluster_p { p } subgraph cluster_q { subgraph cluster_x { b } } }",
+    )?;
+    let changed = before.symmetric_difference(&after);
+    let cluster = String::from("cluster_x");
+    assert_eq!(changed.subgraph_parent(&cluster), Some(&String::from("cluster_p")));
+    assert!(changed.collect_subgraphs(&String::from("cluster_q"))?.is_empty());
+    assert_eq!(changed.collect_nodes(&String::from("G"))?.len(), 4);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn summarize() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a -> x -> b -> c -> d
+            e
+        }
+        "#,
+    )?;
+
+    let view = graph.filter(&[&String::from("b")]);
+    let (summarized, map) = graph.summarize_with_map(&view);
+    assert_eq!(summarized.nodes().len(), 4);
+
+    let b = String::from("b");
+    let upstream = summarized.froms(&b)?.into_iter().next().unwrap().clone();
+    let downstream = summarized.tos(&b)?.into_iter().next().unwrap().clone();
+    assert_eq!(map.nodes().originals(&upstream).unwrap().len(), 2);
+    assert_eq!(map.nodes().originals(&downstream).unwrap().len(), 2);
+
+    let label = summarized.search_node(&upstream).unwrap().attrs().get("label").unwrap();
+    assert_eq!(label.value(), "… 2 nodes hidden");
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn prune() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a -> b -> c
+            a -> d
+            e -> e
+        }
+        "#,
+    )?;
+
+    let (pruned, removed) = graph.prune_leaves(1);
+    assert_eq!(removed, vec!["c", "d"]);
+    assert_eq!(pruned.nodes().len(), 3);
+
+    let (pruned, removed) = graph.prune_leaves(usize::MAX);
+    assert_eq!(removed, vec!["c", "d", "b", "a"]);
+    assert_eq!(pruned.nodes(), [&String::from("e")].into());
+
+    let (_, removed) = graph.prune_sources(2);
+    assert_eq!(removed, vec!["a", "b", "d"]);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn reachable_from() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a -> b -> c
+            d -> c
+            e -> f
+            g
+        }
+        "#,
+    )?;
+
+    let reachable = graph.reachable_from(&[&String::from("b"), &String::from("e")]);
+    let mut nodes: Vec<&NodeId> = reachable.nodes().into_iter().collect();
+    nodes.sort();
+    assert_eq!(nodes, vec!["b", "c", "e", "f"]);
+    assert_eq!(reachable.edges().len(), 2);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn iter_nodes() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory("digraph G { a [color=red]; b; a -> b }")?;
+
+    let mut colors: Vec<(&NodeId, Option<&String>)> = graph
+        .iter_nodes()
+        .map(|node| (node.id(), node.attrs().get("color").map(|attr| attr.value())))
+        .collect();
+    colors.sort();
+    assert_eq!(
+        colors,
+        vec![(&String::from("a"), Some(&String::from("red"))), (&String::from("b"), None)]
+    );
+    assert_eq!(graph.par_iter_nodes().count(), 2);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn iter_edges() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory("digraph G { a -> b [label=x]; b -> c }")?;
+
+    let mut labels: Vec<(String, Option<&String>)> = graph
+        .iter_edges()
+        .map(|edge| (edge.id().to_string(), edge.attrs().get("label").map(|attr| attr.value())))
+        .collect();
+    labels.sort();
+    assert_eq!(labels[0], (String::from("a -> b"), Some(&String::from("x"))));
+    assert_eq!(labels[1], (String::from("b -> c"), None));
+    assert_eq!(graph.par_iter_edges().filter(|edge| edge.attrs().is_empty()).count(), 1);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn edges_from_and_to() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a [shape=record, label="<l> left|<r> right"]
+            a:l -> b [label=left]
+            a:r -> b [label=right]
+            b -> c
+        }
+        "#,
+    )?;
+
+    let b = String::from("b");
+    let mut labels: Vec<&String> = graph
+        .edges_to(&b)?
+        .into_iter()
+        .map(|edge| edge.attrs().get("label").unwrap().value())
+        .collect();
+    labels.sort();
+    assert_eq!(labels, vec!["left", "right"]);
+
+    let outgoing = graph.edges_from(&b)?;
+    assert_eq!(outgoing.len(), 1);
+    assert_eq!(outgoing[0].id().to(), "c");
+    assert!(graph.edges_from(&String::from("c"))?.is_empty());
+    assert!(graph.edges_to(&String::from("d")).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn sources_and_sinks() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory("digraph G { a -> c; b -> c; c -> d; c -> e; f }")?;
+
+    let id = |id: &str| String::from(id);
+    let (a, b, d, e, f) = (id("a"), id("b"), id("d"), id("e"), id("f"));
+    assert_eq!(graph.sources(), [&a, &b, &f].into());
+    assert_eq!(graph.sinks(), [&d, &e, &f].into());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn ancestors_and_descendants() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(CLUSTERED)?;
+
+    let id = |id: &str| String::from(id);
+    let (a, b, c, d) = (id("a"), id("b"), id("c"), id("d"));
+    assert_eq!(graph.ancestors(&c)?, [&a, &b].into());
+    assert_eq!(graph.descendants(&b)?, [&c, &d].into());
+    assert!(graph.ancestors(&a)?.is_empty());
+    assert!(graph.descendants(&id("e")).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn neighbors_directed() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(CLUSTERED)?;
+
+    let sorted = |graph: Graph| {
+        let mut nodes: Vec<String> = graph.nodes().into_iter().cloned().collect();
+        nodes.sort();
+        nodes
+    };
+    let b = String::from("b");
+    assert_eq!(sorted(graph.neighbors_directed(&b, 1, Direction::Forward)?), vec!["b", "c"]);
+    assert_eq!(sorted(graph.neighbors_directed(&b, 2, Direction::Backward)?), vec!["a", "b"]);
+    assert_eq!(sorted(graph.neighbors_directed(&b, 1, Direction::Both)?), vec!["a", "b", "c"]);
+    assert_eq!(sorted(graph.neighbors(&b, 2)?), vec!["a", "b", "c", "d"]);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn neighbors_of() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory("digraph G { a -> b -> c -> d -> e -> f }")?;
+
+    let a = String::from("a");
+    let f = String::from("f");
+    let neighbors = graph.neighbors_of(&[&a, &f], 1)?;
+    let mut nodes: Vec<&NodeId> = neighbors.nodes().into_iter().collect();
+    nodes.sort();
+    assert_eq!(nodes, vec!["a", "b", "e", "f"]);
+    assert_eq!(neighbors.edges().len(), 2);
+
+    assert!(graph.neighbors_of(&[&a, &String::from("g")], 1).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn neighbor_ids() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(CLUSTERED)?;
+
+    let id = |id: &str| String::from(id);
+    let (a, b, c) = (id("a"), id("b"), id("c"));
+    assert_eq!(graph.neighbor_ids(&b, 1)?, [&a, &b, &c].into());
+    assert_eq!(graph.neighbor_ids(&b, 0)?, [&b].into());
+    assert_eq!(graph.neighbor_ids(&b, 2)?, graph.neighbors(&b, 2)?.nodes());
+    assert!(graph.neighbor_ids(&id("e"), 1).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn bfs_and_dfs() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory("digraph G { a -> c -> e; a -> b -> d; x -> b }")?;
+
+    let a = String::from("a");
+    let bfs: Vec<&NodeId> = graph.bfs(&a, Direction::Forward)?.collect();
+    assert_eq!(bfs, vec!["a", "b", "c", "d", "e"]);
+    let dfs: Vec<&NodeId> = graph.dfs(&a, Direction::Forward)?.collect();
+    assert_eq!(dfs, vec!["a", "b", "d", "c", "e"]);
+
+    let d = String::from("d");
+    let upstream: Vec<&NodeId> = graph.bfs(&d, Direction::Backward)?.collect();
+    assert_eq!(upstream, vec!["d", "b", "a", "x"]);
+    assert_eq!(graph.dfs(&d, Direction::Both)?.count(), 6);
+    assert_eq!(graph.bfs(&a, Direction::Forward)?.take(2).count(), 2);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn visit() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory("digraph G { a -> b -> d; a -> c -> d; c -> e }")?;
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<String>,
+    }
+
+    impl GraphVisitor for Recorder {
+        fn discover_node(&mut self, id: &NodeId) -> Control {
+            self.events.push(format!("discover {id}"));
+            if id == "b" {
+                Control::Prune
+            } else {
+                Control::Continue
+            }
+        }
+
+        fn examine_edge(&mut self, edge: &Edge) -> Control {
+            self.events.push(format!("examine {}", edge.id()));
+            if edge.id().to() == "e" {
+                Control::Break
+            } else {
+                Control::Continue
+            }
+        }
+
+        fn finish_node(&mut self, id: &NodeId) -> Control {
+            self.events.push(format!("finish {id}"));
+            Control::Continue
+        }
+    }
+
+    let mut recorder = Recorder::default();
+    let control = graph.visit(&String::from("a"), Direction::Forward, &mut recorder)?;
+    assert_eq!(control, Control::Break);
+    assert_eq!(
+        recorder.events,
+        vec![
+            "discover a",
+            "examine a -> b",
+            "discover b",
+            "finish b",
+            "examine a -> c",
+            "discover c",
+            "examine c -> d",
+            "discover d",
+            "finish d",
+            "examine c -> e",
+        ]
+    );
+
+    struct Counter(usize);
+    impl GraphVisitor for Counter {
+        fn finish_node(&mut self, _: &NodeId) -> Control {
+            self.0 += 1;
+            Control::Continue
+        }
+    }
+
+    let mut counter = Counter(0);
+    let control = graph.visit(&String::from("d"), Direction::Backward, &mut counter)?;
+    assert_eq!(control, Control::Continue);
+    assert_eq!(counter.0, 4);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn subtree_depth() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            subgraph cluster_0 {
+                subgraph cluster_00 { a }
+                subgraph cluster_01 { b }
+            }
+            subgraph cluster_1 { c }
+        }
+        "#,
+    )?;
+
+    assert_eq!(graph.subtree_depth(&String::from("G"))?, 0);
+    assert_eq!(graph.subtree_depth(&String::from("cluster_1"))?, 1);
+    assert_eq!(graph.subtree_depth(&String::from("cluster_01"))?, 2);
+    assert!(graph.subtree_depth(&String::from("cluster_2")).is_err());
+
+    assert_eq!(graph.subgraphs_at_depth(0), vec!["G"]);
+    assert_eq!(graph.subgraphs_at_depth(1), vec!["cluster_0", "cluster_1"]);
+    assert_eq!(graph.subgraphs_at_depth(2), vec!["cluster_00", "cluster_01"]);
+    assert!(graph.subgraphs_at_depth(3).is_empty());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn filter_by_attr() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a [shape=record, label="conv2d"]
+            b [shape=record, label="relu"]
+            c [label="conv3d"]
+            a -> b -> c
+        }
+        "#,
+    )?;
+
+    let sorted = |graph: Graph| {
+        let mut nodes: Vec<String> = graph.nodes().into_iter().cloned().collect();
+        nodes.sort();
+        nodes
+    };
+    let records = graph.filter_by_attr("shape", "record");
+    assert_eq!(records.edges().len(), 1);
+    assert_eq!(sorted(records), vec!["a", "b"]);
+    assert_eq!(sorted(graph.filter_by_attr_contains("label", "conv")), vec!["a", "c"]);
+    assert!(graph.filter_by_attr("shape", "box").nodes().is_empty());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn filter_nodes_and_edges() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        "digraph G { a -> b [weight=2]; b -> c [weight=5]; c -> a; d [color=red] }",
+    )?;
+
+    let colored = graph.filter_nodes(|node| !node.attrs().is_empty());
+    assert_eq!(colored.nodes(), [&String::from("d")].into());
+
+    let heavy = graph.filter_edges(|edge| edge.attrs().contains("weight"));
+    assert_eq!(heavy.nodes().len(), 4);
+    assert_eq!(heavy.edges().len(), 2);
+    assert!(heavy.is_acyclic());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn search_fuzzy() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            "layer2/conv" -> "layer2/relu"
+            "clover" -> "layer12/conv"
+            n0 [label="Conv2D"]
+        }
+        "#,
+    )?;
+
+    assert_eq!(graph.search_fuzzy("l2cv", 10), vec!["layer2/conv", "layer12/conv"]);
+    assert_eq!(graph.search_fuzzy("conv", 2), vec!["n0", "layer2/conv"]);
+    assert_eq!(graph.search_fuzzy("CONV2", 10), vec!["n0"]);
+    assert!(graph.search_fuzzy("xyz", 10).is_empty());
+    assert!(graph.search_fuzzy("", 10).is_empty());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn text_index() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a [label="Conv2D 3x3", tooltip="layer1/block2"]
+            b [label="Conv2D 1x1"]
+            c [label="ReLU"]
+            a -> b -> c
+        }
+        "#,
+    )?;
+
+    let index = graph.build_text_index();
+    let id = |id: &str| String::from(id);
+    let (a, b, c) = (id("a"), id("b"), id("c"));
+    assert_eq!(index.query("conv2d"), [&a, &b].into());
+    assert_eq!(index.query("conv2d 3x"), [&a].into());
+    assert_eq!(index.query("BLOCK2"), [&a].into());
+    assert_eq!(index.query("re"), [&c].into());
+    assert!(index.query("relu conv2d").is_empty());
+    assert!(index.query("  ").is_empty());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn nodes_with_prefix() -> Result<(), DotGraphError> {
+    let mut graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            "layer1/block1/conv" -> "layer1/block2/conv" -> "layer10/conv" -> "layer2/conv"
+        }
+        "#,
+    )?;
+
+    assert_eq!(
+        graph.nodes_with_prefix("layer1/"),
+        vec!["layer1/block1/conv", "layer1/block2/conv"]
+    );
+    assert_eq!(graph.nodes_with_prefix("layer1").len(), 3);
+    assert_eq!(graph.nodes_with_prefix("").len(), 4);
+    assert!(graph.nodes_with_prefix("layer3").is_empty());
+
+    graph.rename_node(&String::from("layer2/conv"), String::from("layer1/block3/conv"))?;
+    assert_eq!(graph.nodes_with_prefix("layer1/").len(), 3);
+    assert!(graph.nodes_with_prefix("layer2").is_empty());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn query() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            subgraph cluster_bar {
+                c [label="conv2d"]
+                d
+            }
+            a [label="conv1d"]
+            a -> b
+            a -> c
+            a -> e
+            c -> d
+            "x-y" -> a
+        }
+        "#,
+    )?;
+
+    let sorted = |ids: std::collections::HashSet<&NodeId>| {
+        let mut ids: Vec<String> = ids.into_iter().cloned().collect();
+        ids.sort();
+        ids
+    };
+    assert_eq!(sorted(graph.select(r#"node[label~="conv"] & out_degree > 2"#)?), vec!["a"]);
+    assert_eq!(
+        sorted(graph.select("reachable_from(a) - cluster(cluster_bar)")?),
+        vec!["a", "b", "e"]
+    );
+    assert_eq!(sorted(graph.select("node[label] | id(\"x-y\")")?), vec!["a", "c", "x-y"]);
+    assert_eq!(sorted(graph.select("!(node[label] | in_degree = 0)")?), vec!["b", "d", "e"]);
+    assert_eq!(sorted(graph.select("ancestors(d) & node[label!=conv2d]")?), vec!["a", "x-y"]);
+    assert_eq!(sorted(graph.select("neighbors(c, 1) - descendants(c)")?), vec!["a", "c"]);
+
+    let extracted = graph.query("cluster(cluster_bar) | id(a)")?;
+    assert_eq!(extracted.edges().len(), 2);
+
+    let query = query::parse("degree >= 1 & node[label]")?;
+    assert_eq!(query.select(&graph)?.len(), 2);
+
+    assert!(matches!(graph.select("node[label"), Err(DotGraphError::InvalidQuery(..))));
+    assert!(matches!(graph.select("out_degree > x"), Err(DotGraphError::InvalidQuery(..))));
+    assert!(matches!(graph.select("id(z)"), Err(DotGraphError::NoSuchNode(..))));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn find_path() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory("digraph G { a -> b -> c -> d; a -> x -> d; e }")?;
+
+    let id = |id: &str| String::from(id);
+    assert_eq!(graph.find_path(&id("a"), &id("d"))?.unwrap(), vec!["a", "x", "d"]);
+    assert_eq!(graph.find_path(&id("b"), &id("b"))?.map(|path| path.len()), Some(1));
+    assert_eq!(graph.find_path(&id("d"), &id("a"))?, None);
+    assert_eq!(graph.find_path(&id("a"), &id("e"))?, None);
+    assert!(graph.find_path(&id("a"), &id("f")).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn between() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(CLUSTERED_DIAMOND)?;
+
+    let id = |id: &str| String::from(id);
+    let between = graph.between(&id("a"), &id("d"))?;
+    let mut nodes: Vec<&NodeId> = between.nodes().into_iter().collect();
+    nodes.sort();
+    assert_eq!(nodes, vec!["a", "b", "c", "d"]);
+    assert_eq!(between.edges().len(), 4);
+    assert_eq!(between.collect_nodes(&id("cluster_0"))?.len(), 2);
+
+    assert!(graph.between(&id("d"), &id("a"))?.nodes().is_empty());
+    assert!(graph.between(&id("a"), &id("z")).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn reachability_index() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(CLUSTERED_DIAMOND)?;
+
+    let index = graph.reachability_index()?;
+    let nodes: Vec<&NodeId> = graph.nodes().into_iter().collect();
+    for from in &nodes {
+        let descendants = graph.descendants(from)?;
+        for to in &nodes {
+            let expected = from == to || descendants.contains(to);
+            assert_eq!(index.can_reach(from, to), expected, "{from} -> {to}");
+        }
+    }
+    assert!(!index.can_reach(&String::from("a"), &String::from("z")));
+    assert!(index.width() <= 3);
+
+    let cyclic = parser::parse_from_memory("digraph G { a -> b -> a }")?;
+    assert!(matches!(cyclic.reachability_index(), Err(DotGraphError::Cycle(..))));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn slices() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(CLUSTERED_DIAMOND)?;
+
+    let sorted = |graph: Graph| {
+        let mut nodes: Vec<String> = graph.nodes().into_iter().cloned().collect();
+        nodes.sort();
+        nodes
+    };
+    let b = String::from("b");
+    assert_eq!(sorted(graph.forward_slice(&b, None)), vec!["b", "d", "y"]);
+    assert_eq!(sorted(graph.forward_slice(&b, Some(1))), vec!["b", "d"]);
+    assert!(graph.forward_slice(&String::from("z"), None).nodes().is_empty());
+
+    let d = String::from("d");
+    assert_eq!(sorted(graph.backward_slice(&d, None)), vec!["a", "b", "c", "d", "x"]);
+    assert_eq!(sorted(graph.backward_slice(&d, Some(1))), vec!["b", "c", "d"]);
+    assert_eq!(sorted(graph.backward_slice(&b, Some(0))), vec!["b"]);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn edges_between_subgraphs() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            subgraph cluster_a { a1; a2 }
+            subgraph cluster_b { b1; b2 }
+            a1 -> b1
+            a2 -> b1
+            a2 -> b2 [label=x]
+            b2 -> a1
+            a1 -> a2
+        }
+        "#,
+    )?;
+
+    let (a, b) = (String::from("cluster_a"), String::from("cluster_b"));
+    let edges = graph.edges_between_subgraphs(&a, &b)?;
+    assert_eq!(edges.len(), 3);
+    assert!(edges.iter().all(|edge| edge.id().from().starts_with('a')));
+    assert!(edges.iter().any(|edge| edge.attrs().contains("label")));
+    assert_eq!(graph.edges_between_subgraphs(&b, &a)?.len(), 1);
+    assert_eq!(graph.edges_between_subgraphs(&a, &a)?.len(), 1);
+    assert!(graph.edges_between_subgraphs(&a, &String::from("cluster_c")).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn find_subgraphs() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            subgraph cluster_0 { label="decoder block 0"; a }
+            subgraph cluster_1 { label="decoder block 1"; color=red; b }
+            subgraph cluster_2 { label="encoder"; c }
+        }
+        "#,
+    )?;
+
+    let ids = |subgraphs: Vec<&SubGraph>| -> Vec<String> {
+        subgraphs.into_iter().map(|subgraph| subgraph.id().clone()).collect()
+    };
+    assert_eq!(
+        ids(graph.find_subgraphs_by_attr_contains("label", "decoder")),
+        vec!["cluster_0", "cluster_1"]
+    );
+    assert_eq!(ids(graph.find_subgraphs_by_attr("label", "encoder")), vec!["cluster_2"]);
+    assert_eq!(
+        ids(graph.find_subgraphs(|subgraph| subgraph.attrs().len() == 2)),
+        vec!["cluster_1"]
+    );
+    assert!(graph.find_subgraphs_by_attr("label", "decoder").is_empty());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn boundary_edges() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(CLUSTERED_DIAMOND)?;
+
+    let mut edges: Vec<String> = graph
+        .boundary_edges(&String::from("cluster_0"))?
+        .into_iter()
+        .map(|edge| edge.id().to_string())
+        .collect();
+    edges.sort();
+    assert_eq!(edges, vec!["a -> b", "a -> c", "b -> d", "c -> d", "x -> b"]);
+    assert!(graph.boundary_edges(&String::from("G"))?.is_empty());
+    assert!(graph.boundary_edges(&String::from("cluster_1")).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn subgraph_stats() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            subgraph cluster_0 {
+                subgraph cluster_00 { a -> b }
+                subgraph cluster_01 { c }
+                b -> c
+            }
+            d
+        }
+        "#,
+    )?;
+
+    let stats = graph.subgraph_stats(&String::from("cluster_0"))?;
+    assert_eq!((stats.nodes(), stats.edges(), stats.children(), stats.depth()), (3, 2, 2, 1));
+    let stats = graph.subgraph_stats(&String::from("G"))?;
+    assert_eq!((stats.nodes(), stats.edges(), stats.children(), stats.depth()), (4, 2, 1, 2));
+    assert_eq!(stats.nodes(), graph.collect_nodes(&String::from("G"))?.len());
+    assert_eq!(graph.subgraph_stats(&String::from("cluster_01"))?.depth(), 0);
+    assert!(graph.subgraph_stats(&String::from("cluster_1")).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn shortest_path_lengths() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(CLUSTERED_DIAMOND)?;
+    let id = |id: &str| String::from(id);
+
+    let paths = graph.shortest_path_lengths(&id("a"))?;
+    assert_eq!(paths.source(), "a");
+    assert_eq!(paths.distance(&id("a")), Some(0));
+    assert_eq!(paths.distance(&id("d")), Some(2));
+    assert_eq!(paths.distance(&id("y")), Some(3));
+    assert_eq!(paths.distance(&id("x")), None);
+    assert_eq!(paths.distances().len(), 5);
+    assert_eq!(paths.path_to(&id("y")).unwrap(), vec!["a", "b", "d", "y"]);
+    assert_eq!(paths.path_to(&id("x")), None);
+
+    assert_eq!(graph.shortest_path(&id("x"), &id("y"))?.unwrap(), vec!["x", "b", "d", "y"]);
+    assert!(graph.shortest_path_lengths(&id("z")).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn dijkstra() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a -> b [ weight = 4 ]
+            a -> c [ weight = 1 ]
+            c -> b [ weight = 2 ]
+            b -> d
+            c -> d [ weight = 5 ]
+            e
+        }
+        "#,
+    )?;
+    let id = |id: &str| String::from(id);
+
+    let paths = graph.dijkstra_by_weight(&id("a"))?;
+    assert_eq!(paths.distance(&id("b")), Some(3.0));
+    assert_eq!(paths.distance(&id("d")), Some(4.0));
+    assert_eq!(paths.distance(&id("e")), None);
+    assert_eq!(paths.path_to(&id("d")).unwrap(), vec!["a", "c", "b", "d"]);
+
+    let paths = graph.dijkstra(&id("a"), |_| 1.0)?;
+    assert_eq!(paths.distance(&id("d")), Some(2.0));
+    assert_eq!(paths.path_to(&id("d")).unwrap(), vec!["a", "b", "d"]);
+
+    assert!(graph.dijkstra_by_weight(&id("z")).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn longest_path() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a -> b [ weight = 4 ]
+            a -> c [ weight = 1 ]
+            c -> b [ weight = 2 ]
+            b -> d
+            c -> d [ weight = 5 ]
+        }
+        "#,
+    )?;
+
+    let (path, length) = graph.longest_path(|edge| edge.weight().unwrap_or(1.0))?;
+    assert_eq!(path, vec!["a", "c", "d"]);
+    assert_eq!(length, 6.0);
+
+    let (path, length) = graph.longest_path(|_| 1.0)?;
+    assert_eq!(path, vec!["a", "c", "b", "d"]);
+    assert_eq!(length, 3.0);
+
+    let cyclic = parser::parse_from_memory("digraph G { a -> b -> a }")?;
+    assert!(matches!(cyclic.longest_path(|_| 1.0), Err(DotGraphError::Cycle(..))));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn topo_generations() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(CLUSTERED_DIAMOND)?;
+
+    let generations = graph.topo_generations()?;
+    assert_eq!(generations, vec![vec!["a", "x"], vec!["b", "c"], vec!["d"], vec!["y"]]);
+
+    let graph = parser::parse_from_memory(CLUSTERED)?;
+    assert_eq!(graph.topo_generations()?.len(), 4);
+
+    let cyclic = parser::parse_from_memory("digraph G { a -> b -> a }")?;
+    assert!(matches!(cyclic.topo_generations(), Err(DotGraphError::Cycle(..))));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn eulerian_path() -> Result<(), DotGraphError> {
+    let path = |graph: &Graph| {
+        graph.eulerian_path().map(|path| path.iter().map(|id| id.to_string()).collect::<Vec<_>>())
+    };
+
+    let circuit = parser::parse_from_memory("digraph G { a -> b -> c -> a; b -> d -> b }")?;
+    assert_eq!(path(&circuit).unwrap(), vec!["a -> b", "b -> d", "d -> b", "b -> c", "c -> a"]);
+
+    let open = parser::parse_from_memory("digraph G { s -> a -> b -> a -> t }")?;
+    assert_eq!(path(&open).unwrap(), vec!["s -> a", "a -> b", "b -> a", "a -> t"]);
+
+    let forked = parser::parse_from_memory("digraph G { a -> b; a -> c }")?;
+    assert!(forked.eulerian_path().is_none());
+    let disconnected = parser::parse_from_memory("digraph G { a -> b -> a; c -> d -> c }")?;
+    assert!(disconnected.eulerian_path().is_none());
+
+    let empty = parser::parse_from_memory("digraph G { a }")?;
+    assert!(empty.eulerian_path().unwrap().is_empty());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn find_cycle() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(CLUSTERED_DIAMOND)?;
+    assert_eq!(graph.find_cycle(), None);
+    assert!(graph.find_cycle_edges().is_none());
+
+    let cyclic = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a -> b -> c -> d -> b
+            c -> e
+            f -> f
+        }
+        "#,
+    )?;
+    assert_eq!(cyclic.find_cycle().unwrap(), vec!["b", "c", "d"]);
+    let edges: Vec<String> =
+        cyclic.find_cycle_edges().unwrap().iter().map(|edge| edge.id().to_string()).collect();
+    assert_eq!(edges, vec!["b -> c", "c -> d", "d -> b"]);
+
+    let error = cyclic.topsort().unwrap_err();
+    assert!(matches!(&error, DotGraphError::Cycle(_, cycle) if cycle == &["b", "c", "d"]));
+    assert_eq!(error.to_string(), "`G` contains a cycle `b -> c -> d -> b`");
+
+    let looped = parser::parse_from_memory("digraph G { f -> f }")?;
+    assert_eq!(looped.find_cycle().unwrap(), vec!["f"]);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn cycles() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(CLUSTERED_DIAMOND)?;
+    assert_eq!(graph.cycles(None).count(), 0);
+
+    let cyclic = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a -> b -> c -> a
+            b -> a
+            c -> d -> c
+            e -> e
+            c -> f
+        }
+        "#,
+    )?;
+    let cycles: Vec<Vec<&NodeId>> = cyclic.cycles(None).collect();
+    assert_eq!(cycles, vec![vec!["a", "b"], vec!["a", "b", "c"], vec!["c", "d"], vec!["e"]]);
+
+    let cycles: Vec<Vec<&NodeId>> = cyclic.cycles(Some(2)).collect();
+    assert_eq!(cycles, vec![vec!["a", "b"], vec!["c", "d"], vec!["e"]]);
+    assert_eq!(cyclic.cycles(Some(0)).count(), 0);
+
+    // a large DAG is searched only through its strongly connected components
+    let edges: Vec<String> = (0..20_000).map(|i| format!("n{i} -> n{}", i + 1)).collect();
+    let dag = parser::parse_from_memory(&format!("digraph G {{ {} }}", edges.join("; ")))?;
+    assert_eq!(dag.cycles(None).count(), 0);
+    let looped = parser::parse_from_memory(&format!(
+        "digraph G {{ {}; n20000 -> n19999 }}",
+        edges.join("; ")
+    ))?;
+    let cycles: Vec<Vec<&NodeId>> = looped.cycles(None).collect();
+    assert_eq!(cycles, vec![vec!["n19999", "n20000"]]);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn feedback_arc_set() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(CLUSTERED_DIAMOND)?;
+    assert!(graph.feedback_arc_set().is_empty());
+
+    let mut cyclic = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a -> b -> c -> d -> e
+            e -> b
+            c -> c
+            x -> a
+        }
+        "#,
+    )?;
+    let mut arcs: Vec<String> = cyclic.feedback_arc_set().iter().map(|id| id.to_string()).collect();
+    arcs.sort_unstable();
+    assert_eq!(arcs, vec!["c -> c", "e -> b"]);
+
+    let removed = cyclic.break_cycles();
+    assert_eq!(removed.len(), 2);
+    assert!(cyclic.is_acyclic());
+    assert_eq!(cyclic.edges().len(), 5);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn dominators() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            entry -> a -> b -> d -> exit
+            a -> c -> d
+            c -> c
+            d -> a
+            x -> d
+        }
+        "#,
+    )?;
+    let id = |id: &str| String::from(id);
+
+    let dominators = graph.dominators(&id("entry"))?;
+    assert_eq!(dominators.root(), "entry");
+    assert_eq!(dominators.immediate_dominator(&id("d")).unwrap(), "a");
+    assert_eq!(dominators.immediate_dominator(&id("exit")).unwrap(), "d");
+    assert_eq!(dominators.immediate_dominator(&id("entry")), None);
+    assert_eq!(dominators.immediate_dominator(&id("x")), None);
+    assert_eq!(dominators.dominators(&id("exit")).unwrap(), vec!["exit", "d", "a", "entry"]);
+    assert!(dominators.dominates(&id("a"), &id("c")));
+    assert!(!dominators.dominates(&id("b"), &id("d")));
+    assert!(!dominators.dominates(&id("entry"), &id("x")));
+
+    let post_dominators = graph.post_dominators(&id("exit"))?;
+    assert_eq!(post_dominators.immediate_dominator(&id("a")).unwrap(), "d");
+    assert_eq!(post_dominators.immediate_dominator(&id("x")).unwrap(), "d");
+    assert!(post_dominators.dominates(&id("d"), &id("entry")));
+    assert!(!post_dominators.dominates(&id("b"), &id("a")));
+
+    assert!(graph.post_dominators(&id("y")).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn bipartition() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            op0 -> t0 -> op1 -> t1
+            op0 -> t2 -> op1
+            t3 -> op2
+            lonely
+        }
+        "#,
+    )?;
+    let ids = |ids: &[&str]| {
+        ids.iter().map(|id| String::from(*id)).collect::<std::collections::HashSet<_>>()
+    };
+
+    let (left, right) = graph.bipartition().unwrap();
+    assert_eq!(left, ids(&["lonely", "op0", "op1", "op2"]));
+    assert_eq!(right, ids(&["t0", "t1", "t2", "t3"]));
+
+    let odd = parser::parse_from_memory("digraph G { a -> b -> c; a -> c }")?;
+    assert!(odd.bipartition().is_none());
+    let looped = parser::parse_from_memory("digraph G { a -> a }")?;
+    assert!(looped.bipartition().is_none());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn max_bipartite_matching() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            w0 -> t0
+            w0 -> t1
+            w1 -> t0
+            w2 -> t1
+            w2 -> t2
+            t2 -> t3
+            idle
+        }
+        "#,
+    )?;
+    let id = |id: &str| String::from(id);
+    let ids = |ids: &[&str]| {
+        ids.iter().map(|id| String::from(*id)).collect::<std::collections::HashSet<_>>()
+    };
+
+    let matching = graph
+        .max_bipartite_matching(&ids(&["w0", "w1", "w2", "idle"]), &ids(&["t0", "t1", "t2"]))?;
+    assert_eq!(matching.len(), 3);
+    assert_eq!(matching[&id("w0")], "t1");
+    assert_eq!(matching[&id("w1")], "t0");
+    assert_eq!(matching[&id("w2")], "t2");
+
+    let matching = graph.max_bipartite_matching(&ids(&["t0", "t1"]), &ids(&["w0", "w1"]))?;
+    assert_eq!(matching.len(), 2);
+
+    assert!(graph.max_bipartite_matching(&ids(&["w0"]), &ids(&["none"])).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn greedy_coloring() -> Result<(), DotGraphError> {
+    let mut graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a -> b -> c -> a
+            c -> d
+            d -> d
+            e
+        }
+        "#,
+    )?;
+
+    let colors = graph.greedy_coloring();
+    assert_eq!(colors.len(), 5);
+    for edge in graph.edges() {
+        let (from, to) = (edge.from(), edge.to());
+        assert!(from == to || colors[from] != colors[to]);
+    }
+    assert_eq!(colors[&String::from("c")], 0);
+    assert_eq!(colors[&String::from("e")], 0);
+    assert_eq!(colors.values().max(), Some(&2));
+
+    assert_eq!(graph.paint_coloring(), 3);
+    let node = graph.search_node(&String::from("b")).unwrap();
+    assert_eq!(node.attrs().get("style").unwrap().value(), "filled");
+    assert_eq!(node.attrs().get("fillcolor").unwrap().value(), "3");
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn minimum_spanning_tree() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a -> b [ weight = 1 ]
+            b -> c [ weight = 2 ]
+            a -> c [ weight = 3 ]
+            d -> c [ weight = 1 ]
+            d -> d
+            e -> f [ weight = 5 ]
+            g
+        }
+        "#,
+    )?;
+
+    let tree = graph.minimum_spanning_tree(|edge| edge.weight().unwrap_or(1.0));
+    let mut edges: Vec<String> = tree.edges().iter().map(|id| id.to_string()).collect();
+    edges.sort_unstable();
+    assert_eq!(edges, vec!["a -> b", "b -> c", "d -> c", "e -> f"]);
+    assert_eq!(tree.nodes().len(), graph.nodes().len());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn pagerank() -> Result<(), DotGraphError> {
+    let mut graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a -> hub
+            b -> hub
+            c -> hub
+            hub -> a
+            d
+        }
+        "#,
+    )?;
+    let id = |id: &str| String::from(id);
+
+    let ranks = graph.pagerank(0.85, 50);
+    assert_eq!(ranks.len(), 5);
+    assert!((ranks.values().sum::<f64>() - 1.0).abs() < 1e-9);
+    assert!(ranks[&id("hub")] > ranks[&id("a")]);
+    assert!(ranks[&id("a")] > ranks[&id("b")]);
+    assert!((ranks[&id("b")] - ranks[&id("d")]).abs() < 1e-9);
+    let rank = ranks[&id("hub")];
+
+    graph.annotate_pagerank("score", 0.85, 50);
+    let hub = graph.search_node(&id("hub")).unwrap();
+    let score: f64 = hub.attrs().get("score").unwrap().value().parse().unwrap();
+    assert!((score - rank).abs() < 1e-9);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn metrics() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(CLUSTERED_DIAMOND)?;
+
+    let metrics = graph.metrics();
+    assert_eq!((metrics.nodes(), metrics.edges()), (6, 6));
+    assert!((metrics.density() - 0.2).abs() < 1e-9);
+    assert_eq!((metrics.min_degree(), metrics.max_degree()), (1, 3));
+    assert!((metrics.avg_degree() - 2.0).abs() < 1e-9);
+    assert_eq!(metrics.components(), 1);
+    assert_eq!(metrics.diameter(), 3);
+
+    let graph = parser::parse_from_memory("digraph G { a -> b -> c -> d; e -> e; f }")?;
+    let metrics = graph.metrics();
+    assert_eq!(metrics.components(), 3);
+    assert_eq!(metrics.diameter(), 3);
+    assert_eq!(metrics.min_degree(), 0);
+    assert_eq!(metrics.max_degree(), 2);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn clustering_coefficient() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a -> b -> c -> a
+            c -> d
+            d -> d
+            e
+        }
+        "#,
+    )?;
+    let id = |id: &str| String::from(id);
+
+    let coefficients = graph.clustering_coefficients();
+    assert_eq!(coefficients[&id("a")], 1.0);
+    assert!((coefficients[&id("c")] - 1.0 / 3.0).abs() < 1e-9);
+    assert_eq!(coefficients[&id("d")], 0.0);
+    assert_eq!(coefficients[&id("e")], 0.0);
+
+    // 3 linked out of 5 pairs of neighbors: a pair each for a and b, three pairs for c
+    assert!((graph.clustering_coefficient() - 0.6).abs() < 1e-9);
+
+    let chain = parser::parse_from_memory(CLUSTERED)?;
+    assert_eq!(chain.clustering_coefficient(), 0.0);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn find_pattern() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            conv0 [ op = "conv" ]
+            relu0 [ op = "relu" ]
+            conv1 [ op = "conv" ]
+            relu1 [ op = "relu" ]
+            add [ op = "add" ]
+            conv0 -> relu0 -> add
+            conv1 -> relu1 -> add
+            conv0 -> add
+        }
+        "#,
+    )?;
+    let pattern = parser::parse_from_memory(
+        r#"
+        digraph P {
+            c [ op = "conv" ]
+            r [ op = "relu" ]
+            c -> r
+        }
+        "#,
+    )?;
+    let id = |id: &str| String::from(id);
+
+    let embeddings = graph.find_pattern(&pattern);
+    let mut matched: Vec<(&NodeId, &NodeId)> =
+        embeddings.iter().map(|embedding| (embedding[&id("c")], embedding[&id("r")])).collect();
+    matched.sort_unstable();
+    assert_eq!(matched, vec![(&id("conv0"), &id("relu0")), (&id("conv1"), &id("relu1"))]);
+
+    // without attributes, a triangle with a shortcut only embeds at conv0
+    let shape = parser::parse_from_memory("digraph P { x -> y -> z; x -> z }")?;
+    let embeddings = graph.find_pattern_with(&shape, |_, _| true);
+    assert_eq!(embeddings.len(), 1);
+    assert_eq!(embeddings[0][&id("x")], "conv0");
+    assert_eq!(embeddings[0][&id("z")], "add");
+
+    assert_eq!(graph.find_pattern_with(&pattern, |_, _| true).len(), graph.edges().len());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn structural_hash() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(CLUSTERED_DIAMOND)?;
+    let renamed = parser::parse_from_memory(
+        r#"
+        digraph H {
+            p -> q -> s
+            p -> r -> s
+            w -> r
+            s -> t [ color = "red" ]
+        }
+        "#,
+    )?;
+    assert_eq!(graph.structural_hash(), renamed.structural_hash());
+    assert_eq!(graph.structural_hash(), graph.clone().structural_hash());
+
+    let reversed = parser::parse_from_memory(
+        r#"
+        digraph H {
+            q -> p
+            s -> q
+            r -> p
+            s -> r
+            r -> w
+            t -> s
+        }
+        "#,
+    )?;
+    assert_ne!(graph.structural_hash(), reversed.structural_hash());
+
+    let chain = parser::parse_from_memory("digraph G { a -> b -> c }")?;
+    let fork = parser::parse_from_memory("digraph G { a -> b; a -> c }")?;
+    assert_ne!(chain.structural_hash(), fork.structural_hash());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn diff() -> Result<(), DotGraphError> {
+    let old = parser::parse_from_memory(CLUSTERED_DIAMOND)?;
+    let new = parser::parse_from_memory(
+        r#"
+        digraph G {
+            subgraph cluster_0 {
+                label = "pair"
+                b [ color = "red" ]
+                c
+            }
+            subgraph cluster_1 { z }
+            a -> b -> d
+            a -> c
+            c -> d [ weight = 2 ]
+            d -> y
+            d -> z
+        }
+        "#,
+    )?;
+
+    assert!(old.diff(&old).is_empty());
+
+    let diff = old.diff(&new);
+    assert!(!diff.is_empty());
+    assert_eq!(diff.subgraphs().added(), [&String::from("cluster_1")]);
+    assert_eq!(diff.subgraphs().changed()[0].1.added()[0].value(), "pair");
+    assert_eq!(diff.nodes().added(), [&String::from("z")]);
+    assert_eq!(diff.nodes().removed(), [&String::from("x")]);
+    let (id, attrs) = &diff.nodes().changed()[0];
+    assert_eq!((id.as_str(), attrs.added().len()), ("b", 1));
+
+    let ids = |ids: &[&EdgeId]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+    assert_eq!(ids(diff.edges().added()), vec!["d -> z"]);
+    assert_eq!(ids(diff.edges().removed()), vec!["x -> b"]);
+    assert_eq!(diff.edges().changed().len(), 1);
+    assert_eq!(diff.edges().changed()[0].0.to_string(), "c -> d");
+
+    let reverse = new.diff(&old);
+    assert_eq!(reverse.nodes().removed(), [&String::from("z")]);
+    assert_eq!(reverse.nodes().changed()[0].1.removed()[0].key(), "color");
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn diff_to_dot() -> Result<(), DotGraphError> {
+    let old = parser::parse_from_memory(CLUSTERED_DIAMOND)?;
+    let new = parser::parse_from_memory(
+        r#"
+        digraph G {
+            subgraph cluster_1 { z }
+            a -> b [ weight = 2 ]
+            b -> d
+            d -> y
+            d -> z
+        }
+        "#,
+    )?;
+    let diff = old.diff(&new);
+
+    let mut dot = Vec::new();
+    diff.to_dot(&mut dot)?;
+    let merged = parser::parse_from_memory(std::str::from_utf8(&dot).unwrap())?;
+    assert_eq!(merged.nodes().len(), 7);
+    assert_eq!(merged.edges().len(), 7);
+    assert_eq!(merged.subgraphs().len(), 3);
+
+    let id = |id: &str| String::from(id);
+    let attr = |attrs: &std::collections::HashSet<Attr>, key: &str| {
+        attrs.get(key).map(|attr| attr.value().clone())
+    };
+    let node_attrs = |id: &NodeId| merged.search_node(id).unwrap().attrs();
+    assert_eq!(attr(node_attrs(&id("z")), "color").unwrap(), "green");
+    assert_eq!(attr(node_attrs(&id("x")), "color").unwrap(), "red");
+    assert_eq!(attr(node_attrs(&id("x")), "style").unwrap(), "dashed");
+    assert_eq!(attr(node_attrs(&id("c")), "color").unwrap(), "red");
+    assert_eq!(attr(node_attrs(&id("a")), "color"), None);
+    assert_eq!(merged.parent_subgraph(&id("c")).unwrap(), "cluster_0");
+
+    let edge_id = |from: &str, to: &str| EdgeId::new(id(from), None, id(to), None);
+    let attrs = merged.search_edge(&edge_id("a", "b")).unwrap().attrs();
+    assert_eq!(attr(attrs, "color").unwrap(), "orange");
+    assert_eq!(attr(attrs, "xlabel").unwrap(), "+weight=2");
+    let attrs = merged.search_edge(&edge_id("x", "b")).unwrap().attrs();
+    assert_eq!(attr(attrs, "color").unwrap(), "red");
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn tree() -> Result<(), DotGraphError> {
+    let tree = parser::parse_from_memory(
+        r#"
+        digraph G {
+            src -> lib -> graphs
+            src -> main
+            lib -> utils
+        }
+        "#,
+    )?;
+    let id = |id: &str| String::from(id);
+
+    assert!(tree.is_tree());
+    assert!(tree.is_forest());
+    assert_eq!(tree.root().unwrap(), "src");
+    assert_eq!(tree.roots(), vec!["src"]);
+    assert_eq!(tree.children(&id("lib"))?, vec!["graphs", "utils"]);
+    assert_eq!(tree.parent(&id("utils"))?.unwrap(), "lib");
+    assert_eq!(tree.parent(&id("src"))?, None);
+    let subtree = tree.subtree(&id("lib"))?;
+    assert_eq!(subtree.nodes().len(), 3);
+    assert_eq!(subtree.root().unwrap(), "lib");
+    assert!(tree.subtree(&id("none")).is_err());
+
+    let forest = parser::parse_from_memory("digraph G { a -> b; c -> d; c -> e; f }")?;
+    assert!(forest.is_forest());
+    assert!(!forest.is_tree());
+    assert_eq!(forest.root(), None);
+    assert_eq!(forest.roots(), vec!["a", "c", "f"]);
+
+    for dot in ["digraph G { a -> b; a -> c; b -> d; c -> d }", "digraph G { a -> b -> a }"] {
+        let graph = parser::parse_from_memory(dot)?;
+        assert!(!graph.is_forest());
+        assert!(graph.roots().is_empty());
+    }
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn assign_ranks() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(CLUSTERED_DIAMOND)?;
+    let id = |id: &str| String::from(id);
+
+    let ranks = graph.assign_ranks();
+    let rank = |name: &str| ranks[&id(name)];
+    assert_eq!((rank("a"), rank("x"), rank("b"), rank("c"), rank("d")), (0, 0, 1, 1, 2));
+    assert_eq!(rank("y"), 3);
+
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a -> b [ minlen = 3 ]
+            b -> c -> a
+            late -> c
+        }
+        "#,
+    )?;
+    let ranks = graph.assign_ranks();
+    for edge in graph.edges().iter().filter(|edge| edge.from() != "c") {
+        assert!(ranks[edge.to()] > ranks[edge.from()]);
+    }
+    assert_eq!(ranks[&id("b")] - ranks[&id("a")], 3);
+    // late is pulled down right above c, rather than ranked along with a
+    assert_eq!(ranks[&id("c")] - ranks[&id("late")], 1);
+    assert_eq!(ranks.values().min(), Some(&0));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn quotient() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            conv0 [ op = "conv", shape = "box" ]
+            conv1 [ op = "conv", shape = "box" ]
+            relu0 [ op = "relu", shape = "box" ]
+            relu1 [ op = "relu", shape = "oval" ]
+            conv0 -> relu0 -> conv1 -> relu1
+            conv0 -> relu1
+            conv0 -> conv1
+        }
+        "#,
+    )?;
+    let op =
+        |id: &NodeId| graph.search_node(id).unwrap().attrs().get("op").unwrap().value().clone();
+
+    let (quotient, map) = graph.quotient_with_map(op, &["shape", "color"]);
+    assert_eq!(quotient.nodes().len(), 2);
+    assert_eq!(quotient.edges().len(), 2);
+
+    let id = |id: &str| String::from(id);
+    let conv = quotient.search_node(&id("conv")).unwrap().attrs();
+    assert_eq!(conv.get("count").unwrap().value(), "2");
+    assert_eq!(conv.get("shape").unwrap().value(), "box");
+    assert!(conv.get("op").is_none());
+    let relu = quotient.search_node(&id("relu")).unwrap().attrs();
+    assert!(relu.get("shape").is_none());
+
+    let edge = EdgeId::new(id("conv"), None, id("relu"), None);
+    assert_eq!(quotient.search_edge(&edge).unwrap().attrs().get("count").unwrap().value(), "3");
+    assert_eq!(map.edges().originals(&edge).unwrap().len(), 3);
+    assert_eq!(map.nodes().derived(&id("relu1")).unwrap(), "relu");
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn simplify() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a -> b -> c -> d -> e
+            a -> c
+            a -> e
+            b -> d
+            x -> y -> x
+        }
+        "#,
+    )?;
+    let ids = |ids: &[&EdgeId]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+    let (simplified, removed) = graph.simplify(1);
+    assert_eq!(ids(&removed), vec!["a -> c", "b -> d"]);
+    assert_eq!(simplified.edges().len(), graph.edges().len() - 2);
+
+    let (_, removed) = graph.simplify(3);
+    assert_eq!(ids(&removed), vec!["a -> c", "a -> e", "b -> d"]);
+
+    let (_, removed) = graph.simplify(0);
+    assert!(removed.is_empty());
+
+    // a cycle of two keeps both edges, as neither has a path around it
+    let (simplified, _) = graph.simplify(5);
+    assert!(simplified.search_edge(&EdgeId::new("x".into(), None, "y".into(), None)).is_some());
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn find_duplicates() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(CLUSTERED_DIAMOND)?;
+    assert!(graph.find_duplicates().is_empty());
+
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a [ shape = "record", label = "<l> left | <r> right" ]
+            b [ shape = "record", label = "<l> left | <r> right" ]
+            a:l -> b:l [ color = "red" ]
+            a:r -> b:r [ color = "blue" ]
+            a -> c
+            a:l -> c
+        }
+        "#,
+    )?;
+
+    let duplicates = graph.find_duplicates();
+    let groups: Vec<Vec<String>> = duplicates
+        .parallel_edges()
+        .iter()
+        .map(|group| group.iter().map(|id| id.to_string()).collect())
+        .collect();
+    assert_eq!(groups, vec![vec!["a:l -> b:l", "a:r -> b:r"], vec!["a -> c", "a:l -> c"]]);
+
+    let conflicting: Vec<&[&EdgeId]> = duplicates.conflicting_edges().collect();
+    assert_eq!(conflicting.len(), 1);
+    assert_eq!(conflicting[0][0].to(), "b");
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn adjacency_matrix() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a -> b [ cost = 2.5 ]
+            b -> c [ cost = high ]
+            c -> c
+        }
+        "#,
+    )?;
+
+    let (ids, matrix) = graph.adjacency_matrix(None);
+    assert_eq!(ids, vec!["a", "b", "c"]);
+    assert_eq!(matrix, vec![0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0]);
+
+    let (_, matrix) = graph.adjacency_matrix(Some("cost"));
+    assert_eq!(matrix, vec![0.0, 2.5, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0]);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn to_csr() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory(
+        r#"
+        digraph G {
+            a -> c [ cost = 2.5 ]
+            a -> b
+            c -> a
+            d
+        }
+        "#,
+    )?;
+
+    let csr = graph.to_csr(Some("cost"));
+    assert_eq!(csr.ids(), &["a", "b", "c", "d"]);
+    assert_eq!(csr.indptr(), &[0, 2, 2, 3, 3]);
+    assert_eq!(csr.indices(), &[1, 2, 0]);
+    assert_eq!(csr.data(), &[1.0, 2.5, 1.0]);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "petgraph")]
+fn to_petgraph() -> Result<(), DotGraphError> {
+    let graph = parser::parse_from_memory("digraph G { a -> b -> c; a -> c [ weight = 2 ] }")?;
+    let id = |id: &str| String::from(id);
+
+    let (converted, indices) = graph.to_petgraph();
+    assert_eq!(converted.node_count(), 3);
+    assert_eq!(converted.edge_count(), 3);
+    assert_eq!(converted[indices[&id("b")]].id(), "b");
+
+    let edge = converted.find_edge(indices[&id("a")], indices[&id("c")]).unwrap();
+    assert_eq!(converted[edge].weight(), Some(2.0));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+#[cfg(feature = "petgraph")]
+fn from_petgraph() -> Result<(), DotGraphError> {
+    let mut built = petgraph::graph::DiGraph::<&str, f64>::new();
+    let a = built.add_node("a");
+    let b = built.add_node("b");
+    let c = built.add_node("c");
+    built.add_edge(a, b, 1.0);
+    built.add_edge(b, c, 2.0);
+    let id = |id: &str| String::from(id);
+
+    let node = |_, name: &&str| Node::new(id(name), std::collections::HashSet::new());
+    let edge = |weight: &f64| {
+        std::collections::HashSet::from([Attr::new(id("weight"), weight.to_string(), false)])
+    };
+    let cluster = |node: &Node| (node.id() != "c").then(|| id("cluster_0"));
+    let graph = Graph::from_petgraph_with_clusters(&built, node, edge, cluster)?;
+
+    assert_eq!(graph.nodes().len(), 3);
+    assert_eq!(graph.edges().len(), 2);
+    let mut nodes = graph.collect_nodes(&id("cluster_0"))?;
+    nodes.sort_unstable();
+    assert_eq!(nodes, vec!["a", "b"]);
+    let edges = graph.collect_edges(&id("cluster_0"))?;
+    assert_eq!(edges.len(), 1);
+
+    let same = |_, _: &&str| Node::new(id("x"), std::collections::HashSet::new());
+    assert!(Graph::from_petgraph(&built, same, edge).is_err());
+
+    Ok(())
+}